@@ -144,6 +144,23 @@ fn bench_select_scaling(c: &mut Criterion) {
     group.finish();
 }
 
+fn bench_select_limit(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Select_Limit_Performance");
+    for n in [1000, 100000].iter() {
+        group.bench_with_input(BenchmarkId::from_parameter(n), n, |b, &n| {
+            let db = setup_populated_db(n);
+            b.iter(|| {
+                // unordered, so the scan stops after the 5th match
+                let res = db
+                    .query("SELECT * FROM users WHERE age = 42 LIMIT 5")
+                    .unwrap();
+                black_box(res);
+            });
+        });
+    }
+    group.finish();
+}
+
 fn bench_update_performance(c: &mut Criterion) {
     let mut group = c.benchmark_group("Update_Performance");
 
@@ -209,15 +226,81 @@ fn bench_select_string_interned(c: &mut Criterion) {
     group.finish();
 }
 
+fn bench_where_text_equality(c: &mut Criterion) {
+    let n = 10000;
+    let db = setup_populated_db(n);
+
+    let mut group = c.benchmark_group("Where_Text_Equality");
+    group.bench_function("filter_unique_name_10k", |b| {
+        b.iter(|| {
+            let res = db
+                .query(black_box("SELECT id FROM users WHERE name = 'user5000'"))
+                .unwrap();
+            black_box(res);
+        });
+    });
+    group.bench_function("filter_missing_name_10k", |b| {
+        b.iter(|| {
+            let res = db
+                .query(black_box("SELECT id FROM users WHERE name = 'nobody'"))
+                .unwrap();
+            black_box(res);
+        });
+    });
+    group.finish();
+}
+
+fn bench_where_is_null(c: &mut Criterion) {
+    let n = 100_000;
+    let mut db = Database::new();
+    db.execute("CREATE TABLE contacts (id INT, email TEXT)")
+        .unwrap();
+    let table = db.get_table_mut("contacts").unwrap();
+    // sparse nulls: one row in a thousand has no email
+    for i in 0..n {
+        let email = if i % 1000 == 0 {
+            Value::Null
+        } else {
+            Value::Text(Arc::from(format!("user{}@example.org", i).as_str()))
+        };
+        table.insert(vec![Value::Int(i as i64), email]).unwrap();
+    }
+
+    let mut group = c.benchmark_group("Where_Is_Null_Sparse");
+    group.bench_function("null_bitmap_100k", |b| {
+        b.iter(|| {
+            let res = db
+                .query(black_box("SELECT id FROM contacts WHERE email IS NULL"))
+                .unwrap();
+            black_box(res);
+        });
+    });
+    // the same test below an AND is evaluated row by row
+    group.bench_function("row_scan_100k", |b| {
+        b.iter(|| {
+            let res = db
+                .query(black_box(
+                    "SELECT id FROM contacts WHERE email IS NULL AND TRUE",
+                ))
+                .unwrap();
+            black_box(res);
+        });
+    });
+    group.finish();
+}
+
 criterion_group!(
     benches,
     bench_delete_logical,
     bench_vacuum_scaling,
     bench_auto_vacuum_overhead,
     bench_select_scaling,
+    bench_select_limit,
     bench_update_performance,
     bench_insert_sql,
     bench_select_string_interned,
     bench_string_update_performance,
+    bench_where_text_equality,
+    bench_where_is_null,
 );
 criterion_main!(benches);
@@ -88,9 +88,12 @@ fn print_table(columns: &[Cow<'_, str>], rows: &[Vec<Value>]) {
         for value in row {
             let display = match value {
                 Value::Int(i) => i.to_string(),
+                Value::UInt(u) => u.to_string(),
                 Value::Float(f) => format!("{:.2}", f),
                 Value::Text(s) => s.to_string(),
                 Value::Bool(b) => b.to_string().to_uppercase(),
+                Value::Timestamp(t) => format!("@{}us", t),
+                Value::Blob(b) => format!("<{} bytes>", b.len()),
                 Value::Null => "NULL".to_string(),
             };
             print!("{:<15} ", display);
@@ -1,8 +1,8 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, fmt};
 
 use allocative::Allocative;
 
-use crate::{ColumnDef, Value};
+use crate::{ColumnDef, Value, value::to_hex};
 
 /// Represents the top-level SQL statements supported by the database.
 /// This enum is the entry point of the Abstract Syntax Tree (AST).
@@ -10,6 +10,12 @@ use crate::{ColumnDef, Value};
 pub enum Statement<'a> {
     /// An instruction to create a new table schema.
     CreateTable(CreateTable<'a>),
+    /// An instruction to create a table from the result of a query.
+    CreateTableAs(CreateTableAs<'a>),
+    /// An instruction to remove a table and all of its rows.
+    DropTable(DropTable<'a>),
+    /// An instruction to change the columns of an existing table.
+    AlterTable(AlterTable<'a>),
     /// An instruction to insert new rows into an existing table.
     InsertInto(InsertInto<'a>),
     /// An instruction to query and retrieve data from a table.
@@ -22,12 +28,19 @@ pub enum Statement<'a> {
     /// If no table is given, it will vacuum all tables.
     #[allocative(skip)]
     Vacuum(Option<&'a str>),
+    /// An instruction to remove every row of a table at once (`TRUNCATE TABLE t`).
+    #[allocative(skip)]
+    Truncate(&'a str),
     ///Start of an explicit transaction
     Begin,
     ///Validate the current transaction
     Commit,
     /// Cancel the current transaction and restore the old state
     Rollback,
+    /// Change a session setting of the database (`SET float_epsilon = 0.001`).
+    Set(SetVariable<'a>),
+    /// Describe how a query would run, without running it (`EXPLAIN SELECT ...`).
+    Explain(Select<'a>),
 }
 
 /// Data structure representing a `CREATE TABLE` SQL statement.
@@ -41,6 +54,56 @@ pub struct CreateTable<'a> {
     pub columns: Vec<ColumnDef>,
 }
 
+/// Data structure representing a `DROP TABLE [IF EXISTS]` SQL statement.
+#[derive(Debug, PartialEq, Allocative)]
+pub struct DropTable<'a> {
+    /// The name of the table to remove.
+    #[allocative(skip)]
+    pub name: &'a str,
+    /// With `IF EXISTS`, dropping a missing table does nothing instead of failing.
+    pub if_exists: bool,
+}
+
+/// Data structure representing an `ALTER TABLE` SQL statement.
+#[derive(Debug, PartialEq, Allocative)]
+pub struct AlterTable<'a> {
+    /// The name of the table to change.
+    #[allocative(skip)]
+    pub table: &'a str,
+    /// The change to apply.
+    pub action: AlterAction<'a>,
+}
+
+/// A change made by an `ALTER TABLE` statement.
+#[derive(Debug, PartialEq, Allocative)]
+pub enum AlterAction<'a> {
+    /// `DROP [COLUMN] name`: removes the column and its data.
+    #[allocative(skip)]
+    DropColumn(&'a str),
+}
+
+/// Data structure representing a `SET name = value` SQL statement.
+/// The name is matched case-insensitively when the statement runs.
+#[derive(Debug, PartialEq, Allocative)]
+pub struct SetVariable<'a> {
+    /// The name of the setting to change.
+    #[allocative(skip)]
+    pub name: &'a str,
+    /// The new value of the setting.
+    pub value: Value,
+}
+
+/// Data structure representing a `CREATE TABLE ... AS SELECT` SQL statement.
+/// The schema is inferred from the query's result columns.
+#[derive(Debug, PartialEq, Allocative)]
+pub struct CreateTableAs<'a> {
+    /// The unique name of the table to be created.
+    #[allocative(skip)]
+    pub name: &'a str,
+    /// The query whose rows populate the new table.
+    pub query: Select<'a>,
+}
+
 /// Data structure representing an `INSERT INTO` SQL statement.
 /// Used to populate a table with new data.
 #[derive(Debug, PartialEq, Allocative)]
@@ -52,14 +115,16 @@ pub struct InsertInto<'a> {
     /// If `None`, values are expected to match the table's schema order.
     #[allocative(skip)]
     pub columns: Option<Vec<&'a str>>,
-    /// The actual data values to be stored in the new row.
-    pub values: Vec<Value>,
+    /// The rows to store, one per parenthesized tuple of `VALUES`.
+    pub values: Vec<Vec<Value>>,
+    /// Optional `RETURNING` projection, evaluated on the inserted row.
+    pub returning: Option<ColumnsSelect<'a>>,
 }
 
 /// Defines which columns should be retrieved in a `SELECT` query.
-#[derive(Debug, PartialEq, Allocative)]
+#[derive(Debug, PartialEq, Clone, Allocative)]
 pub enum ColumnsSelect<'a> {
-    /// Represents the `*` wildcard, indicating all columns should be returned.
+    /// Represents a lone `*` wildcard, indicating all columns should be returned.
     Star,
     /// Represents a specific list of Items to be returned.
     Items(Vec<SelectItem<'a>>),
@@ -67,25 +132,92 @@ pub enum ColumnsSelect<'a> {
 
 /// Data structure representing a `SELECT` SQL statement.
 /// Used to define what data to fetch and from which source.
-#[derive(Debug, PartialEq, Allocative)]
+#[derive(Debug, PartialEq, Clone, Allocative)]
 pub struct Select<'a> {
+    /// `SELECT DISTINCT`: duplicate result rows are removed.
+    pub distinct: bool,
     /// The projection of the query (which columns to include in the result).
     pub columns: ColumnsSelect<'a>,
     /// The name of the table to query data from.
     #[allocative(skip)]
     pub table: &'a str,
 
+    /// Optional alias of the table (`FROM users u` or `FROM users AS u`). When set, the
+    /// columns of the table are qualified by the alias instead of the table name.
+    #[allocative(skip)]
+    pub alias: Option<&'a str>,
+
+    /// Optional `TABLESAMPLE`, keeping a random share of the rows.
+    pub sample: Option<TableSample>,
+
+    /// Optional `JOIN` with a second table.
+    pub join: Option<Join<'a>>,
+
     /// Where clause optionnal
     pub where_clause: Option<Expr<'a>>,
 
     // Limit the number or rows to be shown optionnal
     pub limit: Option<usize>,
 
+    /// Number of result rows to skip before `limit` applies, optional.
+    pub offset: Option<usize>,
+
     // Order by clause optionnal
     pub order_by: Option<Vec<OrderByClause<'a>>>,
 
     #[allocative(skip)]
     pub group_by: Option<Vec<&'a str>>,
+
+    /// `true` for `GROUP BY ROLLUP(a, b)`: besides the `(a, b)` groups, the query also
+    /// returns a subtotal row per `a` and a grand total, `NULL` standing for the columns
+    /// rolled up.
+    pub rollup: bool,
+
+    /// Optional `HAVING` clause, filtering the groups once aggregates are computed.
+    pub having: Option<Expr<'a>>,
+}
+
+/// A `TABLESAMPLE (p PERCENT) [REPEATABLE (seed)]` clause.
+///
+/// Each row coming out of the `FROM` clause is kept with probability `percent / 100`,
+/// before the `WHERE` clause applies.
+#[derive(Debug, PartialEq, Clone, Copy, Allocative)]
+pub struct TableSample {
+    /// The share of rows to keep, from 0 to 100.
+    pub percent: f64,
+    /// Seed of the random generator, so the same sample can be drawn again. A random
+    /// seed is used when `None`.
+    pub seed: Option<u64>,
+}
+
+/// How a [Join] treats the rows of the left table.
+#[derive(Debug, PartialEq, Clone, Copy, Allocative)]
+pub enum JoinKind {
+    /// `[INNER] JOIN`: only pairs of matching rows are kept.
+    Inner,
+    /// `LEFT [OUTER] JOIN`: a left row without any match is kept once, with `NULL` for
+    /// every column of the right table.
+    Left,
+}
+
+/// A `JOIN table ON left_column = right_column` clause.
+///
+/// Columns of a joined query can be qualified (`users.id`); unqualified names must
+/// belong to a single table.
+#[derive(Debug, PartialEq, Clone, Allocative)]
+pub struct Join<'a> {
+    /// Whether unmatched left rows are kept.
+    pub kind: JoinKind,
+    /// The name of the joined (right) table.
+    #[allocative(skip)]
+    pub table: &'a str,
+    /// Optional alias of the joined table (`JOIN orders o`), replacing its name as the
+    /// qualifier of its columns.
+    #[allocative(skip)]
+    pub alias: Option<&'a str>,
+    /// The two columns of the equi-join condition, one from each table, in any order.
+    #[allocative(skip)]
+    pub on: (&'a str, &'a str),
 }
 
 #[derive(Debug, PartialEq, Clone, Allocative)]
@@ -107,15 +239,163 @@ pub enum Aggregate<'a> {
     /// COUNT(column)
     #[allocative(skip)]
     Count(&'a str),
+    /// APPROX_COUNT_DISTINCT(column), estimated with a [crate::sketch::HyperLogLog]
+    #[allocative(skip)]
+    ApproxCountDistinct(&'a str),
+}
+
+/// Renders the aggregate call as SQL (`SUM(price)`, `COUNT(*)`).
+impl fmt::Display for Aggregate<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Aggregate::CountStar => f.write_str("COUNT(*)"),
+            Aggregate::Count(col) => write!(f, "COUNT({})", col),
+            Aggregate::Avg(col) => write!(f, "AVG({})", col),
+            Aggregate::Min(col) => write!(f, "MIN({})", col),
+            Aggregate::Max(col) => write!(f, "MAX({})", col),
+            Aggregate::Sum(col) => write!(f, "SUM({})", col),
+            Aggregate::ApproxCountDistinct(col) => write!(f, "APPROX_COUNT_DISTINCT({})", col),
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Clone, Allocative)]
 pub enum SelectItem<'a> {
+    /// The `*` wildcard mixed with other items (`SELECT *, id`), expanded to every
+    /// schema column at its position.
+    Star,
     /// A simple column
     #[allocative(skip)]
     Column(&'a str),
-    /// Aggregation: `SUM(price)` or `COUNT(*)`
-    Aggregate(Aggregate<'a>),
+    /// Aggregation: `SUM(price)`, `COUNT(*)` or `COUNT(*) FILTER (WHERE active)`
+    Aggregate {
+        /// The aggregate function and its argument.
+        function: Aggregate<'a>,
+        /// Optional `FILTER (WHERE ...)`: only the rows of the group matching it are
+        /// aggregated, on top of the query's own `WHERE`.
+        filter: Option<Expr<'a>>,
+        /// The name given with `AS`, used as the result column name and usable in `ORDER BY`.
+        #[allocative(skip)]
+        alias: Option<&'a str>,
+    },
+    /// A scalar subquery, run once per result row: `(SELECT COUNT(*) FROM orders o
+    /// WHERE o.user_id = u.id) AS order_count`. Its `WHERE` may compare its columns with
+    /// the columns of the outer row (a correlated subquery).
+    Subquery {
+        /// The query producing the value, one column and at most one row.
+        query: Box<Select<'a>>,
+        /// The name given with `AS`, used as the result column name.
+        #[allocative(skip)]
+        alias: Option<&'a str>,
+    },
+    /// A computed expression, optionally named: `salary * 2 AS double_salary`
+    Computed {
+        /// The expression evaluated for every row.
+        expr: ScalarExpr<'a>,
+        /// The name given with `AS`, used as the result column name and usable in `ORDER BY`.
+        #[allocative(skip)]
+        alias: Option<&'a str>,
+    },
+}
+
+/// An arithmetic operator usable in computed projections.
+#[derive(Debug, PartialEq, Clone, Copy, Allocative)]
+pub enum ArithmeticOp {
+    /// Addition (`+`)
+    Add,
+    /// Subtraction (`-`)
+    Sub,
+    /// Multiplication (`*`)
+    Mul,
+    /// Division (`/`)
+    Div,
+}
+
+/// A scalar expression producing one [Value] per row (e.g. `price * 2 + 1`).
+#[derive(Debug, PartialEq, Clone, Allocative)]
+pub enum ScalarExpr<'a> {
+    /// The value of a column in the current row.
+    #[allocative(skip)]
+    Column(&'a str),
+    /// A constant value.
+    Literal(Value),
+    /// An arithmetic operation between two sub-expressions.
+    Binary {
+        left: Box<ScalarExpr<'a>>,
+        op: ArithmeticOp,
+        right: Box<ScalarExpr<'a>>,
+    },
+}
+
+impl<'a> ScalarExpr<'a> {
+    /// Returns every column referenced by the expression.
+    pub fn columns(&self) -> Vec<&'a str> {
+        match self {
+            ScalarExpr::Column(name) => vec![*name],
+            ScalarExpr::Literal(_) => vec![],
+            ScalarExpr::Binary { left, right, .. } => {
+                let mut cols = left.columns();
+                cols.extend(right.columns());
+                cols
+            }
+        }
+    }
+}
+
+impl fmt::Display for ArithmeticOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let symbol = match self {
+            ArithmeticOp::Add => "+",
+            ArithmeticOp::Sub => "-",
+            ArithmeticOp::Mul => "*",
+            ArithmeticOp::Div => "/",
+        };
+        f.write_str(symbol)
+    }
+}
+
+/// Renders the expression back as SQL, used as the result column name when no alias is given.
+impl fmt::Display for ScalarExpr<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScalarExpr::Column(name) => f.write_str(name),
+            ScalarExpr::Literal(value) => fmt_literal(value, f),
+            ScalarExpr::Binary { left, op, right } => {
+                fmt_operand(left, f)?;
+                write!(f, " {} ", op)?;
+                fmt_operand(right, f)
+            }
+        }
+    }
+}
+
+/// Writes an operand of a binary operation, parenthesizing nested operations so the
+/// rendering keeps the evaluation order.
+fn fmt_operand(expr: &ScalarExpr, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    if matches!(expr, ScalarExpr::Binary { .. }) {
+        write!(f, "({})", expr)
+    } else {
+        write!(f, "{}", expr)
+    }
+}
+
+/// Writes a [Value] as a SQL literal (`42`, `'it''s'`, `TRUE`, `NULL`...).
+fn fmt_literal(value: &Value, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match value {
+        Value::Null => f.write_str("NULL"),
+        Value::Int(v) => write!(f, "{}", v),
+        Value::UInt(v) => write!(f, "{}", v),
+        // `{}` never uses an exponent, which the tokenizer would not read back, and `.0`
+        // keeps a whole float from reading back as an integer
+        Value::Float(v) if v.is_finite() && v.fract() == 0.0 => write!(f, "{}.0", v),
+        Value::Float(v) => write!(f, "{}", v),
+        Value::Text(s) => write!(f, "'{}'", s.replace('\'', "''")),
+        Value::Bool(true) => f.write_str("TRUE"),
+        Value::Bool(false) => f.write_str("FALSE"),
+        // there is no timestamp literal syntax, the raw microseconds are the closest form
+        Value::Timestamp(t) => write!(f, "{}", t),
+        Value::Blob(b) => write!(f, "X'{}'", to_hex(b)),
+    }
 }
 
 /// Data structure representing a `DELETE` SQL statement.
@@ -128,17 +408,48 @@ pub struct Delete<'a> {
 
     /// Where clause
     pub where_clause: Expr<'a>,
+
+    /// Optional `LIMIT`: at most this many matching rows are deleted, the first ones
+    /// in storage order.
+    pub limit: Option<usize>,
+
+    /// Optional `RETURNING` projection, evaluated on the deleted rows.
+    pub returning: Option<ColumnsSelect<'a>>,
 }
 
-/// Represents a boolean comparison operation between a column and a literal value.
-#[derive(Debug, PartialEq, Allocative)]
+/// Represents a boolean comparison operation between a column and a literal value or
+/// another column.
+#[derive(Debug, PartialEq, Clone, Allocative)]
 pub enum ComparisonOp {
     /// Greater than (`>`)
     Gt,
     /// Lower than (`<`)
     Lt,
+    /// Greater than or equal (`>=`)
+    Gte,
+    /// Lower than or equal (`<=`)
+    Lte,
     /// Equal (`=`)
     Eq,
+    /// Not equal (`!=` or `<>`)
+    Neq,
+    /// NULL-safe equal (`<=>`): `NULL <=> NULL` is true, `NULL <=> x` is false.
+    NullSafeEq,
+}
+
+impl fmt::Display for ComparisonOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let symbol = match self {
+            ComparisonOp::Gt => ">",
+            ComparisonOp::Lt => "<",
+            ComparisonOp::Gte => ">=",
+            ComparisonOp::Lte => "<=",
+            ComparisonOp::Eq => "=",
+            ComparisonOp::Neq => "!=",
+            ComparisonOp::NullSafeEq => "<=>",
+        };
+        f.write_str(symbol)
+    }
 }
 
 /// Represents a single sorting criterion in an `ORDER BY` clause.
@@ -147,15 +458,41 @@ pub enum ComparisonOp {
 /// ```sql
 /// ORDER BY age ASC        -- Single clause
 /// ORDER BY age DESC, name ASC  -- Multiple clauses (age first, then name)
+/// ORDER BY price * qty DESC    -- Computed sort key
+/// ORDER BY age DESC NULLS FIRST  -- Explicit NULL placement
 /// ```
 #[derive(Debug, PartialEq, Clone, Allocative)]
 pub struct OrderByClause<'a> {
-    /// The name of the column to sort by.
-    #[allocative(skip)]
-    pub column: &'a str,
+    /// The sort key: a column, a projection alias, or an expression evaluated per row.
+    pub expr: ScalarExpr<'a>,
 
     /// The sort direction (ascending or descending).
     pub direction: SortDirection,
+
+    /// Where `NULL` keys go (`NULLS FIRST` / `NULLS LAST`), `None` if not specified.
+    pub nulls: Option<NullsOrder>,
+}
+
+impl OrderByClause<'_> {
+    /// Whether `NULL` keys sort before every other value for this clause.
+    ///
+    /// Without an explicit `NULLS FIRST`/`NULLS LAST`, `NULL` is the smallest value: it
+    /// comes first in ascending order and last in descending order.
+    pub fn nulls_first(&self) -> bool {
+        match self.nulls {
+            Some(nulls) => nulls == NullsOrder::First,
+            None => self.direction == SortDirection::Asc,
+        }
+    }
+}
+
+/// Placement of `NULL` keys in an `ORDER BY` clause, whatever the sort direction.
+#[derive(Debug, PartialEq, Clone, Copy, Allocative)]
+pub enum NullsOrder {
+    /// `NULLS FIRST`: `NULL` keys come before every other value.
+    First,
+    /// `NULLS LAST`: `NULL` keys come after every other value.
+    Last,
 }
 
 /// Defines the direction of sorting for an `ORDER BY` clause.
@@ -170,7 +507,7 @@ pub enum SortDirection {
 }
 
 /// A recursive expression tree used in `WHERE` clauses to filter rows.
-#[derive(Debug, PartialEq, Allocative)]
+#[derive(Debug, PartialEq, Clone, Allocative)]
 pub enum Expr<'a> {
     /// A leaf node: compares a specific column to a constant value.
     Comparison {
@@ -182,6 +519,30 @@ pub enum Expr<'a> {
         /// The constant value to compare against.
         value: Value,
     },
+    /// Compares two columns of the same row (`o.user_id = u.id`).
+    ///
+    /// In a subquery of the projection, one of them may be a column of the outer query:
+    /// it is replaced by its value in the current outer row before the subquery runs.
+    ColumnComparison {
+        /// The column on the left of the operator.
+        #[allocative(skip)]
+        left: &'a str,
+        /// The operator to apply.
+        op: ComparisonOp,
+        /// The column on the right of the operator.
+        #[allocative(skip)]
+        right: &'a str,
+    },
+    /// Compares the result of an aggregate to a constant value.
+    /// Only meaningful in a `HAVING` clause, where it is evaluated once per group.
+    AggregateComparison {
+        /// The aggregate computed over the group rows.
+        aggregate: Aggregate<'a>,
+        /// The operator to apply.
+        op: ComparisonOp,
+        /// The constant value to compare against.
+        value: Value,
+    },
     /// A logical AND operation. Both sides must be true.
     And {
         left: Box<Expr<'a>>,
@@ -192,6 +553,175 @@ pub enum Expr<'a> {
         left: Box<Expr<'a>>,
         right: Box<Expr<'a>>,
     },
+    /// A logical NOT operation. As in SQL, the negation of an unknown comparison (one
+    /// reading a `NULL`) is still unknown, so `NOT age > 18` skips rows where `age` is
+    /// `NULL`.
+    Not(Box<Expr<'a>>),
+    /// Compares a column to the result of an uncorrelated scalar subquery
+    /// (`total > (SELECT AVG(total) FROM orders)`).
+    ///
+    /// The subquery is run once before the scan and replaced by an [Expr::Comparison]
+    /// against its single value.
+    Subquery {
+        /// The name of the column to evaluate.
+        #[allocative(skip)]
+        column: &'a str,
+        /// The operator to apply.
+        op: ComparisonOp,
+        /// The query producing the value to compare against.
+        query: Box<Select<'a>>,
+    },
+    /// An inclusive range test (`x BETWEEN low AND high`).
+    /// The bounds are evaluated on the same row, so they may reference other columns.
+    Between {
+        /// The name of the column to test.
+        #[allocative(skip)]
+        column: &'a str,
+        /// The lower bound, included.
+        low: ScalarExpr<'a>,
+        /// The upper bound, included.
+        high: ScalarExpr<'a>,
+    },
+    /// A null test (`x IS NULL` / `x IS NOT NULL`). On a table scan it is answered from
+    /// the null bitmap of the column, without pivoting the other rows.
+    IsNull {
+        /// The name of the column to test.
+        #[allocative(skip)]
+        column: &'a str,
+        /// True for `IS NOT NULL`.
+        negated: bool,
+    },
+    /// A list membership test (`id IN (1, 2, 3)` / `id NOT IN (1, 2, 3)`), using the
+    /// equality of `=`. As in SQL, a `NULL` in the list makes `NOT IN` unknown for every
+    /// value that is not found.
+    InList {
+        /// The name of the column to test.
+        #[allocative(skip)]
+        column: &'a str,
+        /// The values to look for, at least one.
+        values: Vec<Value>,
+        /// True for `NOT IN`.
+        negated: bool,
+    },
+    /// A pattern match on a text column (`name LIKE 'Al%'` / `name NOT LIKE 'Al%'`), where
+    /// `%` stands for any sequence of characters and `_` for exactly one.
+    Like {
+        /// The name of the column to test.
+        #[allocative(skip)]
+        column: &'a str,
+        /// The pattern, without its quotes.
+        pattern: String,
+        /// True for `NOT LIKE`.
+        negated: bool,
+    },
+    /// A standalone boolean literal (`WHERE TRUE` / `WHERE FALSE`).
+    /// It matches every row or none without looking at the row values.
+    Literal(bool),
+}
+
+impl<'a> Expr<'a> {
+    /// Returns every column compared directly by the expression (aggregate arguments
+    /// are not included).
+    pub fn columns(&self) -> Vec<&'a str> {
+        match self {
+            Expr::Comparison { column, .. }
+            | Expr::Subquery { column, .. }
+            | Expr::IsNull { column, .. }
+            | Expr::InList { column, .. }
+            | Expr::Like { column, .. } => vec![*column],
+            Expr::ColumnComparison { left, right, .. } => vec![*left, *right],
+            Expr::Between { column, low, high } => {
+                let mut cols = vec![*column];
+                cols.extend(low.columns());
+                cols.extend(high.columns());
+                cols
+            }
+            Expr::And { left, right } | Expr::Or { left, right } => {
+                let mut cols = left.columns();
+                cols.extend(right.columns());
+                cols
+            }
+            Expr::Not(inner) => inner.columns(),
+            Expr::AggregateComparison { .. } | Expr::Literal(_) => vec![],
+        }
+    }
+}
+
+/// Renders the condition back as SQL, used in the result column name of a filtered
+/// aggregate. A subquery is abbreviated to `(SELECT ...)`.
+impl fmt::Display for Expr<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expr::Comparison { column, op, value } => {
+                write!(f, "{} {} ", column, op)?;
+                fmt_literal(value, f)
+            }
+            Expr::AggregateComparison {
+                aggregate,
+                op,
+                value,
+            } => {
+                write!(f, "{} {} ", aggregate, op)?;
+                fmt_literal(value, f)
+            }
+            // OR binds looser than AND, so it needs parentheses below an AND
+            Expr::And { left, right } => {
+                for (i, side) in [left, right].into_iter().enumerate() {
+                    if i == 1 {
+                        f.write_str(" AND ")?;
+                    }
+                    if matches!(**side, Expr::Or { .. }) {
+                        write!(f, "({})", side)?;
+                    } else {
+                        write!(f, "{}", side)?;
+                    }
+                }
+                Ok(())
+            }
+            Expr::Or { left, right } => write!(f, "{} OR {}", left, right),
+            Expr::Not(inner) => match **inner {
+                Expr::And { .. } | Expr::Or { .. } => write!(f, "NOT ({})", inner),
+                _ => write!(f, "NOT {}", inner),
+            },
+            Expr::ColumnComparison { left, op, right } => write!(f, "{} {} {}", left, op, right),
+            Expr::Subquery { column, op, .. } => write!(f, "{} {} (SELECT ...)", column, op),
+            Expr::Between { column, low, high } => {
+                write!(f, "{} BETWEEN {} AND {}", column, low, high)
+            }
+            Expr::IsNull { column, negated } => {
+                write!(
+                    f,
+                    "{} IS {}NULL",
+                    column,
+                    if *negated { "NOT " } else { "" }
+                )
+            }
+            Expr::InList {
+                column,
+                values,
+                negated,
+            } => {
+                write!(f, "{} {}IN (", column, if *negated { "NOT " } else { "" })?;
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    fmt_literal(value, f)?;
+                }
+                f.write_str(")")
+            }
+            Expr::Like {
+                column,
+                pattern,
+                negated,
+            } => {
+                write!(f, "{} {}LIKE ", column, if *negated { "NOT " } else { "" })?;
+                fmt_literal(&Value::Text(pattern.as_str().into()), f)
+            }
+            Expr::Literal(true) => f.write_str("TRUE"),
+            Expr::Literal(false) => f.write_str("FALSE"),
+        }
+    }
 }
 
 /// Data structure representing an `UPDATE` SQL statement.
@@ -203,11 +733,15 @@ pub struct Update<'a> {
     pub table: &'a str,
 
     /// List of column assignments (column_name, new_value).
-    /// The order doesn't matter as each assignment is independent.
+    /// The order doesn't matter as each assignment is independent: an expression
+    /// (`hits = hits + 1`) always reads the values of the row before the update.
     #[allocative(skip)]
-    pub assignments: HashMap<&'a str, Value>,
+    pub assignments: HashMap<&'a str, ScalarExpr<'a>>,
 
     /// WHERE clause to filter which rows to update.
     /// All matching rows will have their specified columns updated.
     pub where_clause: Expr<'a>,
+
+    /// Optional `RETURNING` projection, evaluated on the updated rows.
+    pub returning: Option<ColumnsSelect<'a>>,
 }
@@ -1,7 +1,13 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
 use std::mem::take;
+use std::ops::Range;
 use std::sync::Arc;
 
+use crate::ast::ComparisonOp;
 use crate::data_type::DataType;
+use crate::sketch::HyperLogLog;
+use crate::table::ColumnDef;
 use crate::value::Value;
 use allocative::Allocative;
 use bitvec::prelude::*;
@@ -13,6 +19,8 @@ use bitvec::prelude::*;
 pub enum ColumnData {
     /// Vector of 64-bit integers.
     Int(Vec<i64>),
+    /// Vector of 64-bit unsigned integers.
+    UInt(Vec<u64>),
     /// Vector of 64-bit floats.
     Float(Vec<f64>),
     /// Vector of thread-safe atomic reference-counted strings.
@@ -20,6 +28,19 @@ pub enum ColumnData {
     /// Compact bit-vector for boolean values.
     #[allocative(skip)] // As bitvec is non significant
     Bool(BitVec),
+    /// Vector of timestamps, in microseconds since the Unix epoch.
+    Timestamp(Vec<i64>),
+    /// Vector of reference-counted byte buffers.
+    Bytes(Vec<Arc<[u8]>>),
+    /// Dictionary-encoded strings, used for low-cardinality `Text` columns.
+    ///
+    /// Each row stores a 4-byte code pointing into `dict`, which holds every distinct
+    /// string once. `index` maps a string back to its code so pushes stay O(1).
+    Dict {
+        codes: Vec<u32>,
+        dict: Vec<Arc<str>>,
+        index: HashMap<Arc<str>, u32>,
+    },
 }
 
 /// Represents a column within a table.
@@ -35,6 +56,8 @@ pub struct Column {
     /// A bitmap where a `true` bit indicates that the value at that index is `NULL`.
     #[allocative(skip)]
     pub null_bitmap: BitVec,
+    /// Whether [Column::push] keeps the values sorted, see [Column::new_sorted].
+    sorted: bool,
 }
 
 impl Column {
@@ -46,16 +69,124 @@ impl Column {
             DataType::Float => ColumnData::Float(vec![]),
             DataType::Bool => ColumnData::Bool(bitvec!()),
             DataType::Text => ColumnData::Text(vec![]),
+            DataType::Timestamp => ColumnData::Timestamp(vec![]),
+            DataType::UInt => ColumnData::UInt(vec![]),
+            DataType::Bytes => ColumnData::Bytes(vec![]),
         };
         Self {
             name,
             data_type,
             data,
             null_bitmap: bitvec!(),
+            sorted: false,
+        }
+    }
+
+    /// Creates an empty column that keeps its values in ascending order.
+    ///
+    /// [Column::push] then inserts each value at its sorted position (found by binary
+    /// search) instead of appending it, so [Column::sorted_range] can find a range of
+    /// values without a scan. The tradeoff is the insertion cost: every value after the
+    /// new one is shifted, which makes a push O(n) instead of O(1). It suits data that
+    /// mostly arrives in order, such as a time series.
+    ///
+    /// Values are ordered like [Column::sort_permutation]: `NULL`s first, floats by
+    /// [f64::total_cmp]. Since a push moves the following rows, a sorted column cannot
+    /// be part of a [crate::table::Table], whose columns must stay aligned row by row.
+    ///
+    /// As the column serves lookups by value, a sorted `FLOAT` column rejects `NaN` and
+    /// infinite values: `NaN` is not equal to itself, so it could never be found again.
+    /// Unsorted float columns accept them.
+    ///
+    /// # Example
+    /// ```
+    /// # use db::column::Column;
+    /// # use db::data_type::DataType;
+    /// # use db::value::Value;
+    /// let mut col = Column::new_sorted("ts".into(), DataType::Int);
+    /// for v in [30, 10, 20] {
+    ///     col.push(Value::Int(v)).unwrap();
+    /// }
+    /// assert_eq!(col.get(0), Some(Value::Int(10)));
+    /// assert_eq!(col.sorted_range(&Value::Int(15), &Value::Int(30)), Ok(1..3));
+    /// ```
+    pub fn new_sorted(name: String, data_type: DataType) -> Self {
+        Self {
+            sorted: true,
+            ..Self::new(name, data_type)
+        }
+    }
+
+    /// Returns `true` if the column keeps its values sorted, see [Column::new_sorted].
+    pub fn is_sorted(&self) -> bool {
+        self.sorted
+    }
+
+    /// Returns the rows whose values lie between `low` and `high`, both included, in a
+    /// sorted column. The bounds are found by binary search, in O(log n).
+    ///
+    /// # Errors
+    /// Returns an error if the column is not sorted or if a bound does not have the
+    /// column's type.
+    pub fn sorted_range(&self, low: &Value, high: &Value) -> Result<Range<usize>, String> {
+        if !self.sorted {
+            return Err(format!("Column {} is not sorted", self.name));
+        }
+        for bound in [low, high] {
+            if bound.data_type().is_some_and(|t| t != self.data_type) {
+                return Err(format!(
+                    "Value {bound:?} has type {:?} while column data type is {:?}",
+                    bound.data_type(),
+                    self.data_type
+                ));
+            }
+        }
+        let start = self.partition_point(|row| self.cmp_row_value(row, low) == Ordering::Less);
+        let end = self.partition_point(|row| self.cmp_row_value(row, high) != Ordering::Greater);
+        Ok(start..end.max(start))
+    }
+
+    /// Returns the first row for which `pred` is false, assuming `pred` is true for a
+    /// prefix of the rows and false after it.
+    fn partition_point(&self, pred: impl Fn(usize) -> bool) -> usize {
+        let (mut low, mut high) = (0, self.len());
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if pred(mid) {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
         }
+        low
     }
 
-    /// Appends a new value to the end of the column.
+    /// Compares the value stored at `row` with `value`, with the ordering of
+    /// [Column::cmp_rows]. `value` must be `NULL` or of the column's type.
+    fn cmp_row_value(&self, row: usize, value: &Value) -> Ordering {
+        match (self.null_bitmap[row], value.is_null()) {
+            (true, true) => return Ordering::Equal,
+            (true, false) => return Ordering::Less,
+            (false, true) => return Ordering::Greater,
+            (false, false) => {}
+        }
+        match (&self.data, value) {
+            (ColumnData::Int(col), Value::Int(v))
+            | (ColumnData::Timestamp(col), Value::Timestamp(v)) => col[row].cmp(v),
+            (ColumnData::UInt(col), Value::UInt(v)) => col[row].cmp(v),
+            (ColumnData::Float(col), Value::Float(v)) => col[row].total_cmp(v),
+            (ColumnData::Text(col), Value::Text(v)) => col[row].cmp(v),
+            (ColumnData::Bytes(col), Value::Blob(v)) => col[row].cmp(v),
+            (ColumnData::Bool(col), Value::Bool(v)) => col[row].cmp(v),
+            (ColumnData::Dict { codes, dict, .. }, Value::Text(v)) => {
+                dict[codes[row] as usize].cmp(v)
+            }
+            _ => Ordering::Equal,
+        }
+    }
+
+    /// Appends a new value to the end of the column, or inserts it at its sorted position
+    /// in a column created with [Column::new_sorted].
     ///
     /// # Errors
     /// Returns an error if the value's type does not match the column's data type.
@@ -78,14 +209,52 @@ impl Column {
     /// assert!(col.get(1).unwrap().is_null());
     /// ```
     pub fn push(&mut self, value: Value) -> Result<(), String> {
+        if self.sorted {
+            if let Value::Float(v) = value
+                && !v.is_finite()
+            {
+                return Err(format!(
+                    "Column {} is sorted and cannot store the non-finite float {v}",
+                    self.name
+                ));
+            }
+            // after the last equal value, so equal values keep their insertion order
+            let position =
+                self.partition_point(|row| self.cmp_row_value(row, &value) != Ordering::Greater);
+            self.append(value)?;
+            self.move_last_to(position);
+            return Ok(());
+        }
+        self.append(value)
+    }
+
+    /// Moves the last row to `position`, shifting the following rows by one.
+    fn move_last_to(&mut self, position: usize) {
+        match &mut self.data {
+            ColumnData::Int(v) | ColumnData::Timestamp(v) => v[position..].rotate_right(1),
+            ColumnData::UInt(v) => v[position..].rotate_right(1),
+            ColumnData::Float(v) => v[position..].rotate_right(1),
+            ColumnData::Text(v) => v[position..].rotate_right(1),
+            ColumnData::Bytes(v) => v[position..].rotate_right(1),
+            ColumnData::Bool(v) => v[position..].rotate_right(1),
+            ColumnData::Dict { codes, .. } => codes[position..].rotate_right(1),
+        }
+        self.null_bitmap[position..].rotate_right(1);
+    }
+
+    /// Appends a value to the end of the column, see [Column::push].
+    fn append(&mut self, value: Value) -> Result<(), String> {
         if value.is_null() {
             self.null_bitmap.push(true);
             // Add default value to keep alignment between the data vector and the bitmap
             match &mut self.data {
-                ColumnData::Int(v) => v.push(0),
+                ColumnData::Int(v) | ColumnData::Timestamp(v) => v.push(0),
+                ColumnData::UInt(v) => v.push(0),
                 ColumnData::Float(v) => v.push(0.0),
                 ColumnData::Text(v) => v.push(String::new().into()),
+                ColumnData::Bytes(v) => v.push(Arc::from([])),
                 ColumnData::Bool(v) => v.push(false),
+                ColumnData::Dict { codes, .. } => codes.push(0),
             }
 
             return Ok(());
@@ -103,9 +272,16 @@ impl Column {
 
         match (&mut self.data, value) {
             (ColumnData::Int(col), Value::Int(v)) => col.push(v),
+            (ColumnData::UInt(col), Value::UInt(v)) => col.push(v),
+            (ColumnData::Timestamp(col), Value::Timestamp(v)) => col.push(v),
             (ColumnData::Float(col), Value::Float(v)) => col.push(v),
             (ColumnData::Text(col), Value::Text(v)) => col.push(v),
+            (ColumnData::Bytes(col), Value::Blob(v)) => col.push(v),
             (ColumnData::Bool(col), Value::Bool(v)) => col.push(v),
+            (ColumnData::Dict { codes, dict, index }, Value::Text(v)) => {
+                let code = dict_code(dict, index, &v)?;
+                codes.push(code);
+            }
 
             _ => {
                 return Err("Internal error: type mismatch".into());
@@ -125,6 +301,87 @@ impl Column {
         self.len() == 0
     }
 
+    /// Estimates the number of distinct non-null values in the column.
+    ///
+    /// Uses a fixed-size [HyperLogLog] sketch (4 KiB) instead of a set of every value,
+    /// so memory stays constant on huge columns. The estimate has a relative standard
+    /// error of about 1.6%, and is nearly exact below ~10 000 distinct values. Every
+    /// stored row counts, including rows only marked as deleted in their table.
+    pub fn approx_distinct(&self) -> u64 {
+        let mut sketch = HyperLogLog::new();
+        for row_idx in 0..self.len() {
+            if !self.null_bitmap[row_idx]
+                && let Some(value) = self.get(row_idx)
+            {
+                sketch.insert(&value);
+            }
+        }
+        sketch.estimate()
+    }
+
+    /// Checks that this column still matches its definition and holds `row_count` rows.
+    ///
+    /// Catches drift between the schema and the physical storage, e.g. after a table was
+    /// rebuilt from untrusted data: the name and logical type must match `def`, the storage
+    /// variant must match the type, and both the data vector and the null bitmap must be
+    /// exactly `row_count` long.
+    pub fn validate_against(&self, def: &ColumnDef, row_count: usize) -> Result<(), String> {
+        if self.name != def.name {
+            return Err(format!(
+                "Column '{}' does not match schema column '{}'",
+                self.name, def.name
+            ));
+        }
+        if self.data_type != def.data_type {
+            return Err(format!(
+                "Column '{}' has type {:?} while schema declares {:?}",
+                self.name, self.data_type, def.data_type
+            ));
+        }
+        if self.sorted {
+            return Err(format!(
+                "Column '{}' is sorted and cannot stay aligned with the other columns",
+                self.name
+            ));
+        }
+        let data_len = match (&self.data, self.data_type) {
+            (ColumnData::Int(v), DataType::Int)
+            | (ColumnData::Timestamp(v), DataType::Timestamp) => v.len(),
+            (ColumnData::UInt(v), DataType::UInt) => v.len(),
+            (ColumnData::Float(v), DataType::Float) => v.len(),
+            (ColumnData::Text(v), DataType::Text) => v.len(),
+            (ColumnData::Bytes(v), DataType::Bytes) => v.len(),
+            (ColumnData::Bool(v), DataType::Bool) => v.len(),
+            (ColumnData::Dict { codes, .. }, DataType::Text) => codes.len(),
+            _ => {
+                return Err(format!(
+                    "Column '{}' storage does not match its type {:?}",
+                    self.name, self.data_type
+                ));
+            }
+        };
+        if data_len != row_count || self.null_bitmap.len() != row_count {
+            return Err(format!(
+                "Column '{}' holds {data_len} values and {} null flags, expected {row_count} rows",
+                self.name,
+                self.null_bitmap.len()
+            ));
+        }
+        if let ColumnData::Dict { codes, dict, .. } = &self.data {
+            let dangling = codes
+                .iter()
+                .zip(self.null_bitmap.iter())
+                .any(|(&code, is_null)| !*is_null && code as usize >= dict.len());
+            if dangling {
+                return Err(format!(
+                    "Column '{}' has a dictionary code out of range",
+                    self.name
+                ));
+            }
+        }
+        Ok(())
+    }
+
     /// Retrieves the value at the specified row index.
     ///
     /// Returns `Some(Value)` if the index is valid, or `None` if it is out of bounds.
@@ -138,9 +395,15 @@ impl Column {
         }
         match &self.data {
             ColumnData::Int(col) => Some(Value::Int(col[row_idx])),
+            ColumnData::UInt(col) => Some(Value::UInt(col[row_idx])),
+            ColumnData::Timestamp(col) => Some(Value::Timestamp(col[row_idx])),
             ColumnData::Text(col) => Some(Value::Text(col[row_idx].clone())),
+            ColumnData::Bytes(col) => Some(Value::Blob(col[row_idx].clone())),
             ColumnData::Float(col) => Some(Value::Float(col[row_idx])),
             ColumnData::Bool(col) => Some(Value::Bool(col[row_idx])),
+            ColumnData::Dict { codes, dict, .. } => {
+                Some(Value::Text(dict[codes[row_idx] as usize].clone()))
+            }
         }
     }
 
@@ -169,7 +432,7 @@ impl Column {
             ));
         }
         match &mut self.data {
-            ColumnData::Int(col) => {
+            ColumnData::Int(col) | ColumnData::Timestamp(col) => {
                 let old = take(col);
                 *col = compact_vec(old, deletion);
             }
@@ -179,6 +442,16 @@ impl Column {
                 *col = compact_vec(old, deletion);
             }
 
+            ColumnData::Bytes(col) => {
+                let old = take(col);
+                *col = compact_vec(old, deletion);
+            }
+
+            ColumnData::UInt(col) => {
+                let old = take(col);
+                *col = compact_vec(old, deletion);
+            }
+
             ColumnData::Bool(col) => {
                 let old = take(col);
 
@@ -196,6 +469,12 @@ impl Column {
                 let old = take(col);
                 *col = compact_vec(old, deletion);
             }
+
+            // The dictionary is kept as is: codes stay valid and entries are cheap.
+            ColumnData::Dict { codes, .. } => {
+                let old = take(codes);
+                *codes = compact_vec(old, deletion);
+            }
         }
 
         // compact null bitmap
@@ -213,16 +492,67 @@ impl Column {
         Ok(())
     }
 
+    /// Removes every value, keeping the allocations for the rows inserted next.
+    ///
+    /// A dictionary encoded column stays encoded, with an empty dictionary.
+    pub fn clear(&mut self) {
+        match &mut self.data {
+            ColumnData::Int(v) | ColumnData::Timestamp(v) => v.clear(),
+            ColumnData::UInt(v) => v.clear(),
+            ColumnData::Float(v) => v.clear(),
+            ColumnData::Text(v) => v.clear(),
+            ColumnData::Bytes(v) => v.clear(),
+            ColumnData::Bool(v) => v.clear(),
+            ColumnData::Dict { codes, dict, index } => {
+                codes.clear();
+                dict.clear();
+                index.clear();
+            }
+        }
+        self.null_bitmap.clear();
+    }
+
+    /// Releases the spare capacity of the data vector and the null bitmap.
+    ///
+    /// [Column::compact] keeps the allocation of the original column, so after a bulk
+    /// delete and a vacuum most of it is unused until this is called. For a dictionary
+    /// encoded column, the dictionary and its index are shrunk as well.
+    pub fn shrink_to_fit(&mut self) {
+        match &mut self.data {
+            ColumnData::Int(v) | ColumnData::Timestamp(v) => v.shrink_to_fit(),
+            ColumnData::UInt(v) => v.shrink_to_fit(),
+            ColumnData::Float(v) => v.shrink_to_fit(),
+            ColumnData::Text(v) => v.shrink_to_fit(),
+            ColumnData::Bytes(v) => v.shrink_to_fit(),
+            ColumnData::Bool(v) => v.shrink_to_fit(),
+            ColumnData::Dict { codes, dict, index } => {
+                codes.shrink_to_fit();
+                dict.shrink_to_fit();
+                index.shrink_to_fit();
+            }
+        }
+        self.null_bitmap.shrink_to_fit();
+    }
+
     /// Replace a value in the column by a new value.
     ///
     /// # Errors
     /// Returns an error if the row_idx is too high or if the value's type does not match the
-    /// column's data type.
+    /// column's data type, or if the column is sorted ([Column::new_sorted]): changing a
+    /// value in place could break the order.
     ///
     /// # Behavior
+    /// - `Null` is accepted whatever the column type, like in [Column::push]: every column
+    ///   is nullable.
     /// - If the new value is `Null`, the previous value is not changed but only the null_bitmap to
     ///   be faster.
     pub fn set(&mut self, row_idx: usize, value: &Value) -> Result<(), String> {
+        if self.sorted {
+            return Err(format!(
+                "Column {} is sorted, its values cannot be updated",
+                self.name
+            ));
+        }
         if self.len() <= row_idx {
             return Err("The row index is too high".into());
         }
@@ -242,20 +572,348 @@ impl Column {
             ));
         }
 
-        self.null_bitmap.set(row_idx, false);
+        // write the data first: if anything fails, the cell is left untouched
         match (&mut self.data, value) {
             (ColumnData::Int(col), Value::Int(v)) => col[row_idx] = *v,
+            (ColumnData::UInt(col), Value::UInt(v)) => col[row_idx] = *v,
+            (ColumnData::Timestamp(col), Value::Timestamp(v)) => col[row_idx] = *v,
             (ColumnData::Float(col), Value::Float(v)) => col[row_idx] = *v,
             (ColumnData::Text(col), Value::Text(v)) => col[row_idx] = Arc::clone(v),
+            (ColumnData::Bytes(col), Value::Blob(v)) => col[row_idx] = Arc::clone(v),
             (ColumnData::Bool(col), Value::Bool(v)) => {
                 col.replace(row_idx, *v);
             }
+            (ColumnData::Dict { codes, dict, index }, Value::Text(v)) => {
+                codes[row_idx] = dict_code(dict, index, v)?;
+            }
             _ => {
                 return Err("Internal error: type mismatch".into());
             }
         }
+        self.null_bitmap.set(row_idx, false);
+        Ok(())
+    }
+
+    /// Replaces every non-null value by the result of `f`, leaving `NULL`s untouched.
+    ///
+    /// All new values are computed and type-checked before the column is modified, so
+    /// if `f` fails or returns a value of another type, the column is left unchanged.
+    /// `f` may return `NULL` to clear a cell.
+    ///
+    /// # Errors
+    /// Returns the first error produced by `f`, a type mismatch error, or an error if
+    /// the column is sorted ([Column::new_sorted]).
+    ///
+    /// # Example
+    /// ```
+    /// # use db::column::Column;
+    /// # use db::data_type::DataType;
+    /// # use db::value::Value;
+    /// let mut col = Column::new("n".into(), DataType::Int);
+    /// col.push(Value::Int(21)).unwrap();
+    /// col.map_in_place(|v| Ok(Value::Int(v.as_int().unwrap() * 2))).unwrap();
+    /// assert_eq!(col.get(0), Some(Value::Int(42)));
+    /// ```
+    pub fn map_in_place<F>(&mut self, mut f: F) -> Result<(), String>
+    where
+        F: FnMut(Value) -> Result<Value, String>,
+    {
+        if self.sorted {
+            return Err(format!(
+                "Column {} is sorted, its values cannot be updated",
+                self.name
+            ));
+        }
+        let mut new_values = Vec::with_capacity(self.len());
+        for row_idx in 0..self.len() {
+            if self.null_bitmap[row_idx] {
+                continue;
+            }
+            let value = f(self.get(row_idx).unwrap_or(Value::Null))?;
+            if value.data_type().is_some_and(|t| t != self.data_type) {
+                return Err(format!(
+                    "Value {value:?} has type {:?} while column data type is {:?}",
+                    value.data_type(),
+                    self.data_type
+                ));
+            }
+            new_values.push((row_idx, value));
+        }
+
+        for (row_idx, value) in new_values {
+            self.set(row_idx, &value)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the row indices of the column in sorted order.
+    ///
+    /// The permutation can be applied to any column of the same table, so a single
+    /// sort on the key column is enough to reorder every other column without
+    /// materializing whole rows.
+    ///
+    /// # Behavior
+    /// - `NULL` values follow the [Value] ordering: first in ascending order,
+    ///   last in descending order.
+    /// - Floats are ordered with [f64::total_cmp], so `NaN` sorts after every number.
+    /// - The sort is stable: rows with equal values keep their physical order.
+    ///
+    /// # Example
+    /// ```
+    /// # use db::column::Column;
+    /// # use db::data_type::DataType;
+    /// # use db::value::Value;
+    /// let mut col = Column::new("age".into(), DataType::Int);
+    /// col.push(Value::Int(30)).unwrap();
+    /// col.push(Value::Int(10)).unwrap();
+    /// col.push(Value::Int(20)).unwrap();
+    ///
+    /// assert_eq!(col.sort_permutation(false), vec![1, 2, 0]);
+    /// assert_eq!(col.sort_permutation(true), vec![0, 2, 1]);
+    /// ```
+    pub fn sort_permutation(&self, desc: bool) -> Vec<usize> {
+        let mut permutation: Vec<usize> = (0..self.len()).collect();
+        permutation.sort_by(|&a, &b| {
+            let ord = self.cmp_rows(a, b);
+            if desc { ord.reverse() } else { ord }
+        });
+        permutation
+    }
+
+    /// Compares the values stored at two row indices without building [Value]s.
+    fn cmp_rows(&self, a: usize, b: usize) -> Ordering {
+        match (self.null_bitmap[a], self.null_bitmap[b]) {
+            (true, true) => return Ordering::Equal,
+            (true, false) => return Ordering::Less,
+            (false, true) => return Ordering::Greater,
+            (false, false) => {}
+        }
+        match &self.data {
+            ColumnData::Int(col) | ColumnData::Timestamp(col) => col[a].cmp(&col[b]),
+            ColumnData::UInt(col) => col[a].cmp(&col[b]),
+            ColumnData::Float(col) => col[a].total_cmp(&col[b]),
+            ColumnData::Text(col) => col[a].cmp(&col[b]),
+            ColumnData::Bytes(col) => col[a].cmp(&col[b]),
+            ColumnData::Bool(col) => col[a].cmp(&col[b]),
+            ColumnData::Dict { codes, dict, .. } => {
+                dict[codes[a] as usize].cmp(&dict[codes[b] as usize])
+            }
+        }
+    }
+
+    /// Returns `true` if the column is stored with dictionary encoding.
+    pub fn is_dictionary_encoded(&self) -> bool {
+        matches!(self.data, ColumnData::Dict { .. })
+    }
+
+    /// Converts a `Text` column to dictionary encoding ([ColumnData::Dict]).
+    ///
+    /// This pays off for low-cardinality columns (departments, statuses, countries...):
+    /// each row then costs a 4-byte code instead of a 16-byte `Arc<str>` pointer, and
+    /// equality checks compare codes instead of strings. Converting an already encoded
+    /// column is a no-op.
+    ///
+    /// # Errors
+    /// Returns an error if the column is not a `Text` column, or if it holds more
+    /// distinct strings than a `u32` code can address.
+    pub fn encode_dictionary(&mut self) -> Result<(), String> {
+        let ColumnData::Text(values) = &mut self.data else {
+            if self.is_dictionary_encoded() {
+                return Ok(());
+            }
+            return Err(format!(
+                "Column {} of type {:?} cannot be dictionary encoded",
+                self.name, self.data_type
+            ));
+        };
+
+        let mut codes = Vec::with_capacity(values.len());
+        let mut dict = Vec::new();
+        let mut index = HashMap::new();
+        for (i, value) in take(values).into_iter().enumerate() {
+            if self.null_bitmap[i] {
+                codes.push(0);
+            } else {
+                codes.push(dict_code(&mut dict, &mut index, &value)?);
+            }
+        }
+
+        self.data = ColumnData::Dict { codes, dict, index };
         Ok(())
     }
+
+    /// Returns the indices of the null rows, read from the set bits of the null bitmap
+    /// without looking at the values.
+    pub fn null_indices(&self) -> Vec<usize> {
+        self.null_bitmap.iter_ones().collect()
+    }
+
+    /// Returns the indices of the non-null rows, read from the unset bits of the null
+    /// bitmap without looking at the values.
+    pub fn non_null_indices(&self) -> Vec<usize> {
+        self.null_bitmap.iter_zeros().collect()
+    }
+
+    /// Returns the indices of the non-null rows equal to `value`.
+    ///
+    /// On a dictionary-encoded column the literal is resolved to its code once and the
+    /// scan only compares integers; a string absent from the dictionary matches nothing
+    /// without scanning at all.
+    pub fn rows_equal_to(&self, value: &Value) -> Vec<usize> {
+        if value.is_null() {
+            return vec![];
+        }
+        let live = |i: &usize| !self.null_bitmap[*i];
+
+        match (&self.data, value) {
+            (ColumnData::Dict { codes, index, .. }, Value::Text(s)) => match index.get(s) {
+                Some(code) => (0..codes.len())
+                    .filter(live)
+                    .filter(|&i| codes[i] == *code)
+                    .collect(),
+                None => vec![],
+            },
+            _ => (0..self.len())
+                .filter(live)
+                .filter(|&i| self.get(i).as_ref() == Some(value))
+                .collect(),
+        }
+    }
+
+    /// Compares every row against `value` at once, returning a mask with one bit per row.
+    ///
+    /// A bit is set when the row satisfies `op`; null rows are never set, except with `<=>`
+    /// against `NULL`. Masks of several predicates can be combined with `&` and `|`
+    /// before any row is materialized. `>`, `<`, `>=` and `<=` apply to numbers, timestamps
+    /// and text, `=` and `!=` to every type.
+    ///
+    /// The comparison is stricter than `WHERE`, which follows the session's
+    /// [ComparisonConfig](crate::database::ComparisonConfig): `value` must have the column
+    /// type exactly (no `INT` against a `FLOAT` column), floats are equal when they differ
+    /// by less than `f64::EPSILON`, and text is compared case-sensitively, in byte order.
+    ///
+    /// # Errors
+    /// Returns an error if `value` does not have the column type, or if `op` is not
+    /// supported for it (e.g. `>` on booleans).
+    pub fn compare_mask(&self, op: &ComparisonOp, value: &Value) -> Result<BitVec, String> {
+        if value.is_null() {
+            return Ok(match op {
+                ComparisonOp::NullSafeEq => self.null_bitmap.clone(),
+                _ => bitvec![0; self.len()],
+            });
+        }
+        if value.data_type() != Some(self.data_type) {
+            return Err(format!(
+                "Type mismatch: cannot compare column {} of type {:?} with {:?}",
+                self.name, self.data_type, value
+            ));
+        }
+        // `!=` is computed as `=` with the result flipped
+        let is_neq = *op == ComparisonOp::Neq;
+        let is_eq = matches!(
+            op,
+            ComparisonOp::Eq | ComparisonOp::NullSafeEq | ComparisonOp::Neq
+        );
+
+        let mut mask: BitVec = match (&self.data, value) {
+            (ColumnData::Int(col), Value::Int(v))
+            | (ColumnData::Timestamp(col), Value::Timestamp(v)) => col
+                .iter()
+                .map(|x| match op {
+                    ComparisonOp::Gt => x > v,
+                    ComparisonOp::Lt => x < v,
+                    ComparisonOp::Gte => x >= v,
+                    ComparisonOp::Lte => x <= v,
+                    ComparisonOp::Neq => x != v,
+                    ComparisonOp::Eq | ComparisonOp::NullSafeEq => x == v,
+                })
+                .collect(),
+            (ColumnData::UInt(col), Value::UInt(v)) => col
+                .iter()
+                .map(|x| match op {
+                    ComparisonOp::Gt => x > v,
+                    ComparisonOp::Lt => x < v,
+                    ComparisonOp::Gte => x >= v,
+                    ComparisonOp::Lte => x <= v,
+                    ComparisonOp::Neq => x != v,
+                    ComparisonOp::Eq | ComparisonOp::NullSafeEq => x == v,
+                })
+                .collect(),
+            (ColumnData::Float(col), Value::Float(v)) => col
+                .iter()
+                .map(|x| match op {
+                    ComparisonOp::Gt => x > v,
+                    ComparisonOp::Lt => x < v,
+                    ComparisonOp::Gte => x >= v,
+                    ComparisonOp::Lte => x <= v,
+                    ComparisonOp::Neq => (x - v).abs() >= f64::EPSILON,
+                    ComparisonOp::Eq | ComparisonOp::NullSafeEq => (x - v).abs() < f64::EPSILON,
+                })
+                .collect(),
+            (ColumnData::Bool(col), Value::Bool(v)) if is_eq => {
+                col.iter().map(|b| (*b == *v) != is_neq).collect()
+            }
+            (ColumnData::Text(col), Value::Text(v)) => col
+                .iter()
+                .map(|s| match op {
+                    ComparisonOp::Gt => **s > **v,
+                    ComparisonOp::Lt => **s < **v,
+                    ComparisonOp::Gte => **s >= **v,
+                    ComparisonOp::Lte => **s <= **v,
+                    ComparisonOp::Neq => **s != **v,
+                    ComparisonOp::Eq | ComparisonOp::NullSafeEq => **s == **v,
+                })
+                .collect(),
+            (ColumnData::Bytes(col), Value::Blob(v)) if is_eq => {
+                col.iter().map(|b| (**b == **v) != is_neq).collect()
+            }
+            (ColumnData::Dict { codes, index, .. }, Value::Text(v)) if is_eq => {
+                match index.get(v) {
+                    Some(code) => codes.iter().map(|c| (c == code) != is_neq).collect(),
+                    None => BitVec::repeat(is_neq, codes.len()),
+                }
+            }
+            // each distinct string is compared once, then rows only look up their code
+            (ColumnData::Dict { codes, dict, .. }, Value::Text(v)) => {
+                let matching: Vec<bool> = dict
+                    .iter()
+                    .map(|s| match op {
+                        ComparisonOp::Gt => **s > **v,
+                        ComparisonOp::Gte => **s >= **v,
+                        ComparisonOp::Lte => **s <= **v,
+                        _ => **s < **v,
+                    })
+                    .collect();
+                codes.iter().map(|&c| matching[c as usize]).collect()
+            }
+            _ => {
+                return Err(format!(
+                    "Cannot apply {} to column {} of type {:?}",
+                    op, self.name, self.data_type
+                ));
+            }
+        };
+        for row in self.null_bitmap.iter_ones() {
+            mask.set(row, false);
+        }
+        Ok(mask)
+    }
+}
+
+/// Returns the dictionary code of `value`, adding it to the dictionary if needed.
+fn dict_code(
+    dict: &mut Vec<Arc<str>>,
+    index: &mut HashMap<Arc<str>, u32>,
+    value: &Arc<str>,
+) -> Result<u32, String> {
+    if let Some(code) = index.get(value) {
+        return Ok(*code);
+    }
+    let code = u32::try_from(dict.len())
+        .map_err(|_| "Dictionary is full: too many distinct values".to_string())?;
+    dict.push(Arc::clone(value));
+    index.insert(Arc::clone(value), code);
+    Ok(code)
 }
 
 /// Generic helper used during column compaction.
@@ -399,6 +1057,28 @@ mod tests {
         assert!(col.set(10, &Value::Int(42)).is_err());
     }
 
+    #[test]
+    fn test_set_null_any_type() {
+        let samples = [
+            (DataType::Int, Value::Int(1)),
+            (DataType::UInt, Value::UInt(1)),
+            (DataType::Float, Value::Float(1.0)),
+            (DataType::Text, Value::Text("a".into())),
+            (DataType::Bool, Value::Bool(true)),
+            (DataType::Timestamp, Value::Timestamp(1)),
+            (DataType::Bytes, Value::Blob(Arc::from([1u8]))),
+        ];
+        for (data_type, value) in samples {
+            let mut col = Column::new("c".into(), data_type);
+            col.push(value.clone()).unwrap();
+            col.push(Value::Null).unwrap();
+            col.set(0, &Value::Null).unwrap();
+            assert_eq!(col.get(0), Some(Value::Null), "{data_type:?}");
+            col.set(1, &value).unwrap();
+            assert_eq!(col.get(1), Some(value), "{data_type:?}");
+        }
+    }
+
     #[test]
     fn test_compact_basic() {
         let mut col = Column::new("test".into(), DataType::Int);
@@ -474,4 +1154,358 @@ mod tests {
         assert_eq!(col.get(0), Some(Value::Bool(true)));
         assert_eq!(col.get(1), Some(Value::Bool(true)));
     }
+
+    #[test]
+    fn test_sorted_column_keeps_order() {
+        let mut col = Column::new_sorted("ts".into(), DataType::Int);
+        for v in [5, 1, 9, 3, 3, 7, 0, 8] {
+            col.push(Value::Int(v)).unwrap();
+        }
+        col.push(Value::Null).unwrap();
+        assert!(col.push(Value::Text("x".into())).is_err());
+
+        let values: Vec<Value> = (0..col.len()).map(|i| col.get(i).unwrap()).collect();
+        let mut expected = vec![Value::Null];
+        expected.extend([0, 1, 3, 3, 5, 7, 8, 9].map(Value::Int));
+        assert_eq!(values, expected);
+
+        assert_eq!(col.sorted_range(&Value::Int(3), &Value::Int(7)), Ok(3..7));
+        assert_eq!(col.sorted_range(&Value::Int(4), &Value::Int(4)), Ok(5..5));
+        assert_eq!(col.sorted_range(&Value::Int(9), &Value::Int(1)), Ok(8..8));
+        assert!(col.set(0, &Value::Int(2)).is_err());
+        assert!(
+            Column::new("n".into(), DataType::Int)
+                .sorted_range(&Value::Int(0), &Value::Int(1))
+                .is_err()
+        );
+
+        let mut words = Column::new_sorted("w".into(), DataType::Text);
+        for w in ["pear", "apple", "fig"] {
+            words.push(Value::Text(w.into())).unwrap();
+        }
+        assert_eq!(words.get(0), Some(Value::Text("apple".into())));
+        assert_eq!(words.get(2), Some(Value::Text("pear".into())));
+    }
+
+    #[test]
+    fn test_sorted_float_column_rejects_non_finite() {
+        let mut sorted = Column::new_sorted("key".into(), DataType::Float);
+        let mut plain = Column::new("score".into(), DataType::Float);
+        for v in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            let err = sorted.push(Value::Float(v)).unwrap_err();
+            assert!(err.contains("non-finite"), "{err}");
+            plain.push(Value::Float(v)).unwrap();
+        }
+        assert!(sorted.is_empty());
+        assert_eq!(plain.len(), 3);
+
+        sorted.push(Value::Float(1.5)).unwrap();
+        sorted.push(Value::Null).unwrap();
+        assert_eq!(sorted.len(), 2);
+    }
+
+    #[test]
+    fn test_sort_permutation_int_with_nulls() {
+        let mut col = Column::new("age".into(), DataType::Int);
+        col.push(Value::Int(30)).unwrap();
+        col.push(Value::Null).unwrap();
+        col.push(Value::Int(10)).unwrap();
+        col.push(Value::Int(20)).unwrap();
+        col.push(Value::Null).unwrap();
+
+        // NULLs first when ascending, stable between themselves
+        assert_eq!(col.sort_permutation(false), vec![1, 4, 2, 3, 0]);
+        // NULLs last when descending
+        assert_eq!(col.sort_permutation(true), vec![0, 3, 2, 1, 4]);
+
+        let sorted: Vec<Value> = col
+            .sort_permutation(false)
+            .into_iter()
+            .map(|i| col.get(i).unwrap())
+            .collect();
+        assert_eq!(
+            sorted,
+            vec![
+                Value::Null,
+                Value::Null,
+                Value::Int(10),
+                Value::Int(20),
+                Value::Int(30)
+            ]
+        );
+    }
+
+    fn make_dict_column() -> Column {
+        let mut col = Column::new("dept".into(), DataType::Text);
+        for dept in ["Sales", "HR", "Sales", "IT", "HR", "Sales"] {
+            col.push(Value::Text(Arc::from(dept))).unwrap();
+        }
+        col.push(Value::Null).unwrap();
+        col.encode_dictionary().unwrap();
+        col
+    }
+
+    #[test]
+    fn test_dict_round_trip() {
+        let mut col = make_dict_column();
+        assert!(col.is_dictionary_encoded());
+        assert_eq!(col.data_type, DataType::Text);
+
+        col.push(Value::Text(Arc::from("Legal"))).unwrap();
+        col.push(Value::Text(Arc::from("HR"))).unwrap();
+
+        assert_eq!(col.len(), 9);
+        assert_eq!(col.get(0), Some(Value::Text(Arc::from("Sales"))));
+        assert_eq!(col.get(3), Some(Value::Text(Arc::from("IT"))));
+        assert_eq!(col.get(6), Some(Value::Null));
+        assert_eq!(col.get(7), Some(Value::Text(Arc::from("Legal"))));
+        assert_eq!(col.get(8), Some(Value::Text(Arc::from("HR"))));
+
+        col.set(0, &Value::Text(Arc::from("IT"))).unwrap();
+        assert_eq!(col.get(0), Some(Value::Text(Arc::from("IT"))));
+        assert!(col.push(Value::Int(1)).is_err());
+
+        if let ColumnData::Dict { dict, .. } = &col.data {
+            assert_eq!(dict.len(), 4);
+        } else {
+            panic!("Expected Dict storage");
+        }
+    }
+
+    #[test]
+    fn test_dict_compact() {
+        let mut col = make_dict_column();
+        col.compact(&bitvec![1, 0, 0, 1, 0, 0, 0]).unwrap();
+
+        assert_eq!(col.len(), 5);
+        assert_eq!(col.get(0), Some(Value::Text(Arc::from("HR"))));
+        assert_eq!(col.get(4), Some(Value::Null));
+    }
+
+    #[test]
+    fn test_dict_equality_on_codes() {
+        let col = make_dict_column();
+        assert_eq!(
+            col.rows_equal_to(&Value::Text(Arc::from("Sales"))),
+            vec![0, 2, 5]
+        );
+        assert!(
+            col.rows_equal_to(&Value::Text(Arc::from("Unknown")))
+                .is_empty()
+        );
+        assert!(col.rows_equal_to(&Value::Null).is_empty());
+    }
+
+    #[test]
+    fn test_encode_dictionary_rejects_non_text() {
+        let mut col = Column::new("age".into(), DataType::Int);
+        assert!(col.encode_dictionary().is_err());
+    }
+
+    #[test]
+    fn test_dict_uses_less_memory() {
+        let shared: Vec<Arc<str>> = ["Sales", "HR", "IT"].into_iter().map(Arc::from).collect();
+        let mut col = Column::new("dept".into(), DataType::Text);
+        for i in 0..10_000 {
+            col.push(Value::Text(Arc::clone(&shared[i % 3]))).unwrap();
+        }
+        let text_size = allocative::size_of_unique_allocated_data(&col);
+
+        col.encode_dictionary().unwrap();
+        let dict_size = allocative::size_of_unique_allocated_data(&col);
+
+        assert!(
+            dict_size * 3 < text_size,
+            "dict: {dict_size} bytes, text: {text_size} bytes"
+        );
+    }
+
+    #[test]
+    fn test_shrink_to_fit_after_compact() {
+        let mut col = Column::new("n".into(), DataType::Int);
+        for i in 0..10_000 {
+            col.push(Value::Int(i)).unwrap();
+        }
+        let mut deletion = bitvec![1; 10_000];
+        deletion[..10].fill(false);
+        col.compact(&deletion).unwrap();
+        let compacted_size = allocative::size_of_unique_allocated_data(&col);
+
+        col.shrink_to_fit();
+        let shrunk_size = allocative::size_of_unique_allocated_data(&col);
+        assert!(
+            shrunk_size * 100 < compacted_size,
+            "shrunk: {shrunk_size} bytes, compacted: {compacted_size} bytes"
+        );
+        assert_eq!(col.len(), 10);
+        assert_eq!(col.get(9), Some(Value::Int(9)));
+    }
+
+    #[test]
+    fn test_approx_distinct() {
+        let mut col = Column::new("id".into(), DataType::Int);
+        for i in 0..100_000 {
+            col.push(Value::Int(i % 40_000)).unwrap();
+        }
+        col.push(Value::Null).unwrap();
+        let estimate = col.approx_distinct() as f64;
+        // 3 standard errors of the sketch
+        assert!(
+            (estimate - 40_000.0).abs() < 40_000.0 * 0.05,
+            "estimate {estimate}"
+        );
+
+        let mut col = make_dict_column();
+        col.push(Value::Null).unwrap();
+        assert_eq!(col.approx_distinct(), 3);
+    }
+
+    #[test]
+    fn test_failed_set_leaves_cell_unchanged() {
+        let mut col = Column::new("n".into(), DataType::Int);
+        col.push(Value::Null).unwrap();
+        col.push(Value::Int(7)).unwrap();
+
+        // public type check
+        assert!(col.set(1, &Value::Text(Arc::from("x"))).is_err());
+        assert_eq!(col.get(1), Some(Value::Int(7)));
+
+        // storage disagreeing with the declared type hits the internal mismatch arm
+        col.data = ColumnData::Float(vec![0.0, 7.0]);
+        assert!(col.set(0, &Value::Int(5)).is_err());
+        assert!(col.null_bitmap[0]);
+        assert_eq!(col.get(0), Some(Value::Null));
+    }
+
+    #[test]
+    fn test_compare_mask_gt() {
+        let mut col = Column::new("age".into(), DataType::Int);
+        for v in [Value::Int(10), Value::Int(30), Value::Null, Value::Int(25)] {
+            col.push(v).unwrap();
+        }
+
+        let mask = col
+            .compare_mask(&ComparisonOp::Gt, &Value::Int(20))
+            .unwrap();
+        assert_eq!(mask, bitvec![0, 1, 0, 1]);
+        let at_least = col
+            .compare_mask(&ComparisonOp::Gte, &Value::Int(25))
+            .unwrap();
+        assert_eq!(at_least, bitvec![0, 1, 0, 1]);
+        let at_most = col
+            .compare_mask(&ComparisonOp::Lte, &Value::Int(25))
+            .unwrap();
+        assert_eq!(at_most, bitvec![1, 0, 0, 1]);
+
+        let nulls = col
+            .compare_mask(&ComparisonOp::NullSafeEq, &Value::Null)
+            .unwrap();
+        assert_eq!(nulls, bitvec![0, 0, 1, 0]);
+        assert!(
+            col.compare_mask(&ComparisonOp::Gt, &Value::Float(1.0))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_null_indices_match_scan() {
+        let mut col = Column::new("email".into(), DataType::Text);
+        for i in 0..200 {
+            let value = if i % 7 == 0 || i % 11 == 3 {
+                Value::Null
+            } else {
+                Value::Text(format!("user{i}").into())
+            };
+            col.push(value).unwrap();
+        }
+        // a null written over a value only flips its bit
+        col.set(5, &Value::Null).unwrap();
+
+        let naive_nulls: Vec<usize> = (0..col.len())
+            .filter(|&i| col.get(i) == Some(Value::Null))
+            .collect();
+        let naive_values: Vec<usize> = (0..col.len())
+            .filter(|&i| col.get(i) != Some(Value::Null))
+            .collect();
+        assert_eq!(col.null_indices(), naive_nulls);
+        assert_eq!(col.non_null_indices(), naive_values);
+        assert!(col.null_indices().contains(&5));
+
+        let empty = Column::new("n".into(), DataType::Int);
+        assert!(empty.null_indices().is_empty());
+        assert!(empty.non_null_indices().is_empty());
+    }
+
+    #[test]
+    fn test_compare_masks_and() {
+        let mut age = Column::new("age".into(), DataType::Int);
+        let mut city = Column::new("city".into(), DataType::Text);
+        for (a, c) in [(10, "Paris"), (30, "Paris"), (40, "Lyon"), (50, "Paris")] {
+            age.push(Value::Int(a)).unwrap();
+            city.push(Value::Text(c.into())).unwrap();
+        }
+        city.encode_dictionary().unwrap();
+
+        let older = age
+            .compare_mask(&ComparisonOp::Gt, &Value::Int(20))
+            .unwrap();
+        let in_paris = city
+            .compare_mask(&ComparisonOp::Eq, &Value::Text("Paris".into()))
+            .unwrap();
+        assert_eq!(older & in_paris, bitvec![0, 1, 0, 1]);
+
+        let after_m = city
+            .compare_mask(&ComparisonOp::Gt, &Value::Text("M".into()))
+            .unwrap();
+        assert_eq!(after_m, bitvec![1, 1, 0, 1]);
+        let not_paris = city
+            .compare_mask(&ComparisonOp::Neq, &Value::Text("Paris".into()))
+            .unwrap();
+        assert_eq!(not_paris, bitvec![0, 0, 1, 0]);
+        let not_rome = city
+            .compare_mask(&ComparisonOp::Neq, &Value::Text("Rome".into()))
+            .unwrap();
+        assert_eq!(not_rome, bitvec![1, 1, 1, 1]);
+        assert_eq!(
+            city.compare_mask(&ComparisonOp::Lt, &Value::Text("M".into()))
+                .unwrap(),
+            bitvec![0, 0, 1, 0]
+        );
+
+        let mut active = Column::new("active".into(), DataType::Bool);
+        active.push(Value::Bool(true)).unwrap();
+        assert!(
+            active
+                .compare_mask(&ComparisonOp::Gt, &Value::Bool(false))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_map_in_place() {
+        let mut col = Column::new("n".into(), DataType::Int);
+        col.push(Value::Int(1)).unwrap();
+        col.push(Value::Null).unwrap();
+        col.push(Value::Int(3)).unwrap();
+
+        col.map_in_place(|v| Ok(Value::Int(v.as_int().unwrap() * 2)))
+            .unwrap();
+        assert_eq!(col.get(0), Some(Value::Int(2)));
+        assert_eq!(col.get(1), Some(Value::Null));
+        assert_eq!(col.get(2), Some(Value::Int(6)));
+
+        // the second value has the wrong type: nothing is written, not even the first one
+        let mut calls = 0;
+        let res = col.map_in_place(|v| {
+            calls += 1;
+            if calls == 1 {
+                Ok(Value::Int(0))
+            } else {
+                Ok(Value::Text(format!("{v:?}").into()))
+            }
+        });
+        assert!(res.is_err());
+        assert_eq!(col.get(0), Some(Value::Int(2)));
+        assert_eq!(col.get(2), Some(Value::Int(6)));
+    }
 }
@@ -2,14 +2,57 @@ use allocative::Allocative;
 
 /// Represents the supported data types in the database schema.
 /// These types define the structure of columns and the expected format of values.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Allocative)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Allocative)]
 pub enum DataType {
     /// A 64-bit signed integer.
     Int,
+    /// A 64-bit unsigned integer (counts, ids...).
+    UInt,
     /// A 64-bit floating-point number.
     Float,
     /// A variable-length UTF-8 character string.
     Text,
     /// A boolean value (true or false).
     Bool,
+    /// A point in time, stored as microseconds since the Unix epoch (UTC).
+    Timestamp,
+    /// Raw binary data (hashes, small images...).
+    Bytes,
+}
+
+impl DataType {
+    /// Returns `true` for the types arithmetic aggregates (`SUM`, `AVG`...) apply to:
+    /// [DataType::Int], [DataType::UInt] and [DataType::Float].
+    pub fn is_numeric(&self) -> bool {
+        matches!(self, Self::Int | Self::UInt | Self::Float)
+    }
+
+    /// Returns `true` for the integer types, [DataType::Int] and [DataType::UInt], which
+    /// can be compared with each other.
+    pub fn is_integer(&self) -> bool {
+        matches!(self, Self::Int | Self::UInt)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_numeric() {
+        assert!(DataType::Int.is_numeric());
+        assert!(DataType::UInt.is_numeric());
+        assert!(DataType::Float.is_numeric());
+        assert!(!DataType::Text.is_numeric());
+        assert!(!DataType::Bool.is_numeric());
+        assert!(!DataType::Timestamp.is_numeric());
+        assert!(!DataType::Bytes.is_numeric());
+    }
+
+    #[test]
+    fn test_is_integer() {
+        assert!(DataType::Int.is_integer());
+        assert!(DataType::UInt.is_integer());
+        assert!(!DataType::Float.is_integer());
+    }
 }
@@ -3,20 +3,32 @@ use allocative::Allocative;
 use crate::{
     Column, ColumnDef, DataType, Value,
     ast::{
-        Aggregate, ColumnsSelect, ComparisonOp, Delete, Expr, InsertInto, OrderByClause,
-        SelectItem, SortDirection, Statement, Update,
+        Aggregate, AlterAction, AlterTable, ArithmeticOp, ColumnsSelect, ComparisonOp,
+        CreateTableAs, Delete, Expr, InsertInto, Join, JoinKind, NullsOrder, OrderByClause,
+        ScalarExpr, Select, SelectItem, SetVariable, SortDirection, Statement, Update,
     },
     parser::Parser,
-    table::{Schema, Table},
+    plan::PlanNode,
+    sample::RowSampler,
+    sketch::HyperLogLog,
+    table::{ColumnDefault, Schema, Table},
     tokenizer::Tokenizer,
+    value,
 };
 use std::{
     borrow::Cow,
     cmp::Ordering,
-    collections::{BTreeMap, HashMap},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet, hash_map::DefaultHasher},
+    hash::{Hash, Hasher},
     sync::Arc,
 };
 
+/// A compiled `WHERE` predicate, see [Database::compile_expr].
+type RowPredicate<'e> = Box<dyn Fn(&[Value]) -> Result<bool, String> + 'e>;
+
+/// A compiled scalar expression, see [Database::compile_scalar].
+type ScalarFn<'e> = Box<dyn Fn(&[Value]) -> Result<Value, String> + 'e>;
+
 /// The main entry point for the in-memory database engine.
 /// It manages a collection of tables and orchestrates query execution.
 #[derive(Default, Allocative)]
@@ -25,11 +37,47 @@ pub struct Database {
     tables: HashMap<String, Table>,
     /// Configuration for automatic data compaction.
     pub vacuum_config: VacuumConfig,
+    /// How values are compared in `WHERE` and `HAVING` clauses, also settable with `SET`.
+    pub comparison_config: ComparisonConfig,
 
     /// Snapshot of all tables taken at `BEGIN`. `None` means no active transaction.
     /// Restored on `ROLLBACK`; discarded on `COMMIT`. Auto-vacuum is suppressed while
     /// a snapshot is held to avoid compacting data that may need to be rolled back.
     transaction_snapshot: Option<HashMap<String, Table>>,
+
+    /// Opt-in string pool shared by every table, enabled with [Database::enable_string_pool].
+    ///
+    /// Each table already interns its own strings; the pool extends this across tables so
+    /// the same text inserted anywhere in the database shares a single `Arc<str>`.
+    /// Unused entries are released by `VACUUM`.
+    string_pool: Option<HashSet<Arc<str>>>,
+
+    /// Callbacks registered with [Database::on_change], run after every successful mutation.
+    #[allocative(skip)]
+    change_hooks: Vec<ChangeHook>,
+}
+
+/// A callback registered with [Database::on_change].
+pub type ChangeHook = Box<dyn FnMut(&ChangeEvent) + Send + Sync>;
+
+/// The kind of mutation reported by a [ChangeEvent].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Insert,
+    Update,
+    Delete,
+}
+
+/// Describes a successful `INSERT`, `UPDATE` or `DELETE`, passed to the change hooks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangeEvent {
+    /// The mutated table.
+    pub table: String,
+    /// What happened to the rows.
+    pub kind: ChangeKind,
+    /// The physical indices of the affected rows, in ascending order, as they were at
+    /// the time of the mutation (a later `VACUUM` renumbers rows).
+    pub rows: Vec<usize>,
 }
 
 /// Represents the result of a successful `SELECT` query.
@@ -38,10 +86,44 @@ pub struct QueryResult<'a> {
     /// The names of the columns included in the result set.
     #[allocative(skip)]
     pub columns: Vec<Cow<'a, str>>,
+    /// The logical type of each result column, aligned with `columns`.
+    ///
+    /// `None` when the type cannot be derived from the schema (e.g. an untyped expression).
+    pub column_types: Vec<Option<DataType>>,
     /// The actual data, returned as a vector of rows, where each row is a vector of [Value].
     pub rows: Vec<Vec<Value>>,
 }
 
+impl<'a> QueryResult<'a> {
+    /// Returns page `page` (0-based) of `size` rows, with the same columns.
+    ///
+    /// Works on the already materialized rows, without re-running the query. The last
+    /// page may be partial; pages past the end (or a `size` of 0) are empty.
+    pub fn page(&self, page: usize, size: usize) -> QueryResult<'a> {
+        let start = page.saturating_mul(size).min(self.rows.len());
+        let end = start.saturating_add(size).min(self.rows.len());
+        QueryResult {
+            columns: self.columns.clone(),
+            column_types: self.column_types.clone(),
+            rows: self.rows[start..end].to_vec(),
+        }
+    }
+}
+
+/// Execution statistics of a `SELECT`, returned by [Database::query_with_stats].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Allocative)]
+pub struct QueryStats {
+    /// Number of live rows read from the table.
+    ///
+    /// An unordered `LIMIT` without grouping or `DISTINCT` stops the scan as soon as
+    /// enough rows matched, so this can be far below the table size.
+    pub rows_scanned: usize,
+    /// Number of rows satisfying the `WHERE` clause, among the scanned ones.
+    pub rows_matched: usize,
+    /// Number of rows in the result, after grouping, `DISTINCT` and `LIMIT`.
+    pub rows_returned: usize,
+}
+
 #[derive(Debug, Clone, Allocative)]
 pub struct VacuumConfig {
     /// If false, auto-vacuum will never trigger.
@@ -63,6 +145,28 @@ impl Default for VacuumConfig {
     }
 }
 
+/// Settings of value comparisons and ordering, changed from SQL with `SET name = value`.
+#[derive(Debug, Clone, Allocative)]
+pub struct ComparisonConfig {
+    /// Two floats are equal when they differ by at most this much (`SET float_epsilon`).
+    pub float_epsilon: f64,
+    /// If false, text equality ignores case (`SET case_sensitive`).
+    pub case_sensitive: bool,
+    /// If true, `ORDER BY` breaks ties on the rowid, so the order of the rows is total
+    /// (`SET deterministic_order`). Off by default to save the extra sort key.
+    pub deterministic_order: bool,
+}
+
+impl Default for ComparisonConfig {
+    fn default() -> Self {
+        Self {
+            float_epsilon: f64::EPSILON,
+            case_sensitive: true,
+            deterministic_order: false,
+        }
+    }
+}
+
 /// Extracts all non-null Int values from a column, by row index.
 /// Returns an owned Vec so no lifetime annotation is needed.
 fn collect_int_col(rows: &[Vec<Value>], idx: usize) -> Vec<i64> {
@@ -74,6 +178,25 @@ fn collect_int_col(rows: &[Vec<Value>], idx: usize) -> Vec<i64> {
         .collect()
 }
 
+/// Extracts all non-null UInt values from a column, by row index.
+fn collect_uint_col(rows: &[Vec<Value>], idx: usize) -> Vec<u64> {
+    rows.iter()
+        .filter_map(|row| match row[idx] {
+            Value::UInt(v) => Some(v),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Widens an `Int` or `UInt` value to `i128`, which holds both ranges exactly.
+fn widen_integer(value: &Value) -> i128 {
+    match value {
+        Value::Int(i) => *i as i128,
+        Value::UInt(u) => *u as i128,
+        _ => unreachable!("not an integer: {:?}", value),
+    }
+}
+
 /// Extracts all non-null numeric values from a column as f64, by row index.
 /// Returns an owned Vec.
 fn collect_float_col(rows: &[Vec<Value>], idx: usize) -> Vec<f64> {
@@ -85,18 +208,99 @@ fn collect_float_col(rows: &[Vec<Value>], idx: usize) -> Vec<f64> {
         .collect()
 }
 
+/// Running `f64` sum using Neumaier's compensated summation: the low-order bits lost
+/// by each addition are accumulated separately and added back at the end, so the
+/// result stays accurate over many values of different magnitudes.
+#[derive(Default)]
+struct CompensatedSum {
+    sum: f64,
+    compensation: f64,
+}
+
+impl CompensatedSum {
+    fn add(&mut self, value: f64) {
+        let total = self.sum + value;
+        if self.sum.abs() >= value.abs() {
+            self.compensation += (self.sum - total) + value;
+        } else {
+            self.compensation += (value - total) + self.sum;
+        }
+        self.sum = total;
+    }
+
+    fn total(&self) -> f64 {
+        self.sum + self.compensation
+    }
+}
+
+/// The current row of the outer query of a correlated subquery.
+struct OuterRow<'o, 'a> {
+    /// The tables and aliases the outer query can qualify its columns with.
+    names: &'o [&'a str],
+    schema: &'o Schema,
+    row: &'o [Value],
+}
+
 /// Validates that a column exists and is numeric (Int or Float).
 /// Returns the column index and its DataType.
 fn validate_numeric_col(schema: &Schema, col: &str) -> Result<(usize, DataType), String> {
-    let column = schema
-        .columns
-        .iter()
-        .find(|c| c.name == col)
-        .ok_or_else(|| format!("Column {} does not exist", col))?;
-    if matches!(column.data_type, DataType::Text | DataType::Bool) {
+    let idx = schema.index_of(col)?;
+    let data_type = schema.columns[idx].data_type;
+    if !data_type.is_numeric() {
         return Err(format!("Column {} is not numeric", col));
     }
-    Ok((schema.index_of(col)?, column.data_type))
+    Ok((idx, data_type))
+}
+
+/// Matches `text` against a SQL `LIKE` pattern, where `%` stands for any sequence of
+/// characters (including none) and `_` for exactly one character.
+///
+/// On a mismatch, the last `%` seen absorbs one more character and matching resumes
+/// right after it, so the worst case is O(text × pattern) without any recursion.
+fn like_match(text: &str, pattern: &str) -> bool {
+    let text: Vec<char> = text.chars().collect();
+    let pattern: Vec<char> = pattern.chars().collect();
+    let (mut t, mut p) = (0, 0);
+    // the position of the last `%` in the pattern, and of the text it is matched up to
+    let mut backtrack: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('%') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '_' || c == text[t] => {
+                t += 1;
+                p += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    backtrack = Some((star, matched + 1));
+                    p = star + 1;
+                    t = matched + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '%')
+}
+
+/// Deep-copies every table, giving a simple (if memory-heavy) snapshot of the database.
+///
+/// Change hooks are not cloned: they are arbitrary callbacks tied to the original
+/// database, so the clone starts without any.
+impl Clone for Database {
+    fn clone(&self) -> Self {
+        Self {
+            tables: self.tables.clone(),
+            vacuum_config: self.vacuum_config.clone(),
+            comparison_config: self.comparison_config.clone(),
+            transaction_snapshot: self.transaction_snapshot.clone(),
+            string_pool: self.string_pool.clone(),
+            change_hooks: Vec::new(),
+        }
+    }
 }
 
 impl<'a> Database {
@@ -105,23 +309,112 @@ impl<'a> Database {
         Self {
             tables: HashMap::default(),
             vacuum_config: VacuumConfig::default(),
+            comparison_config: ComparisonConfig::default(),
             transaction_snapshot: None,
+            string_pool: None,
+            change_hooks: Vec::new(),
+        }
+    }
+
+    /// Enables the database-wide string pool.
+    ///
+    /// Once enabled, every text value written through `INSERT` or `UPDATE` is resolved
+    /// against the pool first, so identical strings share one allocation even across
+    /// different tables. Enabling it twice is a no-op.
+    pub fn enable_string_pool(&mut self) {
+        self.string_pool.get_or_insert_with(HashSet::default);
+    }
+
+    /// Registers a callback invoked after every successful `INSERT`, `UPDATE` and
+    /// `DELETE`, e.g. to invalidate a cache or emulate a trigger.
+    ///
+    /// Hooks run in registration order. Statements affecting no row are not reported,
+    /// and reported changes are not undone by a `ROLLBACK`.
+    pub fn on_change(&mut self, hook: ChangeHook) {
+        self.change_hooks.push(hook);
+    }
+
+    /// Runs the change hooks for a mutation of `table`.
+    fn notify_change(&mut self, table: &str, kind: ChangeKind, rows: Vec<usize>) {
+        if self.change_hooks.is_empty() || rows.is_empty() {
+            return;
+        }
+        let event = ChangeEvent {
+            table: table.to_string(),
+            kind,
+            rows,
+        };
+        for hook in &mut self.change_hooks {
+            hook(&event);
+        }
+    }
+
+    /// Stores a text column with dictionary encoding, see [Table::dictionary_encode].
+    pub fn dictionary_encode(&mut self, table: &str, column: &str) -> Result<(), String> {
+        self.tables
+            .get_mut(table)
+            .ok_or_else(|| format!("Table {} not found", table))?
+            .dictionary_encode(column)
+    }
+
+    /// Replaces a text value by its pooled `Arc<str>`, registering it if it is new.
+    fn intern_in_pool(pool: &mut HashSet<Arc<str>>, value: &mut Value) {
+        if let Value::Text(s) = value {
+            if let Some(existing) = pool.get(s) {
+                *s = Arc::clone(existing);
+            } else {
+                pool.insert(Arc::clone(s));
+            }
         }
     }
 
     /// Creates a new table in the database.
     ///
     /// # Errors
-    /// Returns an error if a table with the same name already exists.
+    /// Returns an error if a table with the same name already exists, or if the
+    /// schema is malformed (see [Schema::validate]).
     pub fn create_table(&mut self, name: &str, schema: Schema) -> Result<(), String> {
         if self.tables.contains_key(name) {
             return Err(format!("Table {} already exists in the database", name));
         }
+        schema.validate()?;
+        // compiles the CHECK constraints and generated columns, so a wrong one fails here
+        // rather than on insert
+        self.check_rows(&schema, std::iter::empty())?;
+        Self::generated_columns(&schema)?;
         let table = Table::new(name.to_string(), schema);
         self.tables.insert(name.to_string(), table);
         Ok(())
     }
 
+    /// Adds a table assembled outside the engine, e.g. read back from disk, under its
+    /// [Table::name].
+    ///
+    /// Unlike tables built through SQL, its storage may have drifted from its schema, so it
+    /// is checked with [Table::validate] before it is added: a corrupted table is reported
+    /// here instead of causing a panic mid-query. Its `CHECK` constraints and generated
+    /// columns are compiled as in [Database::create_table].
+    ///
+    /// # Errors
+    /// Returns an error if a table with the same name already exists, or if the schema or
+    /// the storage of the table is inconsistent.
+    pub fn load_table(&mut self, table: Table) -> Result<(), String> {
+        if self.tables.contains_key(&table.name) {
+            return Err(format!(
+                "Table {} already exists in the database",
+                table.name
+            ));
+        }
+        table.schema.validate()?;
+        table
+            .validate()
+            .map_err(|err| format!("Cannot load table {}: {}", table.name, err))?;
+        self.check_rows(&table.schema, std::iter::empty())?;
+        Self::generated_columns(&table.schema)?;
+        self.tables.insert(table.name.clone(), table);
+        Ok(())
+    }
+
     /// Removes a table from the database by its name.
     ///
     /// # Errors
@@ -133,11 +426,86 @@ impl<'a> Database {
         }
     }
 
+    /// Removes every row of a table while keeping its schema, in one step instead of
+    /// deleting the rows one by one.
+    ///
+    /// # Errors
+    /// Returns an error if the table does not exist.
+    pub fn truncate_table(&mut self, name: &str) -> Result<(), String> {
+        self.tables
+            .get_mut(name)
+            .ok_or_else(|| format!("Table {} does not exist in the database", name))?
+            .truncate();
+        self.prune_string_pool();
+        Ok(())
+    }
+
+    /// Creates table `dst` as a deep copy of table `src`, schema and data included.
+    ///
+    /// The copy is fully independent: later changes to either table do not affect the other.
+    ///
+    /// # Errors
+    /// Returns an error if `src` does not exist or if `dst` already exists.
+    pub fn copy_table(&mut self, src: &str, dst: &str) -> Result<(), String> {
+        if self.tables.contains_key(dst) {
+            return Err(format!("Table {} already exists in the database", dst));
+        }
+        let mut table = self
+            .tables
+            .get(src)
+            .ok_or_else(|| format!("Table {} does not exist in the database", src))?
+            .clone();
+        table.name = dst.to_string();
+        self.tables.insert(dst.to_string(), table);
+        Ok(())
+    }
+
     /// Retrieves a reference to a table by name.
     pub fn get_table(&self, name: &str) -> Option<&Table> {
         self.tables.get(name)
     }
 
+    /// Returns a hash of the schema and live rows of a table, or `None` if it does not
+    /// exist.
+    ///
+    /// Two tables with the same columns and the same rows get the same hash, whatever
+    /// the order of their rows, deleted rows not yet vacuumed, or the storage of their
+    /// columns (dictionary encoding...). Clients can compare it with a hash computed
+    /// earlier to know whether a table changed without fetching its rows. The hash is
+    /// stable across runs and machines built with the same Rust version, but not
+    /// guaranteed across Rust releases.
+    ///
+    /// # Example
+    /// ```
+    /// # use db::Database;
+    /// let mut db = Database::new();
+    /// db.execute("CREATE TABLE t (id INT)").unwrap();
+    /// let empty = db.table_hash("t").unwrap();
+    /// db.execute("INSERT INTO t VALUES (1)").unwrap();
+    /// assert_ne!(db.table_hash("t"), Some(empty));
+    /// ```
+    pub fn table_hash(&self, table: &str) -> Option<u64> {
+        let table = self.get_table(table)?;
+        let mut hasher = DefaultHasher::new();
+        for col in &table.schema.columns {
+            col.name.hash(&mut hasher);
+            col.data_type.hash(&mut hasher);
+        }
+
+        // rows are hashed one by one and summed, so their order does not matter
+        let mut rows_hash = 0u64;
+        let mut row_count = 0usize;
+        for (_, row) in Self::live_rows(table) {
+            let mut row_hasher = DefaultHasher::new();
+            row.hash(&mut row_hasher);
+            rows_hash = rows_hash.wrapping_add(row_hasher.finish());
+            row_count += 1;
+        }
+        row_count.hash(&mut hasher);
+        rows_hash.hash(&mut hasher);
+        Some(hasher.finish())
+    }
+
     /// Retrieves a mutable reference to a table by name.
     pub fn get_table_mut(&mut self, name: &str) -> Option<&mut Table> {
         self.tables.get_mut(name)
@@ -171,7 +539,58 @@ impl<'a> Database {
     pub fn execute(&mut self, sql: &'a str) -> Result<(), String> {
         let tokens = Tokenizer::new(sql).tokenize()?;
         let statement = Parser::new(tokens).parse()?;
+        self.execute_statement(statement).map(|_| ())
+    }
+
+    /// Executes any SQL statement, returning the rows it produces, if any.
+    ///
+    /// A `SELECT` gives `Some` of its result, like [Database::query]. An `INSERT`,
+    /// `UPDATE` or `DELETE` with a `RETURNING` clause gives `Some` of the affected rows.
+    /// `EXPLAIN SELECT ...` gives the plan of the query (see [Database::explain]) as a
+    /// single `plan` column, one row per line. Every other statement gives `None`.
+    ///
+    /// # Errors
+    /// Returns an error if tokenization, parsing, or execution fails.
+    ///
+    /// # Example
+    /// ```
+    /// use db::{Database, Value};
+    /// let mut db = Database::new();
+    /// db.run("CREATE TABLE users (id INT AUTO_INCREMENT, name TEXT)").unwrap();
+    ///
+    /// let inserted = db
+    ///     .run("INSERT INTO users (name) VALUES ('Alice') RETURNING id")
+    ///     .unwrap()
+    ///     .unwrap();
+    /// assert_eq!(inserted.rows, vec![vec![Value::Int(1)]]);
+    /// ```
+    pub fn run(&mut self, sql: &'a str) -> Result<Option<QueryResult<'a>>, String> {
+        let tokens = Tokenizer::new(sql).tokenize()?;
+        match Parser::new(tokens).parse()? {
+            Statement::Select(select) => self
+                .execute_select(select, &mut QueryStats::default())
+                .map(Some),
+            Statement::Explain(select) => {
+                let plan = self.plan_select(&select)?.to_string();
+                Ok(Some(QueryResult {
+                    columns: vec![Cow::Borrowed("plan")],
+                    column_types: vec![Some(DataType::Text)],
+                    rows: plan
+                        .lines()
+                        .map(|line| vec![Value::Text(line.into())])
+                        .collect(),
+                }))
+            }
+            statement => self.execute_statement(statement),
+        }
+    }
 
+    /// Runs a parsed DDL/DML statement, see [Database::execute] and [Database::run].
+    fn execute_statement(
+        &mut self,
+        statement: Statement<'a>,
+    ) -> Result<Option<QueryResult<'a>>, String> {
+        let mut returned = None;
         match statement {
             Statement::CreateTable(create) => self.create_table(
                 create.name,
@@ -179,18 +598,32 @@ impl<'a> Database {
                     columns: create.columns,
                 },
             )?,
+            Statement::CreateTableAs(create) => {
+                self.create_table_as(create)?;
+            }
+            Statement::AlterTable(alter) => {
+                self.alter_table(alter)?;
+            }
+            Statement::DropTable(drop) => {
+                if !drop.if_exists || self.tables.contains_key(drop.name) {
+                    self.drop_table(drop.name)?;
+                }
+            }
             Statement::InsertInto(insert) => {
-                self.insert(insert)?;
+                returned = self.insert(insert)?;
             }
             Statement::Delete(delete) => {
-                self.delete(delete)?;
+                returned = self.delete(delete)?;
             }
             Statement::Update(update) => {
-                self.update(update)?;
+                returned = self.update(update)?;
             }
             Statement::Vacuum(table) => {
                 self.vacuum(table)?;
             }
+            Statement::Truncate(name) => {
+                self.truncate_table(name)?;
+            }
             Statement::Begin => {
                 self.begin_transaction()?;
             }
@@ -200,6 +633,9 @@ impl<'a> Database {
             Statement::Rollback => {
                 self.rollback_transaction()?;
             }
+            Statement::Set(set) => {
+                self.set_variable(set)?;
+            }
             _ => {
                 return Err(format!(
                     "Statement {:?} is not an executable statement",
@@ -207,100 +643,534 @@ impl<'a> Database {
                 ));
             }
         };
+        Ok(returned)
+    }
+
+    /// Executes a `SET name = value` statement, updating [Database::comparison_config].
+    ///
+    /// Supported settings are `float_epsilon`, a non-negative number, and the booleans
+    /// `case_sensitive` and `deterministic_order`.
+    ///
+    /// # Errors
+    /// Returns an error for an unknown setting or a value of the wrong type.
+    fn set_variable(&mut self, set: SetVariable) -> Result<(), String> {
+        let config = &mut self.comparison_config;
+        match (set.name.to_ascii_lowercase().as_str(), set.value) {
+            ("float_epsilon", value @ (Value::Int(_) | Value::UInt(_) | Value::Float(_))) => {
+                let epsilon = match value {
+                    Value::Int(v) => v as f64,
+                    Value::UInt(v) => v as f64,
+                    Value::Float(v) => v,
+                    _ => unreachable!(),
+                };
+                if !(epsilon >= 0.0 && epsilon.is_finite()) {
+                    return Err(format!(
+                        "float_epsilon must be a non-negative number, got {epsilon}"
+                    ));
+                }
+                config.float_epsilon = epsilon;
+            }
+            ("case_sensitive", Value::Bool(case_sensitive)) => {
+                config.case_sensitive = case_sensitive;
+            }
+            ("deterministic_order", Value::Bool(deterministic_order)) => {
+                config.deterministic_order = deterministic_order;
+            }
+            ("float_epsilon" | "case_sensitive" | "deterministic_order", value) => {
+                return Err(format!("Invalid value {value:?} for setting {}", set.name));
+            }
+            _ => return Err(format!("Unknown setting '{}'", set.name)),
+        }
+        Ok(())
+    }
+
+    /// Executes a `CREATE TABLE ... AS SELECT` statement.
+    ///
+    /// The query runs first; its result column names and types become the schema of
+    /// the new table, which is then filled with the result rows.
+    ///
+    /// # Errors
+    /// Returns an error if the query fails, if a result column has no inferable type,
+    /// or if the resulting schema is invalid (e.g. duplicate column names).
+    fn create_table_as(&mut self, create: CreateTableAs<'a>) -> Result<(), String> {
+        let result = self.execute_select(create.query, &mut QueryStats::default())?;
+
+        let columns = result
+            .columns
+            .iter()
+            .zip(&result.column_types)
+            .map(|(name, data_type)| {
+                data_type
+                    .map(|data_type| ColumnDef::new(name.as_ref(), data_type))
+                    .ok_or_else(|| format!("Cannot infer the type of column {}", name))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        self.create_table(create.name, Schema { columns })?;
+
+        let table = self
+            .tables
+            .get_mut(create.name)
+            .expect("table was just created");
+        for mut row in result.rows {
+            if let Some(pool) = self.string_pool.as_mut() {
+                row.iter_mut()
+                    .for_each(|value| Self::intern_in_pool(pool, value));
+            }
+            table.insert(row)?;
+        }
+        let rows = (0..table.row_count).collect();
+        self.notify_change(create.name, ChangeKind::Insert, rows);
         Ok(())
     }
 
+    /// Executes an `ALTER TABLE` statement.
+    ///
+    /// A column is only dropped if the `CHECK` constraints and generated columns of the
+    /// remaining columns do not reference it.
+    ///
+    /// # Errors
+    /// Returns an error if the table or the column does not exist, if the column is the
+    /// last one of the table, or if another column still depends on it.
+    fn alter_table(&mut self, alter: AlterTable<'a>) -> Result<(), String> {
+        let table = self
+            .tables
+            .get(alter.table)
+            .ok_or_else(|| format!("Table {:?} does not exist", alter.table))?;
+        match alter.action {
+            AlterAction::DropColumn(column) => {
+                let mut remaining = table.schema.clone();
+                remaining.columns.retain(|col| col.name != column);
+                // a missing or last column is reported by Table::drop_column
+                if !remaining.columns.is_empty()
+                    && remaining.columns.len() < table.schema.columns.len()
+                {
+                    self.check_rows(&remaining, std::iter::empty())
+                        .and_then(|()| Self::generated_columns(&remaining).map(drop))
+                        .map_err(|err| format!("Cannot drop column {:?}: {}", column, err))?;
+                }
+                self.tables
+                    .get_mut(alter.table)
+                    .expect("table was found above")
+                    .drop_column(column)
+            }
+        }
+    }
+
     /// Internal helper to handle row insertion logic.
     ///
-    /// It maps provided values to the correct columns, handling cases where:
+    /// It maps the values of each `VALUES` tuple to the correct columns, handling cases
+    /// where:
     /// 1. Columns are not specified (positional insertion).
     /// 2. Columns are specified in a different order than the schema.
     /// 3. Some columns are missing (filling them with `NULL`).
-    fn insert(&mut self, insert: InsertInto) -> Result<(), String> {
+    ///
+    /// Every row is built and validated before the first one is stored, so a failing
+    /// insert never touches the table, even when only one tuple is invalid. Values are
+    /// coerced to their column type (see [Value::coerce_to]), so `5` can be stored in a
+    /// `FLOAT` or `UINT` column.
+    ///
+    /// Returns the inserted rows projected on the `RETURNING` clause, if any. The clause
+    /// is evaluated before the rows are stored, so a failing expression inserts nothing.
+    fn insert(&mut self, insert: InsertInto<'a>) -> Result<Option<QueryResult<'a>>, String> {
         let table = self
-            .get_table_mut(insert.table)
+            .tables
+            .get(insert.table)
             .ok_or_else(|| format!("Table {:?} does not exist", insert.table))?;
 
-        let values = match insert.columns {
-            None => insert.values,
-            Some(columns) => {
-                // Validate that all specified columns exist in the schema
-                for col_name in &columns {
-                    if !table.schema.columns.iter().any(|c| &c.name == col_name) {
-                        return Err(format!(
-                            "Column {:?} does not exist in table {:?}",
-                            col_name, insert.table
-                        ));
+        let generated = Self::generated_columns(&table.schema)?;
+        if let Some(columns) = &insert.columns {
+            for values in &insert.values {
+                Self::validate_insert_columns(&table.schema, insert.table, columns, values.len())?;
+            }
+            if let Some(col) = columns.iter().find(|col| {
+                generated
+                    .iter()
+                    .any(|(idx, _)| table.schema.columns[*idx].name == **col)
+            }) {
+                return Err(format!("Cannot write to generated column {:?}", col));
+            }
+        }
+
+        let mut rows = insert
+            .values
+            .into_iter()
+            .map(|values| {
+                Self::insert_row(&table.schema, &generated, insert.columns.as_deref(), values)
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        for row in &rows {
+            table.validate_row(row)?;
+        }
+        self.check_rows(&table.schema, rows.iter().map(Vec::as_slice))?;
+        // RETURNING is projected before the first row is stored, so its failure leaves
+        // the table untouched
+        let returned = insert
+            .returning
+            .map(|returning| {
+                let mut returned = rows.clone();
+                table.assign_auto_ids(&mut returned);
+                self.project(&returning, returned, &[insert.table], &table.schema)
+            })
+            .transpose()?;
+
+        if let Some(pool) = self.string_pool.as_mut() {
+            rows.iter_mut()
+                .flatten()
+                .for_each(|value| Self::intern_in_pool(pool, value));
+        }
+        let table = self
+            .tables
+            .get_mut(insert.table)
+            .expect("table was found above");
+        let inserted = rows
+            .into_iter()
+            .map(|row| table.insert(row))
+            .collect::<Result<Vec<_>, String>>()?;
+        self.notify_change(insert.table, ChangeKind::Insert, inserted);
+        Ok(returned)
+    }
+
+    /// Builds the full row stored for one `VALUES` tuple of an `INSERT`: values in schema
+    /// order, missing columns set to their default (or `NULL`), literals coerced to
+    /// their column type and generated columns computed. A positional list may leave out
+    /// trailing columns only if all of them have a default.
+    ///
+    /// # Errors
+    /// Returns an error if a value is written to a generated column, or if a generated
+    /// expression fails.
+    fn insert_row(
+        schema: &Schema,
+        generated: &[(usize, ScalarExpr)],
+        columns: Option<&[&str]>,
+        mut values: Vec<Value>,
+    ) -> Result<Vec<Value>, String> {
+        let mut values: Vec<Value> = match columns {
+            None => {
+                // generated columns may be left out of a positional VALUES list
+                if !generated.is_empty() && values.len() + generated.len() == schema.columns.len() {
+                    for (idx, _) in generated {
+                        values.insert(*idx, Value::Null);
                     }
                 }
-
+                // so may trailing columns that all have a default
+                let omitted = schema.columns.get(values.len()..).unwrap_or_default();
+                if !omitted.is_empty() && omitted.iter().all(|col| col.default.is_some()) {
+                    for default in omitted.iter().filter_map(|col| col.default.as_ref()) {
+                        values.push(default.evaluate());
+                    }
+                }
+                values
+            }
+            Some(columns) => {
                 // Map provided values to their column names
                 let mut provided_values: HashMap<&str, Value> =
-                    columns.into_iter().zip(insert.values).collect();
+                    columns.iter().copied().zip(values).collect();
 
                 // Build the final row by following the schema's column order
-                table
-                    .schema
+                schema
                     .columns
                     .iter()
                     .map(|col| {
                         provided_values
                             .remove(col.name.as_str())
+                            .or_else(|| col.default.as_ref().map(ColumnDefault::evaluate))
                             .unwrap_or(Value::Null)
                     })
                     .collect()
             }
         };
 
-        table.insert(values)
+        for (value, col) in values.iter_mut().zip(&schema.columns) {
+            Self::coerce_literal(value, Some(col.data_type));
+        }
+        for (idx, _) in generated {
+            if values.get(*idx).is_some_and(|value| *value != Value::Null) {
+                return Err(format!(
+                    "Cannot write to generated column {:?}",
+                    schema.columns[*idx].name
+                ));
+            }
+        }
+        // a row of the wrong size is reported by Table::validate_row
+        if values.len() == schema.columns.len() {
+            Self::fill_generated(generated, &mut values, schema)?;
+        }
+        Ok(values)
     }
 
-    /// A generic helper function to filter rows within a table.
-    ///
-    /// This method performs the following operations:
-    /// 1. Pivots the data from column-oriented format to row-oriented format.
-    /// 2. Evaluates the provided `WHERE` clause for every row.
-    /// 3. Applies a mapping function (`map_fn`) to rows that satisfy the condition.
+    /// Parses the expressions of the generated columns of `schema` ([ColumnDef::generated]),
+    /// each with the index of its column.
     ///
-    /// # Arguments
-    /// * `table` - A reference to the table to be scanned.
-    /// * `where_clause` - An optional expression used to filter rows.
-    /// * `map_fn` - A closure that determines what data to collect for each matching row
-    ///   (e.g., the row's index or the row's values).
-    fn filter_rows<T, F>(
-        &self,
-        table: &Table,
-        where_clause: Option<&Expr>,
-        mut map_fn: F,
-    ) -> Result<Vec<T>, String>
-    where
-        F: FnMut(usize, &Vec<Value>) -> T,
-    {
-        let columns: Vec<&Column> = table
-            .schema
-            .columns
-            .iter()
-            .map(|col_def| table.get_col(&col_def.name).unwrap())
-            .collect();
-        // Pivot: transform the data from Column-oriented (Vec of Columns)
-        // to Row-oriented (Vec of Rows) for the final result.
-        let row_count = columns[0].len();
-        let mut results = Vec::new();
-
-        for i in 0..row_count {
-            // Check if the row is marked as deleted.
-            if table.deletion_vector.get(i).as_deref() == Some(&true) {
-                continue;
+    /// # Errors
+    /// Returns an error if an expression does not parse, references a column that does
+    /// not exist or is itself generated, or produces values that do not fit its column
+    /// (an `INT` expression fits a `FLOAT` or `UINT` column, as literals do).
+    fn generated_columns(schema: &Schema) -> Result<Vec<(usize, ScalarExpr<'_>)>, String> {
+        let mut generated = Vec::new();
+        for (idx, col) in schema.columns.iter().enumerate() {
+            if let Some(sql) = &col.generated {
+                let context = |err: String| {
+                    format!(
+                        "GENERATED ALWAYS AS ({}) on column {}: {}",
+                        sql, col.name, err
+                    )
+                };
+                let tokens = Tokenizer::new(sql).tokenize().map_err(context)?;
+                let expr = Parser::new(tokens).parse_scalar().map_err(context)?;
+                for name in expr.columns() {
+                    let referenced = schema.index_of(name).map_err(context)?;
+                    if schema.columns[referenced].generated.is_some() {
+                        return Err(context(format!(
+                            "cannot reference the generated column {}",
+                            name
+                        )));
+                    }
+                }
+                // an expression of the wrong type would fail every insert
+                match Self::scalar_type(&expr, schema) {
+                    Some(ty)
+                        if ty != col.data_type
+                            && !(ty == DataType::Int
+                                && matches!(col.data_type, DataType::Float | DataType::UInt)) =>
+                    {
+                        return Err(context(format!(
+                            "expression of type {:?} does not match the column type {:?}",
+                            ty, col.data_type
+                        )));
+                    }
+                    None if matches!(expr, ScalarExpr::Binary { .. }) => {
+                        return Err(context("arithmetic needs numeric operands".into()));
+                    }
+                    _ => {}
+                }
+                generated.push((idx, expr));
             }
+        }
+        Ok(generated)
+    }
 
-            let full_row: Vec<Value> = columns
-                .iter()
-                .map(|col| col.get(i).unwrap_or(Value::Null))
-                .collect();
+    /// Stores in a full `row` the value of each `generated` column, computed from the
+    /// other values of the row.
+    fn fill_generated(
+        generated: &[(usize, ScalarExpr)],
+        row: &mut [Value],
+        schema: &Schema,
+    ) -> Result<(), String> {
+        for (idx, expr) in generated {
+            let col = &schema.columns[*idx];
+            let mut value = Self::evaluate_scalar(expr, row, schema)
+                .map_err(|err| format!("Generated column {}: {}", col.name, err))?;
+            Self::coerce_literal(&mut value, Some(col.data_type));
+            row[*idx] = value;
+        }
+        Ok(())
+    }
 
+    /// Checks full rows of a table with `schema` against the `CHECK` constraints of its
+    /// columns ([ColumnDef::check]).
+    ///
+    /// As in SQL, a constraint does not reject a row where the column it is declared on
+    /// is `NULL`.
+    ///
+    /// The constraints are parsed and compiled once per call, and a statement passes all
+    /// its rows in one call, so their cost does not grow with the number of rows.
+    ///
+    /// # Errors
+    /// Returns an error naming the first violated constraint, or if a constraint does
+    /// not parse or does not fit the schema.
+    fn check_rows<'r>(
+        &self,
+        schema: &Schema,
+        rows: impl IntoIterator<Item = &'r [Value]>,
+    ) -> Result<(), String> {
+        let mut conditions = Vec::new();
+        for (idx, col) in schema.columns.iter().enumerate() {
+            if let Some(check) = &col.check {
+                let context =
+                    |err: String| format!("CHECK ({}) on column {}: {}", check, col.name, err);
+                let tokens = Tokenizer::new(check).tokenize().map_err(context)?;
+                let mut condition = Parser::new(tokens).parse_condition().map_err(context)?;
+                if Self::has_subquery(&condition) {
+                    return Err(context("subqueries are not allowed".into()));
+                }
+                self.bind_expression(&mut condition, schema, None);
+                conditions.push((idx, col, check, condition));
+            }
+        }
+        let predicates = conditions
+            .iter()
+            .map(|(idx, col, check, condition)| {
+                let predicate = self
+                    .compile_expr(condition, schema)
+                    .map_err(|err| format!("CHECK ({}) on column {}: {}", check, col.name, err))?;
+                Ok((*idx, col, check, predicate))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        for row in rows {
+            for (idx, col, check, predicate) in &predicates {
+                if !row[*idx].is_null() && !predicate(row)? {
+                    return Err(format!(
+                        "Row violates CHECK ({}) on column {}",
+                        check, col.name
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns `true` if `expr` contains a subquery.
+    fn has_subquery(expr: &Expr) -> bool {
+        match expr {
+            Expr::Subquery { .. } => true,
+            Expr::And { left, right } | Expr::Or { left, right } => {
+                Self::has_subquery(left) || Self::has_subquery(right)
+            }
+            Expr::Not(inner) => Self::has_subquery(inner),
+            _ => false,
+        }
+    }
+
+    /// Validates the explicit column list of an `INSERT` against the target schema.
+    ///
+    /// # Errors
+    /// Returns an error if a column does not exist, if a column is listed twice,
+    /// or if the number of values does not match the number of columns.
+    fn validate_insert_columns(
+        schema: &Schema,
+        table: &str,
+        columns: &[&str],
+        value_count: usize,
+    ) -> Result<(), String> {
+        for (i, col_name) in columns.iter().enumerate() {
+            if !schema.columns.iter().any(|c| &c.name == col_name) {
+                return Err(format!(
+                    "Column {:?} does not exist in table {:?}",
+                    col_name, table
+                ));
+            }
+            if columns[..i].contains(col_name) {
+                return Err(format!("Column {:?} is specified more than once", col_name));
+            }
+        }
+
+        if columns.len() != value_count {
+            return Err(format!(
+                "INSERT has {} columns but {} values",
+                columns.len(),
+                value_count
+            ));
+        }
+        Ok(())
+    }
+
+    /// Lazily pivots the live rows of `table` from columns to rows.
+    ///
+    /// Yields each row index with the row values in schema order, skipping rows marked
+    /// in the deletion vector. Rows are only built as the iterator is advanced, so a
+    /// caller can stop scanning early.
+    fn live_rows(table: &Table) -> impl Iterator<Item = (usize, Vec<Value>)> + '_ {
+        Self::pivot_rows(table, 0..table.row_count)
+    }
+
+    /// Lazily pivots the live rows of `table` that may satisfy `where_clause`.
+    ///
+    /// A clause made of a single `IS [NOT] NULL` test is answered from the null bitmap of
+    /// its column ([Column::null_indices]), and a single `col = 'text'` on a dictionary
+    /// encoded column from its codes ([Column::rows_equal_to]), so only the matching rows
+    /// are pivoted. The codes only match exact strings, so the latter is skipped when
+    /// [ComparisonConfig::case_sensitive] is off. Any other clause yields every live row,
+    /// as [Self::live_rows] does.
+    fn candidate_rows<'t>(
+        &self,
+        table: &'t Table,
+        where_clause: Option<&Expr>,
+    ) -> Box<dyn Iterator<Item = (usize, Vec<Value>)> + 't> {
+        if let Some(Expr::IsNull { column, negated }) = where_clause
+            && let Some(col) = table.get_col(column)
+        {
+            let rows = if *negated {
+                col.non_null_indices()
+            } else {
+                col.null_indices()
+            };
+            return Box::new(Self::pivot_rows(table, rows.into_iter()));
+        }
+        if let Some(Expr::Comparison {
+            column,
+            op: ComparisonOp::Eq,
+            value: value @ Value::Text(_),
+        }) = where_clause
+            && self.comparison_config.case_sensitive
+            && let Some(col) = table.get_col(column)
+            && col.is_dictionary_encoded()
+        {
+            return Box::new(Self::pivot_rows(
+                table,
+                col.rows_equal_to(value).into_iter(),
+            ));
+        }
+        Box::new(Self::live_rows(table))
+    }
+
+    /// Pivots the rows of `table` at `indices`, skipping those marked as deleted.
+    fn pivot_rows<'t>(
+        table: &'t Table,
+        indices: impl Iterator<Item = usize> + 't,
+    ) -> impl Iterator<Item = (usize, Vec<Value>)> + 't {
+        let columns: Vec<&Column> = table
+            .schema
+            .columns
+            .iter()
+            .map(|col_def| table.get_col(&col_def.name).unwrap())
+            .collect();
+        indices
+            .filter(move |&i| !table.deletion_vector[i])
+            .map(move |i| {
+                let row = columns
+                    .iter()
+                    .map(|col| col.get(i).unwrap_or(Value::Null))
+                    .collect();
+                (i, row)
+            })
+    }
+
+    /// A generic helper function to filter rows within a table.
+    ///
+    /// This method performs the following operations:
+    /// 1. Pivots the data from column-oriented format to row-oriented format.
+    /// 2. Evaluates the provided `WHERE` clause for every row.
+    /// 3. Applies a mapping function (`map_fn`) to rows that satisfy the condition.
+    ///
+    /// # Arguments
+    /// * `table` - A reference to the table to be scanned.
+    /// * `where_clause` - An optional expression used to filter rows.
+    /// * `map_fn` - A closure that determines what data to collect for each matching row
+    ///   (e.g., the row's index or the row's values).
+    /// * `limit` - If set, the scan stops once this many rows matched.
+    fn filter_rows<T, F>(
+        &self,
+        table: &Table,
+        where_clause: Option<&Expr>,
+        limit: Option<usize>,
+        mut map_fn: F,
+    ) -> Result<Vec<T>, String>
+    where
+        F: FnMut(usize, &Vec<Value>) -> T,
+    {
+        let mut results = Vec::new();
+        let predicate = where_clause
+            .map(|expr| self.compile_expr(expr, &table.schema))
+            .transpose()?;
+
+        for (i, full_row) in self.candidate_rows(table, where_clause) {
+            if limit.is_some_and(|limit| results.len() >= limit) {
+                break;
+            }
             // TODO: evaluate where before construct all rows.
-            let should_include = match where_clause {
-                Some(expr) => self.evaluate_expr(expr, &full_row, &table.schema)?,
+            let should_include = match &predicate {
+                Some(predicate) => predicate(&full_row)?,
                 None => true,
             };
 
@@ -315,48 +1185,52 @@ impl<'a> Database {
     /// Executes a `DELETE` statement.
     ///
     /// Deletion is performed in two phases:
-    /// 1. Identification: It finds the indices of all rows matching the `WHERE` clause.
-    /// 2. Removal: It removes those rows from the table storage.
-    ///
-    /// Note: Indices are sorted in descending order before deletion. This ensures that
-    /// removing a row doesn't shift the positions of other rows that are still
-    /// scheduled for deletion.
+    /// 1. Identification: It finds the indices of all rows matching the `WHERE` clause,
+    ///    stopping after the first `LIMIT` matches if there is one.
+    /// 2. Removal: It marks those rows as deleted in the table's deletion vector.
     ///
     /// # Errors
     /// Returns an error if the table is not found or if the `WHERE` clause contains
     /// invalid column names or type mismatches.
-    fn delete(&mut self, mut delete: Delete) -> Result<(), String> {
-        self.bind_expression(
-            &mut delete.where_clause,
-            self.get_table(delete.table)
-                .ok_or_else(|| format!("table {:?} does not exist", delete.table))?,
-        );
+    fn delete(&mut self, mut delete: Delete<'a>) -> Result<Option<QueryResult<'a>>, String> {
+        self.resolve_subqueries(&mut delete.where_clause)?;
+        let table = self
+            .get_table(delete.table)
+            .ok_or_else(|| format!("table {:?} does not exist", delete.table))?;
+        self.bind_expression(&mut delete.where_clause, &table.schema, Some(table));
 
-        let rows_to_delete = {
+        let (rows_to_delete, returned) = {
             let table = self
                 .get_table(delete.table)
                 .ok_or_else(|| format!("table {:?} does not exist", delete.table))?;
 
-            self.filter_rows(table, Some(&delete.where_clause), |i, _| i)?
+            let rows =
+                self.filter_rows(table, Some(&delete.where_clause), delete.limit, |i, _| i)?;
+            // deleted rows can no longer be read, so they are projected beforehand
+            let returned = delete
+                .returning
+                .map(|returning| {
+                    let deleted = rows.iter().filter_map(|&i| table.get_row(i)).collect();
+                    self.project(&returning, deleted, &[delete.table], &table.schema)
+                })
+                .transpose()?;
+            (rows, returned)
         };
 
         let table = self
             .get_table_mut(delete.table)
             .ok_or_else(|| format!("table {:?} does not exist", delete.table))?;
 
-        let mut rows = rows_to_delete;
-        // use sort_unstable_by because rows indexes are unique and so we don't care about egality
-        // order.
-        rows.sort_unstable_by(|a, b| b.cmp(a));
-
-        for index in rows {
+        // deletion is logical, so marking a row never shifts the others
+        for &index in &rows_to_delete {
             table.delete_row(index)?;
         }
+        self.notify_change(delete.table, ChangeKind::Delete, rows_to_delete);
 
         // Auto-Vacuum phase (Physical compaction)
         self.maybe_auto_vacuum(delete.table)?;
 
-        Ok(())
+        Ok(returned)
     }
 
     /// Executes an `UPDATE` statement to modify existing rows in a table.
@@ -366,30 +1240,134 @@ impl<'a> Database {
     /// 2. **Modification**: For every column assignment, it updates the values at the identified
     ///    indices in the columnar storage.
     ///
+    /// Literal assignments are written to every matching row at once. Expressions
+    /// (`hits = hits + 1`) and generated columns are computed for each row from its values
+    /// before the update, and all of them are computed and checked before the first row
+    /// changes, so a failing expression leaves the table untouched. The `RETURNING` clause
+    /// is projected over the new rows at the same stage.
+    ///
     /// # Errors
     /// Returns an error string if:
     /// - The target table does not exist.
-    /// - One of the target columns does not exist.
+    /// - One of the target or referenced columns does not exist.
     /// - The provided value's type does not match the column's data type.
+    /// - An expression fails (division by zero, non-numeric operand, overflow).
     /// - The `WHERE` clause evaluation fails.
-    fn update(&mut self, mut update: Update) -> Result<(), String> {
-        self.bind_expression(
-            &mut update.where_clause,
-            self.get_table(update.table)
-                .ok_or_else(|| format!("table {:?} does not exist", update.table))?,
-        );
+    fn update(&mut self, mut update: Update<'a>) -> Result<Option<QueryResult<'a>>, String> {
+        self.resolve_subqueries(&mut update.where_clause)?;
+        let table = self
+            .get_table(update.table)
+            .ok_or_else(|| format!("table {:?} does not exist", update.table))?;
+        self.bind_expression(&mut update.where_clause, &table.schema, Some(table));
 
-        let rows_to_update = {
+        let (rows_to_update, mut literals, mut computed_values, returned) = {
             let table = self
                 .get_table(update.table)
                 .ok_or_else(|| format!("table {:?} does not exist", update.table))?;
-            self.filter_rows(table, Some(&update.where_clause), |i, _| i)?
+            let schema = &table.schema;
+            for (column, expr) in update.assignments.iter_mut() {
+                let data_type = schema.data_type_of(column).ok_or_else(|| {
+                    format!("column {:?} is not a column from this table", column)
+                })?;
+                for name in expr.columns() {
+                    schema.index_of(name)?;
+                }
+                if let ScalarExpr::Literal(value) = expr {
+                    Self::coerce_literal(value, Some(data_type));
+                }
+            }
+            let generated = Self::generated_columns(schema)?;
+            for (idx, _) in &generated {
+                let name = schema.columns[*idx].name.as_str();
+                if update.assignments.contains_key(name) {
+                    return Err(format!("Cannot write to generated column {:?}", name));
+                }
+            }
+            let rows = self.filter_rows(table, Some(&update.where_clause), None, |i, _| i)?;
+
+            // literals are the same for every row; expressions and generated columns are
+            // computed per row
+            let mut literals = HashMap::new();
+            let mut computed = Vec::new();
+            for (column, expr) in &update.assignments {
+                let idx = schema.index_of(column)?;
+                match expr {
+                    ScalarExpr::Literal(value) => {
+                        literals.insert(*column, value.clone());
+                    }
+                    expr => computed.push((idx, Some(expr))),
+                }
+            }
+            computed.extend(generated.iter().map(|(idx, _)| (*idx, None)));
+
+            // every updated row must still satisfy the CHECK constraints, and RETURNING
+            // reads the new rows before they are written
+            let mut updated: Vec<(usize, Vec<Value>)> = Vec::new();
+            if !computed.is_empty()
+                || schema.columns.iter().any(|col| col.check.is_some())
+                || update.returning.is_some()
+            {
+                for &i in &rows {
+                    let Some(old) = table.get_row(i) else {
+                        continue;
+                    };
+                    let mut row = old.clone();
+                    for (column, value) in &literals {
+                        row[schema.index_of(column)?] = value.clone();
+                    }
+                    for (idx, expr) in &computed {
+                        if let Some(expr) = expr {
+                            let mut value = Self::evaluate_scalar(expr, &old, schema)?;
+                            Self::coerce_literal(&mut value, Some(schema.columns[*idx].data_type));
+                            row[*idx] = value;
+                        }
+                    }
+                    Self::fill_generated(&generated, &mut row, schema)?;
+                    table.validate_row(&row)?;
+                    updated.push((i, row));
+                }
+                self.check_rows(schema, updated.iter().map(|(_, row)| row.as_slice()))?;
+            }
+            let returned = update
+                .returning
+                .map(|returning| {
+                    let rows = updated.iter().map(|(_, row)| row.clone()).collect();
+                    self.project(&returning, rows, &[update.table], schema)
+                })
+                .transpose()?;
+            // computed values are grouped by column, so each column is written once
+            let computed_values: Vec<(String, Vec<(usize, Value)>)> = computed
+                .iter()
+                .map(|(idx, _)| {
+                    let values = updated
+                        .iter_mut()
+                        .map(|(i, row)| (*i, std::mem::replace(&mut row[*idx], Value::Null)))
+                        .collect();
+                    (schema.columns[*idx].name.clone(), values)
+                })
+                .collect();
+            (rows, literals, computed_values, returned)
         };
+        if let Some(pool) = self.string_pool.as_mut() {
+            literals
+                .values_mut()
+                .chain(
+                    computed_values
+                        .iter_mut()
+                        .flat_map(|(_, values)| values.iter_mut().map(|(_, value)| value)),
+                )
+                .for_each(|value| Self::intern_in_pool(pool, value));
+        }
+
         let table = self
             .get_table_mut(update.table)
             .ok_or_else(|| format!("table {:?} does not exist", update.table))?;
-        table.update(&rows_to_update, update.assignments)?;
-        Ok(())
+        table.update(&rows_to_update, literals)?;
+        for (column, values) in computed_values {
+            table.update_rows(&column, values)?;
+        }
+        self.notify_change(update.table, ChangeKind::Update, rows_to_update);
+        Ok(returned)
     }
 
     /// Executes a `VACUUM` operation on one or all tables.
@@ -438,14 +1416,33 @@ impl<'a> Database {
                 .get_table_mut(selected_table)
                 .ok_or_else(|| format!("Table {} is not present in database", selected_table))?;
             selected_table.vacuum()?;
-            Ok(())
         } else {
             // exec vacuum on all tables
             for table in self.tables.values_mut() {
                 table.vacuum()?;
             }
-            Ok(())
         }
+        self.prune_string_pool();
+        Ok(())
+    }
+
+    /// Releases pooled strings that are no longer stored in any column.
+    ///
+    /// A pooled string is referenced by the pool itself and by the interner of every
+    /// table that has seen it; anything above that count means a column still uses it.
+    /// Table interners drop their own stale entries on their next vacuum.
+    fn prune_string_pool(&mut self) {
+        let Some(pool) = self.string_pool.as_mut() else {
+            return;
+        };
+        let tables = &self.tables;
+        pool.retain(|s| {
+            let interners = tables
+                .values()
+                .filter(|t| t.lookup_string(s).is_some_and(|i| Arc::ptr_eq(&i, s)))
+                .count();
+            Arc::strong_count(s) > 1 + interners
+        });
     }
 
     /// Executes a `SELECT` query and returns the resulting data set.
@@ -484,6 +1481,57 @@ impl<'a> Database {
     /// - The specified table does not exist.
     /// - One or more specified columns do not exist.
     pub fn query(&self, sql: &'a str) -> Result<QueryResult<'a>, String> {
+        self.query_with_stats(sql).map(|(result, _)| result)
+    }
+
+    /// Executes a `SELECT` expected to return a single value, such as a count, and
+    /// returns that value.
+    ///
+    /// # Example
+    /// ```
+    /// use db::{Database, Value};
+    ///
+    /// let mut db = Database::new();
+    /// db.execute("CREATE TABLE t (n INT)").unwrap();
+    /// db.execute("INSERT INTO t VALUES (4)").unwrap();
+    /// assert_eq!(db.query_scalar("SELECT COUNT(*) FROM t"), Ok(Value::Int(1)));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the query fails like [Self::query], or if its result is not
+    /// exactly one row of one column.
+    pub fn query_scalar(&self, sql: &'a str) -> Result<Value, String> {
+        let result = self.query(sql)?;
+        if result.columns.len() != 1 || result.rows.len() != 1 {
+            return Err(format!(
+                "Expected a single value, got {} row(s) of {} column(s)",
+                result.rows.len(),
+                result.columns.len()
+            ));
+        }
+        let mut rows = result.rows;
+        Ok(rows.swap_remove(0).swap_remove(0))
+    }
+
+    /// Executes a `SELECT` like [Self::query], also returning how many rows were
+    /// scanned, matched by the `WHERE` clause and finally returned.
+    ///
+    /// # Example
+    /// ```
+    /// use db::Database;
+    ///
+    /// let mut db = Database::new();
+    /// db.execute("CREATE TABLE t (n INT)").unwrap();
+    /// db.execute("INSERT INTO t VALUES (1)").unwrap();
+    /// db.execute("INSERT INTO t VALUES (2)").unwrap();
+    ///
+    /// let (_, stats) = db.query_with_stats("SELECT n FROM t WHERE n > 1").unwrap();
+    /// assert_eq!((stats.rows_scanned, stats.rows_matched), (2, 1));
+    /// ```
+    ///
+    /// # Errors
+    /// Same as [Self::query].
+    pub fn query_with_stats(&self, sql: &'a str) -> Result<(QueryResult<'a>, QueryStats), String> {
         let tokens = Tokenizer::new(sql).tokenize()?;
         let statement = Parser::new(tokens).parse()?;
 
@@ -494,34 +1542,313 @@ impl<'a> Database {
             ));
         }
 
-        let Statement::Select(mut select) = statement else {
+        let Statement::Select(select) = statement else {
             unreachable!()
         };
 
+        let mut stats = QueryStats::default();
+        let result = self.execute_select(select, &mut stats)?;
+        stats.rows_returned = result.rows.len();
+        Ok((result, stats))
+    }
+
+    /// Describes how a `SELECT` would be executed, without reading any row.
+    ///
+    /// `sql` may start with `EXPLAIN` or not. The plan is a tree of [PlanNode]s, with
+    /// the table scan at the leaves and the `LIMIT` (if any) at the root.
+    ///
+    /// # Example
+    /// ```
+    /// use db::{Database, plan::PlanNode};
+    ///
+    /// let mut db = Database::new();
+    /// db.execute("CREATE TABLE t (n INT)").unwrap();
+    ///
+    /// let plan = db.explain_plan("SELECT n FROM t WHERE n > 1").unwrap();
+    /// let PlanNode::Filter { condition, input } = plan else {
+    ///     panic!("expected a Filter");
+    /// };
+    /// assert_eq!(condition, "n > 1");
+    /// assert!(matches!(*input, PlanNode::Scan { .. }));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the statement is not a `SELECT` or names an unknown table.
+    pub fn explain_plan(&self, sql: &'a str) -> Result<PlanNode, String> {
+        let tokens = Tokenizer::new(sql).tokenize()?;
+        match Parser::new(tokens).parse()? {
+            Statement::Select(select) | Statement::Explain(select) => self.plan_select(&select),
+            statement => Err(format!(
+                "Statement {:?} is not a queryable statement",
+                statement
+            )),
+        }
+    }
+
+    /// Returns the plan of [Database::explain_plan] as text, one node per line.
+    ///
+    /// # Errors
+    /// Same as [Database::explain_plan].
+    pub fn explain(&self, sql: &'a str) -> Result<String, String> {
+        self.explain_plan(sql).map(|plan| plan.to_string())
+    }
+
+    /// Builds the plan of a parsed `SELECT`, following the steps of
+    /// [Self::execute_select].
+    fn plan_select(&self, select: &Select<'a>) -> Result<PlanNode, String> {
+        let scan = |table: &str, alias: Option<&str>| -> Result<PlanNode, String> {
+            self.get_table(table)
+                .ok_or_else(|| format!("table {:?} does not exist", table))?;
+            Ok(PlanNode::Scan {
+                table: table.to_string(),
+                alias: alias.map(str::to_string),
+            })
+        };
+        let mut plan = scan(select.table, select.alias)?;
+        if let Some(join) = &select.join {
+            plan = PlanNode::Join {
+                kind: join.kind,
+                on: (join.on.0.to_string(), join.on.1.to_string()),
+                left: Box::new(plan),
+                right: Box::new(scan(join.table, join.alias)?),
+            };
+        }
+        if let Some(sample) = select.sample {
+            plan = PlanNode::Sample {
+                percent: sample.percent,
+                seed: sample.seed,
+                input: Box::new(plan),
+            };
+        }
+        if let Some(condition) = &select.where_clause {
+            plan = PlanNode::Filter {
+                condition: condition.to_string(),
+                input: Box::new(plan),
+            };
+        }
+        if let ColumnsSelect::Items(items) = &select.columns
+            && (select.having.is_some()
+                || items
+                    .iter()
+                    .any(|i| matches!(i, SelectItem::Aggregate { .. })))
+        {
+            plan = PlanNode::Aggregate {
+                group_by: match &select.group_by {
+                    Some(cols) if select.rollup => vec![format!("ROLLUP({})", cols.join(", "))],
+                    cols => cols.iter().flatten().map(|col| col.to_string()).collect(),
+                },
+                aggregates: items
+                    .iter()
+                    .filter_map(|i| match i {
+                        // the plan shows the call itself, the alias only names it
+                        SelectItem::Aggregate {
+                            function,
+                            filter,
+                            alias,
+                        } => {
+                            let call = Self::aggregate_call(function, filter.as_ref());
+                            Some(match alias {
+                                Some(alias) => format!("{call} AS {alias}"),
+                                None => call,
+                            })
+                        }
+                        _ => None,
+                    })
+                    .collect(),
+                having: select.having.as_ref().map(Expr::to_string),
+                input: Box::new(plan),
+            };
+        }
+        if let Some(order_by) = select.order_by.as_ref().filter(|o| !o.is_empty()) {
+            let mut keys: Vec<String> = order_by
+                .iter()
+                .map(|clause| {
+                    let direction = match clause.direction {
+                        SortDirection::Asc => "ASC",
+                        SortDirection::Desc => "DESC",
+                    };
+                    let nulls = match clause.nulls {
+                        Some(NullsOrder::First) => " NULLS FIRST",
+                        Some(NullsOrder::Last) => " NULLS LAST",
+                        None => "",
+                    };
+                    format!("{} {}{}", clause.expr, direction, nulls)
+                })
+                .collect();
+            // grouped rows have no rowid to break ties on
+            if self.comparison_config.deterministic_order
+                && !matches!(plan, PlanNode::Aggregate { .. })
+            {
+                keys.push("rowid ASC".into());
+            }
+            plan = PlanNode::Sort {
+                keys,
+                input: Box::new(plan),
+            };
+        }
+        if select.distinct {
+            plan = PlanNode::Distinct {
+                input: Box::new(plan),
+            };
+        }
+        if select.limit.is_some() || select.offset.is_some_and(|offset| offset > 0) {
+            plan = PlanNode::Limit {
+                limit: select.limit,
+                offset: select.offset.unwrap_or(0),
+                input: Box::new(plan),
+            };
+        }
+        Ok(plan)
+    }
+
+    /// Runs a parsed `SELECT` statement, filling the scan statistics in `stats`.
+    fn execute_select(
+        &self,
+        mut select: Select<'a>,
+        stats: &mut QueryStats,
+    ) -> Result<QueryResult<'a>, String> {
         let table = self
             .get_table(select.table)
             .ok_or_else(|| format!("table {:?} does not exist", select.table))?;
+        if select.join.is_none() {
+            Self::strip_qualifiers(&mut select, &table.schema)?;
+        }
+        let (joined_schema, joined_rows) = match &select.join {
+            Some(join) => {
+                let left_name = select.alias.unwrap_or(select.table);
+                let (schema, rows) = self.join_tables(table, left_name, join)?;
+                (Some(schema), Some(rows))
+            }
+            None => (None, None),
+        };
+        let schema = joined_schema.as_ref().unwrap_or(&table.schema);
+        // literals are bound to the table interner, unless rows come from two tables
+        let interner = joined_schema.is_none().then_some(table);
 
         if let Some(ref mut expr) = select.where_clause {
-            self.bind_expression(expr, table);
+            // WHERE runs before the projection, so its aliases do not exist yet
+            if let ColumnsSelect::Items(items) = &select.columns {
+                Self::reject_aliases(expr, items, schema)?;
+            }
+            self.resolve_subqueries(expr)?;
+            self.bind_expression(expr, schema, interner);
+        }
+        if let Some(ref mut expr) = select.having {
+            if let ColumnsSelect::Items(items) = &select.columns {
+                Self::resolve_having_aliases(expr, items, schema)?;
+            }
+            self.resolve_subqueries(expr)?;
+            self.bind_expression(expr, schema, interner);
+        }
+        if let ColumnsSelect::Items(items) = &mut select.columns {
+            for item in items {
+                if let SelectItem::Aggregate {
+                    filter: Some(expr), ..
+                } = item
+                {
+                    self.resolve_subqueries(expr)?;
+                    self.bind_expression(expr, schema, interner);
+                }
+            }
         }
 
-        let mut filtered_rows =
-            self.filter_rows(table, select.where_clause.as_ref(), |_, row| row.clone())?;
+        // TABLESAMPLE draws from the rows of the FROM clause, before WHERE filters them
+        let mut sampler = select
+            .sample
+            .map(|sample| RowSampler::new(sample.percent, sample.seed));
+        let mut filtered_rows = match joined_rows {
+            Some(mut rows) => {
+                stats.rows_scanned = rows.len();
+                if let Some(sampler) = &mut sampler {
+                    rows.retain(|_| sampler.keep());
+                }
+                match &select.where_clause {
+                    Some(expr) => {
+                        let predicate = self.compile_expr(expr, schema)?;
+                        let mut matched = Vec::new();
+                        for row in rows {
+                            if predicate(&row)? {
+                                matched.push(row);
+                            }
+                        }
+                        matched
+                    }
+                    None => rows,
+                }
+            }
+            None => {
+                // without ordering, grouping or DISTINCT only the first `offset + limit`
+                // matches can reach the result, so the scan stops once they are collected
+                let unordered = select.order_by.as_ref().is_none_or(|o| o.is_empty())
+                    && !select.distinct
+                    && select.having.is_none()
+                    && match &select.columns {
+                        ColumnsSelect::Star => true,
+                        ColumnsSelect::Items(items) => !items
+                            .iter()
+                            .any(|i| matches!(i, SelectItem::Aggregate { .. })),
+                    };
+                let wanted = select
+                    .limit
+                    .filter(|_| unordered)
+                    .map(|limit| limit.saturating_add(select.offset.unwrap_or(0)));
+                let predicate = select
+                    .where_clause
+                    .as_ref()
+                    .map(|expr| self.compile_expr(expr, schema))
+                    .transpose()?;
+
+                // TABLESAMPLE draws from every row, so the null bitmap cannot narrow the scan
+                let rows = match sampler {
+                    Some(_) => Box::new(Self::live_rows(table)),
+                    None => self.candidate_rows(table, select.where_clause.as_ref()),
+                };
+                let mut matched = Vec::new();
+                for (_, row) in rows {
+                    if wanted.is_some_and(|wanted| matched.len() >= wanted) {
+                        break;
+                    }
+                    stats.rows_scanned += 1;
+                    if sampler.as_mut().is_some_and(|sampler| !sampler.keep()) {
+                        continue;
+                    }
+                    let keep = match &predicate {
+                        Some(predicate) => predicate(&row)?,
+                        None => true,
+                    };
+                    if keep {
+                        matched.push(row);
+                    }
+                }
+                matched
+            }
+        };
+        stats.rows_matched = filtered_rows.len();
+
+        if select.having.is_some() && matches!(select.columns, ColumnsSelect::Star) {
+            return Err("HAVING cannot be used with SELECT *".into());
+        }
 
-        // If the SELECT contains aggregates, delegate entirely to compute_aggregates.
-        // ORDER BY and LIMIT do not apply to a single-row aggregate result.
+        // If the SELECT contains aggregates (or a HAVING clause), delegate the grouping to
+        // execute_group_by; ORDER BY and the window then apply to the grouped rows.
         if let ColumnsSelect::Items(ref items) = select.columns
-            && items.iter().any(|i| matches!(i, SelectItem::Aggregate(_)))
+            && (select.having.is_some()
+                || items
+                    .iter()
+                    .any(|i| matches!(i, SelectItem::Aggregate { .. })))
         {
+            if items.contains(&SelectItem::Star) {
+                return Err("* cannot be combined with aggregates or HAVING".into());
+            }
+
             let selected_cols: Vec<&str> = items
                 .iter()
-                .filter_map(|item| {
-                    if let SelectItem::Column(name) = item {
-                        Some(*name)
-                    } else {
-                        None
-                    }
+                .flat_map(|item| match item {
+                    SelectItem::Column(name) => vec![*name],
+                    SelectItem::Computed { expr, .. } => expr.columns(),
+                    SelectItem::Aggregate { .. }
+                    | SelectItem::Star
+                    | SelectItem::Subquery { .. } => vec![],
                 })
                 .collect();
 
@@ -533,66 +1860,558 @@ impl<'a> Database {
                 );
             }
 
-            return Self::execute_group_by(items, &filtered_rows, group_by_cols, &table.schema);
+            let mut result = if select.rollup {
+                self.execute_rollup(
+                    items,
+                    &filtered_rows,
+                    group_by_cols,
+                    select.having.as_ref(),
+                    schema,
+                )?
+            } else {
+                self.execute_group_by(
+                    items,
+                    &filtered_rows,
+                    group_by_cols,
+                    select.having.as_ref(),
+                    schema,
+                )?
+            };
+            if let Some(order_by) = select.order_by.filter(|o| !o.is_empty()) {
+                Self::sort_groups(&mut result, &order_by)?;
+            }
+            if select.distinct {
+                result.rows = Self::distinct_rows(result.rows);
+            }
+            Self::apply_window(&mut result.rows, select.offset, select.limit);
+            return Ok(result);
         }
 
         // Plain column projection path.
-        let selected_cols: Vec<Cow<'a, str>> = match select.columns {
-            ColumnsSelect::Star => table
-                .schema
-                .columns
-                .iter()
-                .map(|col| Cow::Owned(col.name.clone()))
-                .collect(),
-            ColumnsSelect::Items(items) => items
-                .into_iter()
-                .map(|item| match item {
-                    SelectItem::Column(name) => Ok(Cow::Borrowed(name)),
-                    SelectItem::Aggregate(_) => unreachable!(),
-                })
-                .collect::<Result<Vec<_>, String>>()?,
-        };
-
         if let Some(order_by) = select.order_by.filter(|o| !o.is_empty()) {
-            self.sort(&mut filtered_rows, &table.schema.columns, order_by)?;
+            let items = match &select.columns {
+                ColumnsSelect::Star => &[][..],
+                ColumnsSelect::Items(items) => items,
+            };
+            self.sort(&mut filtered_rows, schema, items, order_by)?;
         }
 
-        let final_rows: Vec<Vec<Value>> = filtered_rows
-            .into_iter()
-            .map(|full_row| {
-                selected_cols
-                    .iter()
-                    .map(|col_name| {
-                        let idx = table
-                            .schema
-                            .columns
-                            .iter()
-                            .position(|c| &c.name == col_name)
-                            .unwrap();
-                        // TODO: Use Rc or Arc to avoid clone
-                        full_row[idx].clone()
-                    })
-                    .collect()
-            })
-            .take(select.limit.unwrap_or(usize::MAX))
-            .collect();
+        // with DISTINCT, OFFSET and LIMIT count distinct rows so they are applied after
+        // deduplication; otherwise rows outside the window are dropped before projection
+        if !select.distinct {
+            Self::apply_window(&mut filtered_rows, select.offset, select.limit);
+        }
+
+        let names = Self::query_names(select.table, select.alias, select.join.as_ref());
+        let mut result = self.project(&select.columns, filtered_rows, &names, schema)?;
+        if select.distinct {
+            result.rows = Self::distinct_rows(result.rows);
+            Self::apply_window(&mut result.rows, select.offset, select.limit);
+        }
+        Ok(result)
+    }
+
+    /// Joins the live rows of `left` with those of `join.table` using a nested loop.
+    ///
+    /// Returns the schema of the combined rows, where every column is qualified by its
+    /// table or the table's alias (`users.id`, or `u.id` when `left_name` is the alias of
+    /// `left`), and the rows themselves: the left columns followed by the right ones.
+    /// With a `LEFT JOIN`, a left row without any match is kept once, the right columns
+    /// set to `NULL`.
+    ///
+    /// # Errors
+    /// Returns an error if the joined table does not exist or has the same name as the
+    /// left one, or if the `ON` columns are not one column of each table with the same
+    /// type.
+    fn join_tables(
+        &self,
+        left: &Table,
+        left_name: &str,
+        join: &Join,
+    ) -> Result<(Schema, Vec<Vec<Value>>), String> {
+        let right = self
+            .get_table(join.table)
+            .ok_or_else(|| format!("table {:?} does not exist", join.table))?;
+        let right_name = join.alias.unwrap_or(join.table);
+        if right_name == left_name {
+            return Err(format!(
+                "Cannot join table {} with itself without an alias",
+                left_name
+            ));
+        }
+
+        let qualify = |table: &str, schema: &Schema| -> Vec<ColumnDef> {
+            schema
+                .columns
+                .iter()
+                .map(|col| ColumnDef::new(format!("{}.{}", table, col.name), col.data_type))
+                .collect()
+        };
+        let mut columns = qualify(left_name, &left.schema);
+        columns.extend(qualify(right_name, &right.schema));
+        let schema = Schema { columns };
+
+        // `ON` may name the two columns in either order
+        let width = left.schema.columns.len();
+        let (a, b) = (schema.index_of(join.on.0)?, schema.index_of(join.on.1)?);
+        let (left_idx, right_idx) = match (a < width, b < width) {
+            (true, false) => (a, b),
+            (false, true) => (b, a),
+            _ => {
+                return Err(format!(
+                    "JOIN condition {} = {} must compare a column of {} with a column of {}",
+                    join.on.0, join.on.1, left_name, right_name
+                ));
+            }
+        };
+        let (left_type, right_type) = (
+            schema.columns[left_idx].data_type,
+            schema.columns[right_idx].data_type,
+        );
+        if left_type != right_type {
+            return Err(format!(
+                "JOIN condition {} = {} compares {:?} with {:?}",
+                join.on.0, join.on.1, left_type, right_type
+            ));
+        }
+        let right_idx = right_idx - width;
+
+        let right_rows = self.filter_rows(right, None, None, |_, row| row.clone())?;
+        let mut rows = Vec::new();
+        for left_row in self.filter_rows(left, None, None, |_, row| row.clone())? {
+            let mut matched = false;
+            for right_row in &right_rows {
+                if self.compare_values(
+                    &left_row[left_idx],
+                    &ComparisonOp::Eq,
+                    &right_row[right_idx],
+                )? {
+                    matched = true;
+                    rows.push(left_row.iter().chain(right_row).cloned().collect());
+                }
+            }
+            if !matched && join.kind == JoinKind::Left {
+                let nulls = std::iter::repeat_n(Value::Null, right.schema.columns.len());
+                rows.push(left_row.into_iter().chain(nulls).collect());
+            }
+        }
+        Ok((schema, rows))
+    }
+
+    /// Projects full table rows on `columns`, a `*` or a list of aggregate-free items.
+    /// `names` are the table names and aliases the rows come from.
+    ///
+    /// Column references are checked even when `rows` is empty, so projecting no rows
+    /// validates a projection up front. A subquery item runs once per row, see
+    /// [Self::evaluate_correlated].
+    ///
+    /// # Errors
+    /// Returns an error if a referenced column does not exist or an expression fails to
+    /// evaluate.
+    fn project(
+        &self,
+        columns: &ColumnsSelect<'a>,
+        rows: Vec<Vec<Value>>,
+        names: &[&'a str],
+        schema: &Schema,
+    ) -> Result<QueryResult<'a>, String> {
+        let ColumnsSelect::Items(items) = columns else {
+            return Ok(QueryResult {
+                columns: schema
+                    .columns
+                    .iter()
+                    .map(|col| Cow::Owned(col.name.clone()))
+                    .collect(),
+                column_types: schema
+                    .columns
+                    .iter()
+                    .map(|col| Some(col.data_type))
+                    .collect(),
+                rows,
+            });
+        };
+
+        // a subquery item is kept as `Err` so it can be run against each row
+        let mut exprs: Vec<Result<ScalarExpr, &Select<'a>>> = Vec::with_capacity(items.len());
+        let mut columns = Vec::with_capacity(items.len());
+        let mut column_types = Vec::with_capacity(items.len());
+        for item in items {
+            match item {
+                // expand the wildcard in place to every schema column
+                SelectItem::Star => {
+                    for col in &schema.columns {
+                        exprs.push(Ok(ScalarExpr::Column(&col.name)));
+                        columns.push(Cow::Owned(col.name.clone()));
+                        column_types.push(Some(col.data_type));
+                    }
+                }
+                SelectItem::Column(name) => {
+                    exprs.push(Ok(ScalarExpr::Column(name)));
+                    columns.push(Self::item_name(item));
+                    column_types.push(Self::item_type(item, schema));
+                }
+                SelectItem::Computed { expr, .. } => {
+                    exprs.push(Ok(expr.clone()));
+                    columns.push(Self::item_name(item));
+                    column_types.push(Self::item_type(item, schema));
+                }
+                SelectItem::Subquery { query, .. } => {
+                    exprs.push(Err(query));
+                    columns.push(Self::item_name(item));
+                    column_types.push(Self::item_type(item, schema));
+                }
+                SelectItem::Aggregate { .. } => unreachable!(),
+            }
+        }
+        for col in exprs.iter().flatten().flat_map(ScalarExpr::columns) {
+            schema.index_of(col)?;
+        }
+
+        let rows = rows
+            .into_iter()
+            .map(|full_row| {
+                exprs
+                    .iter()
+                    .map(|expr| match expr {
+                        Ok(expr) => Self::evaluate_scalar(expr, &full_row, schema),
+                        Err(query) => self.evaluate_correlated(query, &full_row, names, schema),
+                    })
+                    .collect()
+            })
+            .collect::<Result<_, String>>()?;
 
         Ok(QueryResult {
-            columns: selected_cols,
-            rows: final_rows,
+            columns,
+            column_types,
+            rows,
         })
     }
 
-    /// Returns the SQL display name for an aggregate expression, used as the column
-    /// header in query results (e.g. `SUM(salary)`, `COUNT(*)`).
-    fn col_name(agg: &Aggregate) -> String {
+    /// Keeps only the rows selected by `OFFSET offset LIMIT limit`.
+    ///
+    /// Bounds are computed with saturating arithmetic, so an absurd offset or limit (up
+    /// to `usize::MAX`) simply yields fewer or no rows instead of overflowing.
+    fn apply_window(rows: &mut Vec<Vec<Value>>, offset: Option<usize>, limit: Option<usize>) {
+        let start = offset.unwrap_or(0).min(rows.len());
+        let end = start
+            .saturating_add(limit.unwrap_or(usize::MAX))
+            .min(rows.len());
+        rows.truncate(end);
+        rows.drain(..start);
+    }
+
+    /// Fails with a targeted error when `expr` names a projection alias that is not also
+    /// a table column, instead of the generic missing-column message.
+    fn reject_aliases(expr: &Expr, items: &[SelectItem], schema: &Schema) -> Result<(), String> {
+        for column in expr.columns() {
+            let is_alias = items.iter().any(|item| match item {
+                SelectItem::Computed { alias, .. }
+                | SelectItem::Aggregate { alias, .. }
+                | SelectItem::Subquery { alias, .. } => *alias == Some(column),
+                SelectItem::Column(_) | SelectItem::Star => false,
+            });
+            if is_alias && schema.data_type_of(column).is_none() {
+                return Err(format!(
+                    "Column `{}` not found (aliases cannot be used in WHERE)",
+                    column
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Rewrites the conditions of a `HAVING` clause that name the alias of an aggregate
+    /// in the projection (`COUNT(*) AS n ... HAVING n > 1`) into comparisons on the
+    /// aggregate itself. A table column of the same name takes precedence.
+    ///
+    /// `n BETWEEN 1 AND 3` becomes `n >= 1 AND n <= 3`, `n IN (2, 3)` becomes
+    /// `n = 2 OR n = 3`, and `n IS NULL` becomes `n <=> NULL`.
+    ///
+    /// # Errors
+    /// Returns an error if the alias names an aggregate with a `FILTER` clause, which
+    /// [Expr::AggregateComparison] cannot express, or if it is used in a condition that
+    /// has no such rewriting (`LIKE`, a comparison with another column, a subquery, a
+    /// `BETWEEN` bound that is not a literal).
+    fn resolve_having_aliases(
+        expr: &mut Expr<'a>,
+        items: &[SelectItem<'a>],
+        schema: &Schema,
+    ) -> Result<(), String> {
+        let aggregate =
+            |op: ComparisonOp, value: Value, function: &Aggregate<'a>| Expr::AggregateComparison {
+                aggregate: function.clone(),
+                op,
+                value,
+            };
+        match expr {
+            Expr::Comparison { column, op, value } => {
+                if let Some(function) = Self::having_alias(column, items, schema)? {
+                    let value = std::mem::replace(value, Value::Null);
+                    *expr = aggregate(op.clone(), value, function);
+                }
+            }
+            Expr::IsNull { column, negated } => {
+                if let Some(function) = Self::having_alias(column, items, schema)? {
+                    let is_null = aggregate(ComparisonOp::NullSafeEq, Value::Null, function);
+                    *expr = if *negated {
+                        Expr::Not(Box::new(is_null))
+                    } else {
+                        is_null
+                    };
+                }
+            }
+            Expr::Between { column, low, high } => {
+                if let Some(function) = Self::having_alias(column, items, schema)? {
+                    let (ScalarExpr::Literal(low), ScalarExpr::Literal(high)) = (low, high) else {
+                        return Err(format!(
+                            "Alias {} can only be compared with literal bounds in HAVING",
+                            column
+                        ));
+                    };
+                    *expr = Expr::And {
+                        left: Box::new(aggregate(ComparisonOp::Gte, low.clone(), function)),
+                        right: Box::new(aggregate(ComparisonOp::Lte, high.clone(), function)),
+                    };
+                }
+            }
+            Expr::InList {
+                column,
+                values,
+                negated,
+            } => {
+                if let Some(function) = Self::having_alias(column, items, schema)? {
+                    let any = values
+                        .iter()
+                        .map(|value| aggregate(ComparisonOp::Eq, value.clone(), function))
+                        .reduce(|left, right| Expr::Or {
+                            left: Box::new(left),
+                            right: Box::new(right),
+                        })
+                        .unwrap_or(Expr::Literal(false));
+                    *expr = if *negated {
+                        Expr::Not(Box::new(any))
+                    } else {
+                        any
+                    };
+                }
+            }
+            Expr::Like { column, .. } | Expr::Subquery { column, .. } => {
+                if Self::having_alias(column, items, schema)?.is_some() {
+                    return Err(format!(
+                        "Alias {} cannot be used in this HAVING condition",
+                        column
+                    ));
+                }
+            }
+            Expr::ColumnComparison { left, right, .. } => {
+                for column in [left, right] {
+                    if Self::having_alias(column, items, schema)?.is_some() {
+                        return Err(format!(
+                            "Alias {} cannot be compared with a column in HAVING",
+                            column
+                        ));
+                    }
+                }
+            }
+            Expr::And { left, right } | Expr::Or { left, right } => {
+                Self::resolve_having_aliases(left, items, schema)?;
+                Self::resolve_having_aliases(right, items, schema)?;
+            }
+            Expr::Not(inner) => Self::resolve_having_aliases(inner, items, schema)?,
+            Expr::AggregateComparison { .. } | Expr::Literal(_) => {}
+        }
+        Ok(())
+    }
+
+    /// Returns the aggregate that `column` is the alias of in the projection `items`, if
+    /// `column` is not a column of `schema`.
+    ///
+    /// # Errors
+    /// Returns an error if the aggregate has a `FILTER` clause.
+    fn having_alias<'i>(
+        column: &str,
+        items: &'i [SelectItem<'a>],
+        schema: &Schema,
+    ) -> Result<Option<&'i Aggregate<'a>>, String> {
+        if schema.data_type_of(column).is_some() {
+            return Ok(None);
+        }
+        let aliased = items.iter().find_map(|item| match item {
+            SelectItem::Aggregate {
+                function,
+                filter,
+                alias: Some(alias),
+            } if *alias == column => Some((function, filter)),
+            _ => None,
+        });
+        match aliased {
+            Some((_, Some(_))) => Err(format!(
+                "Alias {} names a filtered aggregate and cannot be used in HAVING",
+                column
+            )),
+            Some((function, None)) => Ok(Some(function)),
+            None => Ok(None),
+        }
+    }
+
+    /// Removes duplicate rows, keeping the first occurrence of each one in place.
+    ///
+    /// Rows are compared with `Value`'s total order, so all `NULL`s are considered
+    /// equal and collapse into a single row.
+    fn distinct_rows(rows: Vec<Vec<Value>>) -> Vec<Vec<Value>> {
+        let mut seen = BTreeSet::new();
+        rows.into_iter()
+            .filter(|row| seen.insert(row.clone()))
+            .collect()
+    }
+
+    /// Returns the result column name of a projection item: its `AS` alias when named,
+    /// otherwise the column name, the SQL text of a computed expression or the aggregate
+    /// call.
+    fn item_name(item: &SelectItem<'a>) -> Cow<'a, str> {
+        match item {
+            SelectItem::Column(name) => Cow::Borrowed(*name),
+            SelectItem::Computed {
+                alias: Some(alias), ..
+            } => Cow::Borrowed(*alias),
+            SelectItem::Computed { expr, alias: None } => Cow::Owned(expr.to_string()),
+            SelectItem::Aggregate {
+                alias: Some(alias), ..
+            } => Cow::Borrowed(*alias),
+            SelectItem::Aggregate {
+                function, filter, ..
+            } => Cow::Owned(Self::aggregate_call(function, filter.as_ref())),
+            SelectItem::Subquery {
+                alias: Some(alias), ..
+            } => Cow::Borrowed(*alias),
+            SelectItem::Subquery { alias: None, .. } => Cow::Borrowed("(SELECT ...)"),
+            SelectItem::Star => Cow::Borrowed("*"),
+        }
+    }
+
+    /// Returns the SQL text of an aggregate call with its `FILTER` clause, if any.
+    fn aggregate_call(function: &Aggregate, filter: Option<&Expr>) -> String {
+        match filter {
+            Some(filter) => format!("{} FILTER (WHERE {})", function, filter),
+            None => function.to_string(),
+        }
+    }
+
+    /// Returns the logical type of a projection item, `None` when it cannot be inferred.
+    fn item_type(item: &SelectItem, schema: &Schema) -> Option<DataType> {
+        match item {
+            SelectItem::Column(name) => schema.data_type_of(name),
+            SelectItem::Computed { expr, .. } => Self::scalar_type(expr, schema),
+            SelectItem::Aggregate { function, .. } => Self::aggregate_type(function, schema),
+            SelectItem::Star | SelectItem::Subquery { .. } => None,
+        }
+    }
+
+    /// Infers the type produced by a scalar expression.
+    ///
+    /// Arithmetic between two `Int` stays an `Int`, and between two `UInt` a `UInt`;
+    /// mixing them gives an `Int`. As soon as a `Float` is involved the result is a `Float`.
+    fn scalar_type(expr: &ScalarExpr, schema: &Schema) -> Option<DataType> {
+        match expr {
+            ScalarExpr::Column(name) => schema.data_type_of(name),
+            ScalarExpr::Literal(value) => value.data_type(),
+            ScalarExpr::Binary { left, right, .. } => match (
+                Self::scalar_type(left, schema)?,
+                Self::scalar_type(right, schema)?,
+            ) {
+                (DataType::UInt, DataType::UInt) => Some(DataType::UInt),
+                (a, b) if a.is_integer() && b.is_integer() => Some(DataType::Int),
+                (a, b) if a.is_numeric() && b.is_numeric() => Some(DataType::Float),
+                _ => None,
+            },
+        }
+    }
+
+    /// Evaluates a scalar expression against a full row (all columns in schema order).
+    ///
+    /// # Errors
+    /// Returns an error if a column does not exist, or if the arithmetic fails
+    /// (see [Self::apply_arithmetic]).
+    fn evaluate_scalar(expr: &ScalarExpr, row: &[Value], schema: &Schema) -> Result<Value, String> {
+        match expr {
+            ScalarExpr::Column(name) => Ok(row[schema.index_of(name)?].clone()),
+            ScalarExpr::Literal(value) => Ok(value.clone()),
+            ScalarExpr::Binary { left, op, right } => {
+                let left = Self::evaluate_scalar(left, row, schema)?;
+                let right = Self::evaluate_scalar(right, row, schema)?;
+                Self::apply_arithmetic(&left, *op, &right)
+            }
+        }
+    }
+
+    /// Applies an arithmetic operator to two values.
+    ///
+    /// `NULL` on either side gives `NULL`. Integers are computed in `i128` (integer division
+    /// for `/`): two `UInt` produce a `UInt`, any other pair of integers an `Int`. Any
+    /// `Float` operand promotes the result to `Float`.
+    ///
+    /// # Errors
+    /// Returns an error on non-numeric operands, integer overflow or division by zero.
+    fn apply_arithmetic(left: &Value, op: ArithmeticOp, right: &Value) -> Result<Value, String> {
+        match (left, right) {
+            (Value::Null, _) | (_, Value::Null) => Ok(Value::Null),
+            (Value::Int(_) | Value::UInt(_), Value::Int(_) | Value::UInt(_)) => {
+                let (l, r) = (widen_integer(left), widen_integer(right));
+                let result = match op {
+                    ArithmeticOp::Add => l.checked_add(r),
+                    ArithmeticOp::Sub => l.checked_sub(r),
+                    ArithmeticOp::Mul => l.checked_mul(r),
+                    ArithmeticOp::Div if r == 0 => return Err("Division by zero".into()),
+                    ArithmeticOp::Div => l.checked_div(r),
+                };
+                let result = match (left, right) {
+                    (Value::UInt(_), Value::UInt(_)) => {
+                        result.and_then(|v| u64::try_from(v).ok()).map(Value::UInt)
+                    }
+                    _ => result.and_then(|v| i64::try_from(v).ok()).map(Value::Int),
+                };
+                result.ok_or_else(|| format!("Integer overflow in {} {} {}", l, op, r))
+            }
+            (
+                Value::Int(_) | Value::UInt(_) | Value::Float(_),
+                Value::Int(_) | Value::UInt(_) | Value::Float(_),
+            ) => {
+                let to_f64 = |v: &Value| match v {
+                    Value::Int(i) => *i as f64,
+                    Value::UInt(u) => *u as f64,
+                    Value::Float(f) => *f,
+                    _ => unreachable!(),
+                };
+                let (l, r) = (to_f64(left), to_f64(right));
+                let result = match op {
+                    ArithmeticOp::Add => l + r,
+                    ArithmeticOp::Sub => l - r,
+                    ArithmeticOp::Mul => l * r,
+                    ArithmeticOp::Div if r == 0.0 => return Err("Division by zero".into()),
+                    ArithmeticOp::Div => l / r,
+                };
+                Ok(Value::Float(result))
+            }
+            _ => Err(format!(
+                "Type mismatch: cannot apply {} to {:?} and {:?}",
+                op, left, right
+            )),
+        }
+    }
+
+    /// Returns the logical type produced by an aggregate expression.
+    ///
+    /// `COUNT` is always an `Int` and `AVG` always a `Float`, while `SUM`, `MIN` and
+    /// `MAX` keep the type of their input column.
+    fn aggregate_type(agg: &Aggregate, schema: &Schema) -> Option<DataType> {
         match agg {
-            Aggregate::CountStar => "COUNT(*)".into(),
-            Aggregate::Count(col) => format!("COUNT({})", col),
-            Aggregate::Avg(col) => format!("AVG({})", col),
-            Aggregate::Min(col) => format!("MIN({})", col),
-            Aggregate::Max(col) => format!("MAX({})", col),
-            Aggregate::Sum(col) => format!("SUM({})", col),
+            Aggregate::CountStar | Aggregate::Count(_) | Aggregate::ApproxCountDistinct(_) => {
+                Some(DataType::Int)
+            }
+            Aggregate::Avg(_) => Some(DataType::Float),
+            Aggregate::Sum(col) | Aggregate::Min(col) | Aggregate::Max(col) => {
+                schema.data_type_of(col)
+            }
         }
     }
 
@@ -600,7 +2419,8 @@ impl<'a> Database {
     ///
     /// Returns `Value::Null` for `SUM`, `MIN`, `MAX`, and `AVG` when no non-null
     /// values are present, matching SQL standard semantics. `COUNT` always returns
-    /// an integer (0 if no rows or all nulls).
+    /// an integer (0 if no rows or all nulls), as does `APPROX_COUNT_DISTINCT`, which
+    /// estimates the number of distinct non-null values (see [HyperLogLog]).
     ///
     /// # Errors
     /// Returns an error if the referenced column does not exist or is not numeric
@@ -620,6 +2440,15 @@ impl<'a> Database {
                 ))
             }
 
+            Aggregate::ApproxCountDistinct(col) => {
+                let idx = schema.index_of(col)?;
+                let mut sketch = HyperLogLog::new();
+                rows.iter()
+                    .filter(|row| !row[idx].is_null())
+                    .for_each(|row| sketch.insert(&row[idx]));
+                Ok(Value::Int(sketch.estimate() as i64))
+            }
+
             Aggregate::Sum(col) => {
                 let (idx, dtype) = validate_numeric_col(schema, col)?;
                 let val = match dtype {
@@ -631,6 +2460,18 @@ impl<'a> Database {
                             Value::Int(vals.into_iter().sum())
                         }
                     }
+                    DataType::UInt => {
+                        let vals = collect_uint_col(rows, idx);
+                        if vals.is_empty() {
+                            Value::Null
+                        } else {
+                            let sum: u128 = vals.into_iter().map(u128::from).sum();
+                            Value::UInt(
+                                u64::try_from(sum)
+                                    .map_err(|_| format!("SUM({}) overflows UINT", col))?,
+                            )
+                        }
+                    }
                     DataType::Float => {
                         let vals = collect_float_col(rows, idx);
                         if vals.is_empty() {
@@ -645,56 +2486,47 @@ impl<'a> Database {
             }
 
             Aggregate::Min(col) => {
-                let (idx, dtype) = validate_numeric_col(schema, col)?;
-                let val = match dtype {
-                    DataType::Int => collect_int_col(rows, idx)
-                        .into_iter()
-                        .min()
-                        .map(Value::Int)
-                        .unwrap_or(Value::Null),
-                    DataType::Float => collect_float_col(rows, idx)
-                        .into_iter()
-                        .min_by(|a, b| a.partial_cmp(b).unwrap())
-                        .map(Value::Float)
-                        .unwrap_or(Value::Null),
-                    _ => unreachable!(),
-                };
-                Ok(val)
+                let (idx, _) = validate_numeric_col(schema, col)?;
+                Ok(rows
+                    .iter()
+                    .fold(Value::Null, |acc, row| value::min(&acc, &row[idx])))
             }
 
             Aggregate::Max(col) => {
-                let (idx, dtype) = validate_numeric_col(schema, col)?;
-                let val = match dtype {
-                    DataType::Int => collect_int_col(rows, idx)
-                        .into_iter()
-                        .max()
-                        .map(Value::Int)
-                        .unwrap_or(Value::Null),
-                    DataType::Float => collect_float_col(rows, idx)
-                        .into_iter()
-                        .max_by(|a, b| a.partial_cmp(b).unwrap())
-                        .map(Value::Float)
-                        .unwrap_or(Value::Null),
-                    _ => unreachable!(),
-                };
-                Ok(val)
+                let (idx, _) = validate_numeric_col(schema, col)?;
+                Ok(rows
+                    .iter()
+                    .fold(Value::Null, |acc, row| value::max(&acc, &row[idx])))
             }
 
             Aggregate::Avg(col) => {
                 let (idx, dtype) = validate_numeric_col(schema, col)?;
                 // AVG always returns Float (or NULL if no rows).
-                let vals = match dtype {
-                    DataType::Int => collect_int_col(rows, idx)
-                        .into_iter()
-                        .map(|v| v as f64)
-                        .collect::<Vec<f64>>(),
-                    DataType::Float => collect_float_col(rows, idx),
+                // Ints are summed as i128 so the total cannot overflow, floats with
+                // compensated summation so precision does not drift over large columns.
+                let (sum, count) = match dtype {
+                    DataType::Int => {
+                        let vals = collect_int_col(rows, idx);
+                        let sum: i128 = vals.iter().map(|&v| v as i128).sum();
+                        (sum as f64, vals.len())
+                    }
+                    DataType::UInt => {
+                        let vals = collect_uint_col(rows, idx);
+                        let sum: u128 = vals.iter().map(|&v| v as u128).sum();
+                        (sum as f64, vals.len())
+                    }
+                    DataType::Float => {
+                        let vals = collect_float_col(rows, idx);
+                        let mut sum = CompensatedSum::default();
+                        vals.iter().for_each(|&v| sum.add(v));
+                        (sum.total(), vals.len())
+                    }
                     _ => unreachable!(),
                 };
-                let val = if vals.is_empty() {
+                let val = if count == 0 {
                     Value::Null
                 } else {
-                    Value::Float(vals.iter().sum::<f64>() / vals.len() as f64)
+                    Value::Float(sum / count as f64)
                 };
                 Ok(val)
             }
@@ -703,7 +2535,7 @@ impl<'a> Database {
 
     /// Partitions `rows` into groups defined by `group_by_cols`, then evaluates
     /// each `SelectItem` (plain column or aggregate) per group, returning one
-    /// result row per group.
+    /// result row per group, in the order each group is first seen.
     ///
     /// When `group_by_cols` is empty (pure aggregate query without `GROUP BY`),
     /// a single synthetic group is created so that aggregates over an empty
@@ -712,14 +2544,19 @@ impl<'a> Database {
     /// Callers must ensure that every `SelectItem::Column` in `items` appears
     /// in `group_by_cols`; this is validated upstream in `query()`.
     ///
+    /// When a `having` expression is given, groups for which it does not hold
+    /// are dropped before being projected.
+    ///
     /// # Errors
     /// Returns an error if a column referenced in `items` or `group_by_cols`
     /// does not exist in `schema`, or if an aggregate is applied to a
     /// non-numeric column.
     fn execute_group_by(
+        &self,
         items: &[SelectItem<'a>],
         rows: &[Vec<Value>],
         group_by_cols: &[&str],
+        having: Option<&Expr>,
         schema: &Schema,
     ) -> Result<QueryResult<'a>, String> {
         let group_by_indexes: Vec<usize> = group_by_cols
@@ -727,40 +2564,79 @@ impl<'a> Database {
             .map(|col| schema.index_of(col))
             .collect::<Result<Vec<_>, _>>()?;
 
-        let mut groups: BTreeMap<Vec<Value>, Vec<Vec<Value>>> = BTreeMap::new();
+        // Groups are kept in the order their key is first seen; the map only finds the
+        // position of an existing key.
+        let mut group_positions: BTreeMap<Vec<Value>, usize> = BTreeMap::new();
+        let mut groups: Vec<Vec<Vec<Value>>> = Vec::new();
 
         // Without GROUP BY, always produce one group (even on empty input).
         // SQL semantics: SELECT COUNT(*) FROM t WHERE false → 1 row with 0, not 0 rows.
         if group_by_cols.is_empty() {
-            groups.entry(vec![]).or_default();
+            group_positions.insert(vec![], 0);
+            groups.push(vec![]);
         }
 
         for row in rows {
-            groups
-                .entry(group_by_indexes.iter().map(|&i| row[i].clone()).collect())
-                .or_default()
-                .push(row.clone());
+            let key: Vec<Value> = group_by_indexes.iter().map(|&i| row[i].clone()).collect();
+            let position = *group_positions.entry(key).or_insert_with(|| {
+                groups.push(vec![]);
+                groups.len() - 1
+            });
+            groups[position].push(row.clone());
         }
 
-        let cols: Vec<Cow<'a, str>> = items
+        let cols: Vec<Cow<'a, str>> = items.iter().map(Self::item_name).collect();
+
+        let column_types = items
+            .iter()
+            .map(|item| Self::item_type(item, schema))
+            .collect();
+
+        // FILTER conditions are compiled once, then applied to the rows of every group
+        let filters = items
             .iter()
             .map(|item| match item {
-                SelectItem::Column(name) => Cow::Borrowed(*name),
-                SelectItem::Aggregate(agg) => Cow::Owned(Self::col_name(agg)),
+                SelectItem::Aggregate {
+                    filter: Some(expr), ..
+                } => self.compile_expr(expr, schema).map(Some),
+                _ => Ok(None),
             })
-            .collect();
+            .collect::<Result<Vec<_>, String>>()?;
 
         let mut result_rows = Vec::with_capacity(groups.len());
-        for group_rows in groups.values() {
+        for group_rows in &groups {
+            if let Some(expr) = having
+                && !self.evaluate_having(expr, group_rows, group_by_cols, schema)?
+            {
+                continue;
+            }
             let row: Vec<Value> = items
                 .iter()
-                .map(|item| match item {
+                .zip(&filters)
+                .map(|(item, filter)| match item {
                     SelectItem::Column(col) => {
                         let idx = schema.index_of(col)?;
                         Ok(group_rows[0][idx].clone())
                     }
-                    SelectItem::Aggregate(agg) => {
-                        Self::compute_single_aggregate(agg, group_rows, schema)
+                    SelectItem::Aggregate { function, .. } => match filter {
+                        Some(filter) => {
+                            let mut kept = Vec::new();
+                            for row in group_rows {
+                                if filter(row)? {
+                                    kept.push(row.clone());
+                                }
+                            }
+                            Self::compute_single_aggregate(function, &kept, schema)
+                        }
+                        None => Self::compute_single_aggregate(function, group_rows, schema),
+                    },
+                    // only grouped columns are referenced, so any row of the group will do
+                    SelectItem::Computed { expr, .. } => {
+                        Self::evaluate_scalar(expr, &group_rows[0], schema)
+                    }
+                    SelectItem::Star => Err("* cannot be used in a grouped query".into()),
+                    SelectItem::Subquery { .. } => {
+                        Err("A subquery cannot be selected in a grouped query".into())
                     }
                 })
                 .collect::<Result<Vec<_>, _>>()?;
@@ -769,524 +2645,3958 @@ impl<'a> Database {
 
         Ok(QueryResult {
             columns: cols,
+            column_types,
             rows: result_rows,
         })
     }
 
-    /// Prepare expression to be optimized by the string interner of the selected table
-    fn bind_expression(&self, expr: &mut Expr, table: &Table) {
-        match expr {
-            Expr::Comparison { value, .. } => {
-                //check if the value is a Text and try to internalize it.
-                if let Value::Text(s) = value
-                    && let Some(interned) = table.lookup_string(s)
-                {
-                    *s = interned;
+    /// Executes `GROUP BY ROLLUP(cols)`: the grouping runs once per prefix of `cols`,
+    /// from all of them down to none, and the results are concatenated in that order.
+    ///
+    /// In the rows of a shorter prefix, the columns rolled up (and the expressions
+    /// using them) are `NULL`. `HAVING` applies to every level, but cannot compare a
+    /// rolled-up column since it has no single value in a subtotal.
+    fn execute_rollup(
+        &self,
+        items: &[SelectItem<'a>],
+        rows: &[Vec<Value>],
+        group_by_cols: &[&str],
+        having: Option<&Expr>,
+        schema: &Schema,
+    ) -> Result<QueryResult<'a>, String> {
+        if let Some(column) = having
+            .into_iter()
+            .flat_map(Expr::columns)
+            .find(|c| group_by_cols.contains(c))
+        {
+            return Err(format!(
+                "Column {} is rolled up and cannot be used in HAVING",
+                column
+            ));
+        }
+
+        let mut result: Option<QueryResult<'a>> = None;
+        for level in (0..=group_by_cols.len()).rev() {
+            let rolled_up = &group_by_cols[level..];
+            let mut level_result =
+                self.execute_group_by(items, rows, &group_by_cols[..level], having, schema)?;
+            for (position, item) in items.iter().enumerate() {
+                let uses_rolled_up = match item {
+                    SelectItem::Column(col) => rolled_up.contains(col),
+                    SelectItem::Computed { expr, .. } => {
+                        expr.columns().iter().any(|c| rolled_up.contains(c))
+                    }
+                    SelectItem::Aggregate { .. }
+                    | SelectItem::Star
+                    | SelectItem::Subquery { .. } => false,
+                };
+                if uses_rolled_up {
+                    for row in &mut level_result.rows {
+                        row[position] = Value::Null;
+                    }
                 }
             }
-            Expr::And { left, right } | Expr::Or { left, right } => {
-                self.bind_expression(left, table);
-                self.bind_expression(right, table);
+            match &mut result {
+                Some(result) => result.rows.append(&mut level_result.rows),
+                None => result = Some(level_result),
             }
         }
+        // the loop runs at least once, for the grand total
+        result.ok_or_else(|| "Internal error: empty ROLLUP".into())
     }
 
-    /// Sorts the provided rows in-place based on the SQL `ORDER BY` clauses.
+    /// Prepare expression to be optimized by the string interner of the selected table.
     ///
-    /// This method supports multi-column sorting. For each row comparison, it
-    /// iterates through the sort clauses: if the first column results in an
-    /// equal comparison, it moves to the next column, and so on.
+    /// Literals are also coerced to the type of what they are compared to (see
+    /// [Value::coerce_to]), so `price > 18` on a `FLOAT` column compares floats. A literal
+    /// that cannot be coerced is left as is and rejected by [Self::compile_expr] before
+    /// any row is read.
     ///
-    /// # Errors
-    /// Returns an error if a column specified in the `ORDER BY` clause
-    /// does not exist in the table schema.
-    fn sort(
-        &self,
-        rows: &mut [Vec<Value>],
-        cols: &[ColumnDef],
-        order_by: Vec<OrderByClause>,
-    ) -> Result<(), String> {
-        // save all column indexes on which we have to sort + boolean if we have to reverse order
-        let sort = order_by
-            .iter()
-            .map(|clause| {
-                let idx = cols
-                    .iter()
-                    .position(|c| c.name == clause.column)
-                    .ok_or_else(|| format!("Column {} not found", clause.column))?;
-                Ok((idx, clause.direction == SortDirection::Desc))
-            })
-            .collect::<Result<Vec<(usize, bool)>, String>>()?;
-
-        rows.sort_by(|a, b| {
-            for (idx, is_desc) in &sort {
-                let mut ord = a[*idx].cmp(&b[*idx]);
-
-                if *is_desc {
-                    ord = ord.reverse();
+    /// `table` is `None` when the rows come from a join: literals are only coerced
+    /// against `schema`, as there is no single interner to bind them to.
+    fn bind_expression(&self, expr: &mut Expr, schema: &Schema, table: Option<&Table>) {
+        match expr {
+            Expr::Comparison { column, value, .. } => {
+                Self::coerce_literal(value, schema.data_type_of(column));
+                Self::bind_literal(value, table);
+            }
+            Expr::InList { column, values, .. } => {
+                let target = schema.data_type_of(column);
+                for value in values {
+                    Self::coerce_literal(value, target);
+                    Self::bind_literal(value, table);
                 }
-                // if it's not equal no need to compare more
-                if ord != Ordering::Equal {
-                    return ord;
+            }
+            Expr::AggregateComparison {
+                aggregate, value, ..
+            } => {
+                Self::coerce_literal(value, Self::aggregate_type(aggregate, schema));
+                Self::bind_literal(value, table);
+            }
+            Expr::Between { column, low, high } => {
+                let target = schema.data_type_of(column);
+                for bound in [low, high] {
+                    if let ScalarExpr::Literal(value) = bound {
+                        Self::coerce_literal(value, target);
+                        Self::bind_literal(value, table);
+                    }
                 }
             }
-            Ordering::Equal
-        });
-
-        Ok(())
+            Expr::And { left, right } | Expr::Or { left, right } => {
+                self.bind_expression(left, schema, table);
+                self.bind_expression(right, schema, table);
+            }
+            Expr::Not(inner) => self.bind_expression(inner, schema, table),
+            Expr::ColumnComparison { .. }
+            | Expr::Subquery { .. }
+            | Expr::IsNull { .. }
+            | Expr::Like { .. }
+            | Expr::Literal(_) => {}
+        }
     }
 
-    /// Evaluates a WHERE clause expression against a specific row.
+    /// Runs every subquery of `expr` and replaces it by an [Expr::Comparison] against the
+    /// value it returned, so later steps only deal with literals.
     ///
-    /// This function recursively evaluates:
-    /// - **Comparisons**: Column values compared to literals (`age > 18`)
-    /// - **AND**: Logical conjunction with short-circuit evaluation
-    /// - **OR**: Logical disjunction with short-circuit evaluation
+    /// Subqueries of a `WHERE` are uncorrelated, so each one runs once per statement
+    /// (correlated subqueries are only supported in the projection, see
+    /// [Self::evaluate_correlated]). One returning no row yields `NULL`, which matches
+    /// no row.
     ///
-    /// # Arguments
-    /// * `expr` - The expression tree to evaluate
-    /// * `row` - The complete row (all columns in schema order)
-    /// * `schema` - Table schema used to resolve column indices
-    ///
-    /// # Returns
-    /// * `Ok(true)` - The row satisfies the condition
-    /// * `Ok(false)` - The row does not match
-    /// * `Err(...)` - Invalid column name or type mismatch
-    /// ```
-    fn evaluate_expr(&self, expr: &Expr, row: &[Value], schema: &Schema) -> Result<bool, String> {
+    /// # Errors
+    /// Returns an error if a subquery fails, or does not return a single column and at
+    /// most one row.
+    fn resolve_subqueries(&self, expr: &mut Expr<'a>) -> Result<(), String> {
         match expr {
-            Expr::Comparison { column, op, value } => {
-                let col_idx = schema
-                    .columns
-                    .iter()
-                    .position(|c| &c.name == column)
-                    .ok_or_else(|| format!("Column {} not found", column))?;
-
-                let row_value = &row[col_idx];
-
-                self.compare_values(row_value, op, value)
-            }
-            Expr::Or { left, right } => {
-                Ok(self.evaluate_expr(left, row, schema)?
-                    || self.evaluate_expr(right, row, schema)?)
-            }
-            Expr::And { left, right } => {
-                let left_result = self.evaluate_expr(left, row, schema)?;
-                if !left_result {
-                    return Ok(false);
+            Expr::Subquery { .. } => {
+                let Expr::Subquery { column, op, query } =
+                    std::mem::replace(expr, Expr::Literal(false))
+                else {
+                    unreachable!()
+                };
+                let result = self.execute_select(*query, &mut QueryStats::default())?;
+                if result.columns.len() != 1 {
+                    return Err(format!(
+                        "Subquery compared to column `{}` must return one column, got {}",
+                        column,
+                        result.columns.len()
+                    ));
                 }
-                self.evaluate_expr(right, row, schema)
+                let mut rows = result.rows.into_iter();
+                let value = match (rows.next(), rows.next()) {
+                    (None, _) => Value::Null,
+                    (Some(mut row), None) => row.swap_remove(0),
+                    (Some(_), Some(_)) => {
+                        return Err(format!(
+                            "Subquery compared to column `{}` must return at most one row",
+                            column
+                        ));
+                    }
+                };
+                *expr = Expr::Comparison { column, op, value };
+            }
+            Expr::And { left, right } | Expr::Or { left, right } => {
+                self.resolve_subqueries(left)?;
+                self.resolve_subqueries(right)?;
             }
+            Expr::Not(inner) => self.resolve_subqueries(inner)?,
+            Expr::Comparison { .. }
+            | Expr::ColumnComparison { .. }
+            | Expr::AggregateComparison { .. }
+            | Expr::Between { .. }
+            | Expr::IsNull { .. }
+            | Expr::InList { .. }
+            | Expr::Like { .. }
+            | Expr::Literal(_) => {}
         }
+        Ok(())
     }
 
-    /// Compares two values using a comparison operator.
-    ///
-    /// # SQL NULL Semantics
-    /// - `NULL` compared to anything (including `NULL`) always returns `false`.
-    /// - This matches standard SQL three-valued logic.
+    /// Runs a scalar subquery of the projection for one row of the outer query, whose
+    /// columns are described by `outer_schema` and which reads the tables or aliases
+    /// `outer_names`, and returns its single value (`NULL` when it returns no row).
     ///
-    /// # Supported Comparisons
-    /// - **Integers**: `>`, `<`, `=`
-    /// - **Floats**: `>`, `<`, `=` (with epsilon comparison)
-    /// - **Text**: `=` (exact string match)
-    /// - **Booleans**: `=`
+    /// Before running, the comparisons of its `WHERE` that name a column of the outer
+    /// query are bound to the value of that column in `outer_row` (see
+    /// [Self::bind_outer_row]). The subquery runs once per outer row, so the cost is
+    /// the product of the two table sizes.
     ///
     /// # Errors
-    /// Returns an error if comparing incompatible types (e.g., `Int` vs `Text`).
-    fn compare_values(
+    /// Returns an error if the subquery fails, returns several columns or several rows.
+    fn evaluate_correlated(
         &self,
-        left: &Value,
-        op: &ComparisonOp,
-        right: &Value,
-    ) -> Result<bool, String> {
-        // NULL handling : NULL comparé à quoi que ce soit = false
-        if matches!(left, Value::Null) || matches!(right, Value::Null) {
-            return Ok(false);
+        query: &Select<'a>,
+        outer_row: &[Value],
+        outer_names: &[&'a str],
+        outer_schema: &Schema,
+    ) -> Result<Value, String> {
+        let mut query = query.clone();
+        if let Some(expr) = &mut query.where_clause {
+            let inner = Self::query_names(query.table, query.alias, query.join.as_ref());
+            let outer = OuterRow {
+                names: outer_names,
+                schema: outer_schema,
+                row: outer_row,
+            };
+            self.bind_outer_row(expr, &inner, query.join.is_some(), &outer)?;
+        }
+        let result = self.execute_select(query, &mut QueryStats::default())?;
+        if result.columns.len() != 1 {
+            return Err(format!(
+                "Subquery in the projection must return one column, got {}",
+                result.columns.len()
+            ));
+        }
+        let mut rows = result.rows.into_iter();
+        match (rows.next(), rows.next()) {
+            (None, _) => Ok(Value::Null),
+            (Some(mut row), None) => Ok(row.swap_remove(0)),
+            (Some(_), Some(_)) => {
+                Err("Subquery in the projection must return at most one row".into())
+            }
         }
+    }
 
-        match (left, op, right) {
-            // Int comparisons
-            (Value::Int(l), ComparisonOp::Gt, Value::Int(r)) => Ok(l > r),
-            (Value::Int(l), ComparisonOp::Lt, Value::Int(r)) => Ok(l < r),
-            (Value::Int(l), ComparisonOp::Eq, Value::Int(r)) => Ok(l == r),
+    /// Returns the names a query can qualify its columns with: its table, its alias and
+    /// the joined table with its alias.
+    fn query_names(
+        table: &'a str,
+        alias: Option<&'a str>,
+        join: Option<&Join<'a>>,
+    ) -> Vec<&'a str> {
+        let mut names = vec![table];
+        names.extend(alias);
+        if let Some(join) = join {
+            names.push(join.table);
+            names.extend(join.alias);
+        }
+        names
+    }
 
-            // Float comparisons
-            (Value::Float(l), ComparisonOp::Gt, Value::Float(r)) => Ok(l > r),
-            (Value::Float(l), ComparisonOp::Lt, Value::Float(r)) => Ok(l < r),
-            (Value::Float(l), ComparisonOp::Eq, Value::Float(r)) => {
-                Ok((l - r).abs() < f64::EPSILON)
+    /// Drops the qualifier of the columns of a single-table query, whose schema is not
+    /// qualified: `u.id` in `SELECT u.id FROM users u` is the column `id`.
+    ///
+    /// A name that is a column of `schema` as written is kept. Subqueries keep their
+    /// qualifiers, they are resolved when they run.
+    ///
+    /// # Errors
+    /// Returns an error if a column is qualified with a name other than the table or its
+    /// alias.
+    fn strip_qualifiers(select: &mut Select<'a>, schema: &Schema) -> Result<(), String> {
+        let names = Self::query_names(select.table, select.alias, None);
+        if let ColumnsSelect::Items(items) = &mut select.columns {
+            for item in items {
+                match item {
+                    SelectItem::Column(name) => Self::unqualify(name, &names, schema)?,
+                    SelectItem::Computed { expr, .. } => {
+                        Self::unqualify_scalar(expr, &names, schema)?
+                    }
+                    SelectItem::Aggregate {
+                        function, filter, ..
+                    } => {
+                        Self::unqualify_aggregate(function, &names, schema)?;
+                        if let Some(expr) = filter {
+                            Self::unqualify_expr(expr, &names, schema)?;
+                        }
+                    }
+                    SelectItem::Star | SelectItem::Subquery { .. } => {}
+                }
             }
+        }
+        for expr in select.where_clause.iter_mut().chain(&mut select.having) {
+            Self::unqualify_expr(expr, &names, schema)?;
+        }
+        for name in select.group_by.iter_mut().flatten() {
+            Self::unqualify(name, &names, schema)?;
+        }
+        for clause in select.order_by.iter_mut().flatten() {
+            Self::unqualify_scalar(&mut clause.expr, &names, schema)?;
+        }
+        Ok(())
+    }
 
-            // Text comparisons
-            (Value::Text(l), ComparisonOp::Eq, Value::Text(r)) => Ok(Arc::ptr_eq(l, r)),
-
-            // Bool comparisons
-            (Value::Bool(l), ComparisonOp::Eq, Value::Bool(r)) => Ok(l == r),
+    /// Drops the qualifier of `name` if it is one of `names`, see [Self::strip_qualifiers].
+    fn unqualify(name: &mut &'a str, names: &[&'a str], schema: &Schema) -> Result<(), String> {
+        let full: &'a str = name;
+        // a column created from a join result may be named `u.name` itself
+        if schema.columns.iter().any(|col| col.name == full) {
+            return Ok(());
+        }
+        if let Some((qualifier, short)) = full.split_once('.') {
+            if !names.contains(&qualifier) {
+                return Err(format!(
+                    "Unknown table or alias '{}' in column '{}'",
+                    qualifier, full
+                ));
+            }
+            *name = short;
+        }
+        Ok(())
+    }
 
-            // Type mismatch
-            _ => Err(format!(
-                "Type mismatch: cannot compare {:?} with {:?}",
-                left, right
-            )),
+    fn unqualify_scalar(
+        expr: &mut ScalarExpr<'a>,
+        names: &[&'a str],
+        schema: &Schema,
+    ) -> Result<(), String> {
+        match expr {
+            ScalarExpr::Column(name) => Self::unqualify(name, names, schema),
+            ScalarExpr::Literal(_) => Ok(()),
+            ScalarExpr::Binary { left, right, .. } => {
+                Self::unqualify_scalar(left, names, schema)?;
+                Self::unqualify_scalar(right, names, schema)
+            }
         }
     }
 
-    /// Internal helper to check if a table needs vacuuming and execute it.
-    fn maybe_auto_vacuum(&mut self, table_name: &str) -> Result<(), String> {
-        // don't vacuum during snapshot to be consistent in case of rollback
-        if self.transaction_snapshot.is_some() {
-            return Ok(());
+    fn unqualify_aggregate(
+        function: &mut Aggregate<'a>,
+        names: &[&'a str],
+        schema: &Schema,
+    ) -> Result<(), String> {
+        match function {
+            Aggregate::Sum(name)
+            | Aggregate::Avg(name)
+            | Aggregate::Min(name)
+            | Aggregate::Max(name)
+            | Aggregate::Count(name)
+            | Aggregate::ApproxCountDistinct(name) => Self::unqualify(name, names, schema),
+            Aggregate::CountStar => Ok(()),
         }
-        let needs_vacuum = self
-            .tables
-            .get(table_name)
-            .map(|t| t.should_vacuum(&self.vacuum_config))
-            .unwrap_or(false);
+    }
 
-        if needs_vacuum {
-            self.vacuum(Some(table_name))?;
+    fn unqualify_expr(
+        expr: &mut Expr<'a>,
+        names: &[&'a str],
+        schema: &Schema,
+    ) -> Result<(), String> {
+        match expr {
+            Expr::Comparison { column, .. }
+            | Expr::Subquery { column, .. }
+            | Expr::IsNull { column, .. }
+            | Expr::InList { column, .. }
+            | Expr::Like { column, .. } => Self::unqualify(column, names, schema),
+            Expr::Between {
+                column, low, high, ..
+            } => {
+                Self::unqualify(column, names, schema)?;
+                Self::unqualify_scalar(low, names, schema)?;
+                Self::unqualify_scalar(high, names, schema)
+            }
+            Expr::ColumnComparison { left, right, .. } => {
+                Self::unqualify(left, names, schema)?;
+                Self::unqualify(right, names, schema)
+            }
+            Expr::AggregateComparison { aggregate, .. } => {
+                Self::unqualify_aggregate(aggregate, names, schema)
+            }
+            Expr::And { left, right } | Expr::Or { left, right } => {
+                Self::unqualify_expr(left, names, schema)?;
+                Self::unqualify_expr(right, names, schema)
+            }
+            Expr::Not(inner) => Self::unqualify_expr(inner, names, schema),
+            Expr::Literal(_) => Ok(()),
         }
-        Ok(())
     }
 
-    /// Starts an explicit transaction by cloning the entire table map as a rollback point.
+    /// Replaces the references to the outer query in the `WHERE` clause of a correlated
+    /// subquery by their value in the `outer` row, `BETWEEN` bounds included.
     ///
-    /// The clone is a full deep copy, so memory usage doubles for the snapshot duration.
-    /// Nested transactions are not supported: calling `BEGIN` while one is already active
-    /// returns an error instead of creating a savepoint.
+    /// A column belongs to the outer query when it is qualified with a name that is not
+    /// one of `inner_names` (the subquery's table, alias and joined table), or when it
+    /// is unqualified and the subquery's table does not have it. The qualifier of an
+    /// inner column is dropped when the subquery reads a single table, whose columns
+    /// are not qualified.
     ///
     /// # Errors
-    /// Returns an error if a transaction is already in progress.
-    fn begin_transaction(&mut self) -> Result<(), String> {
-        if self.transaction_snapshot.is_some() {
-            return Err("Transaction already in progress".to_string());
+    /// Returns an error if a column of the outer query does not exist, if a qualifier
+    /// names neither query, or if an outer column is tested `BETWEEN` bounds computed
+    /// from inner columns.
+    fn bind_outer_row(
+        &self,
+        expr: &mut Expr<'a>,
+        inner_names: &[&'a str],
+        inner_joined: bool,
+        outer: &OuterRow<'_, 'a>,
+    ) -> Result<(), String> {
+        // `Ok(name)` for a column of the subquery, `Err(value)` for an outer column
+        let resolve = |name: &'a str| -> Result<Result<&'a str, Value>, String> {
+            match name.split_once('.') {
+                Some((qualifier, short)) if inner_names.contains(&qualifier) => {
+                    Ok(Ok(if inner_joined { name } else { short }))
+                }
+                Some((qualifier, short)) => {
+                    let idx = match outer.schema.index_of(name) {
+                        Ok(idx) => idx,
+                        Err(_) if outer.names.contains(&qualifier) => {
+                            outer.schema.index_of(short)?
+                        }
+                        Err(_) => {
+                            return Err(format!(
+                                "Unknown table or alias '{}' in column '{}'",
+                                qualifier, name
+                            ));
+                        }
+                    };
+                    Ok(Err(outer.row[idx].clone()))
+                }
+                None => {
+                    let inner_has = self
+                        .get_table(inner_names[0])
+                        .is_some_and(|table| table.schema.index_of(name).is_ok());
+                    match outer.schema.index_of(name) {
+                        Ok(idx) if !inner_has && !inner_joined => Ok(Err(outer.row[idx].clone())),
+                        _ => Ok(Ok(name)),
+                    }
+                }
+            }
+        };
+
+        match expr {
+            Expr::ColumnComparison { left, op, right } => {
+                *expr = match (resolve(left)?, resolve(right)?) {
+                    (Ok(left), Ok(right)) => Expr::ColumnComparison {
+                        left,
+                        op: op.clone(),
+                        right,
+                    },
+                    (Ok(column), Err(value)) => Expr::Comparison {
+                        column,
+                        op: op.clone(),
+                        value,
+                    },
+                    // `outer < inner` is `inner > outer`
+                    (Err(value), Ok(column)) => Expr::Comparison {
+                        column,
+                        op: match op {
+                            ComparisonOp::Lt => ComparisonOp::Gt,
+                            ComparisonOp::Gt => ComparisonOp::Lt,
+                            ComparisonOp::Lte => ComparisonOp::Gte,
+                            ComparisonOp::Gte => ComparisonOp::Lte,
+                            ComparisonOp::Eq => ComparisonOp::Eq,
+                            ComparisonOp::Neq => ComparisonOp::Neq,
+                            ComparisonOp::NullSafeEq => ComparisonOp::NullSafeEq,
+                        },
+                        value,
+                    },
+                    (Err(left), Err(right)) => {
+                        Expr::Literal(self.compare_values(&left, op, &right)?)
+                    }
+                };
+            }
+            Expr::Comparison { column, op, value } => match resolve(column)? {
+                Ok(name) => *column = name,
+                // a condition on the outer row alone is the same for every inner row
+                Err(outer) => *expr = Expr::Literal(self.compare_values(&outer, op, value)?),
+            },
+            Expr::IsNull { column, negated } => match resolve(column)? {
+                Ok(name) => *column = name,
+                Err(outer) => *expr = Expr::Literal(outer.is_null() != *negated),
+            },
+            Expr::InList {
+                column,
+                values,
+                negated,
+            } => match resolve(column)? {
+                Ok(name) => *column = name,
+                Err(outer) => *expr = Expr::Literal(self.in_list(&outer, values, *negated)?),
+            },
+            Expr::Like {
+                column,
+                pattern,
+                negated,
+            } => match resolve(column)? {
+                Ok(name) => *column = name,
+                Err(outer) => *expr = Expr::Literal(self.like(&outer, pattern, *negated)?),
+            },
+            Expr::Between { column, low, high } => {
+                Self::bind_outer_scalar(low, &resolve)?;
+                Self::bind_outer_scalar(high, &resolve)?;
+                match resolve(column)? {
+                    Ok(name) => *column = name,
+                    // `outer BETWEEN low AND high` is `low <= outer AND high >= outer`
+                    Err(outer) => {
+                        let low = std::mem::replace(low, ScalarExpr::Literal(Value::Null));
+                        let high = std::mem::replace(high, ScalarExpr::Literal(Value::Null));
+                        *expr = Expr::And {
+                            left: Box::new(self.outer_bound(low, ComparisonOp::Lte, &outer)?),
+                            right: Box::new(self.outer_bound(high, ComparisonOp::Gte, &outer)?),
+                        };
+                    }
+                }
+            }
+            Expr::And { left, right } | Expr::Or { left, right } => {
+                self.bind_outer_row(left, inner_names, inner_joined, outer)?;
+                self.bind_outer_row(right, inner_names, inner_joined, outer)?;
+            }
+            Expr::Not(inner) => {
+                self.bind_outer_row(inner, inner_names, inner_joined, outer)?;
+            }
+            Expr::AggregateComparison { .. } | Expr::Subquery { .. } | Expr::Literal(_) => {}
         }
-        self.transaction_snapshot = Some(self.tables.clone());
         Ok(())
     }
 
-    /// Makes the current transaction permanent by dropping the rollback snapshot.
-    ///
-    /// After `COMMIT`, all mutations since `BEGIN` are durably part of the live state.
-    /// The snapshot is simply discarded — no additional work is needed because writes
-    /// were applied directly to `self.tables` throughout the transaction.
+    /// Replaces the outer columns of a `BETWEEN` bound by their value, as resolved by
+    /// `resolve` in [Self::bind_outer_row].
+    fn bind_outer_scalar(
+        expr: &mut ScalarExpr<'a>,
+        resolve: &impl Fn(&'a str) -> Result<Result<&'a str, Value>, String>,
+    ) -> Result<(), String> {
+        match expr {
+            ScalarExpr::Column(name) => match resolve(name)? {
+                Ok(inner) => *name = inner,
+                Err(outer) => *expr = ScalarExpr::Literal(outer),
+            },
+            ScalarExpr::Literal(_) => {}
+            ScalarExpr::Binary { left, right, .. } => {
+                Self::bind_outer_scalar(left, resolve)?;
+                Self::bind_outer_scalar(right, resolve)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the condition `bound op outer` for one bound of an outer column tested
+    /// `BETWEEN` bounds: a constant when the bound reads no inner column, a comparison
+    /// of the inner column otherwise.
     ///
     /// # Errors
-    /// Returns an error if no transaction is in progress.
-    fn commit_transaction(&mut self) -> Result<(), String> {
-        self.transaction_snapshot
-            .take()
-            .ok_or_else(|| "No transaction in progress".to_string())?;
-        Ok(())
+    /// Returns an error if the bound is an expression over inner columns.
+    fn outer_bound(
+        &self,
+        bound: ScalarExpr<'a>,
+        op: ComparisonOp,
+        outer: &Value,
+    ) -> Result<Expr<'a>, String> {
+        match bound {
+            ScalarExpr::Column(column) => Ok(Expr::Comparison {
+                column,
+                op,
+                value: outer.clone(),
+            }),
+            bound if bound.columns().is_empty() => {
+                let bound = Self::evaluate_scalar(&bound, &[], &Schema { columns: vec![] })?;
+                if bound.is_null() || outer.is_null() {
+                    return Ok(Expr::Literal(false));
+                }
+                Ok(Expr::Literal(self.compare_values(&bound, &op, outer)?))
+            }
+            bound => Err(format!(
+                "An outer column cannot be tested BETWEEN `{}` in a subquery",
+                bound
+            )),
+        }
     }
 
-    /// Cancels the current transaction by replacing the live table map with the snapshot
-    /// taken at `BEGIN`, atomically undoing every mutation since then.
+    /// Replaces `value` by its coercion to `target`, when there is one.
+    fn coerce_literal(value: &mut Value, target: Option<DataType>) {
+        if let Some(coerced) = target.and_then(|t| value.coerce_to(t)) {
+            *value = coerced;
+        }
+    }
+
+    /// Points a text literal to the table's interned copy of the same string, if any.
+    fn bind_literal(value: &mut Value, table: Option<&Table>) {
+        if let Value::Text(s) = value
+            && let Some(interned) = table.and_then(|table| table.lookup_string(s))
+        {
+            *s = interned;
+        }
+    }
+
+    /// Sorts the provided rows in-place based on the SQL `ORDER BY` clauses.
     ///
-    /// Uses `Option::take` to move the snapshot out of `self.transaction_snapshot` in one
-    /// step, leaving it `None` (transaction closed) before the swap completes.
+    /// This method supports multi-column sorting. For each row comparison, it
+    /// iterates through the sort clauses: if the first column results in an
+    /// equal comparison, it moves to the next column, and so on.
+    ///
+    /// A clause may also name the alias of a computed projection in `items`
+    /// (`SELECT age * 2 AS double_age ... ORDER BY double_age`): the expression is then
+    /// evaluated for every row before sorting. Any other expression (`ORDER BY price * qty`)
+    /// is evaluated the same way.
+    ///
+    /// With [ComparisonConfig::deterministic_order], the rowid of each row is appended as
+    /// a last ascending key. Rows arrive in rowid order from the scan, so their position
+    /// stands for it.
     ///
     /// # Errors
-    /// Returns an error if no transaction is in progress.
-    fn rollback_transaction(&mut self) -> Result<(), String> {
-        match self.transaction_snapshot.take() {
-            Some(snapshot) => {
-                self.tables = snapshot;
-                Ok(())
+    /// Returns an error if a column specified in the `ORDER BY` clause is neither
+    /// an alias nor a column of the table schema, or if a sort expression fails to
+    /// evaluate (e.g. arithmetic on text).
+    fn sort(
+        &self,
+        rows: &mut [Vec<Value>],
+        schema: &Schema,
+        items: &[SelectItem],
+        order_by: Vec<OrderByClause>,
+    ) -> Result<(), String> {
+        let width = schema.columns.len();
+        let mut computed = vec![];
+
+        // save all column indexes on which we have to sort + boolean if we have to reverse order.
+        // A projection alias takes precedence over a table column of the same name.
+        let mut sort = order_by
+            .iter()
+            .map(|clause| {
+                let idx = match &clause.expr {
+                    ScalarExpr::Column(name) => {
+                        let aliased = items.iter().find_map(|item| match item {
+                            SelectItem::Computed {
+                                expr,
+                                alias: Some(alias),
+                            } if alias == name => Some(expr),
+                            _ => None,
+                        });
+                        match aliased {
+                            Some(expr) => {
+                                computed.push(expr);
+                                width + computed.len() - 1
+                            }
+                            None => schema.index_of(name)?,
+                        }
+                    }
+                    expr => {
+                        for column in expr.columns() {
+                            schema.index_of(column)?;
+                        }
+                        computed.push(expr);
+                        width + computed.len() - 1
+                    }
+                };
+                Ok((
+                    idx,
+                    clause.direction == SortDirection::Desc,
+                    clause.nulls_first(),
+                ))
+            })
+            .collect::<Result<Vec<(usize, bool, bool)>, String>>()?;
+
+        // aliased and computed expressions are evaluated once per row and appended as
+        // extra sort keys
+        for row in rows.iter_mut() {
+            for expr in &computed {
+                let value = Self::evaluate_scalar(expr, row, schema)?;
+                row.push(value);
             }
-            None => Err("No transaction in progress".to_string()),
         }
-    }
-}
+        if self.comparison_config.deterministic_order {
+            for (rowid, row) in rows.iter_mut().enumerate() {
+                row.push(Value::UInt(rowid as u64));
+            }
+            sort.push((width + computed.len(), false, false));
+        }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::data_type::DataType;
-    use crate::table::{ColumnDef, Schema};
-    use crate::value::Value;
+        Self::sort_by_keys(rows, &sort);
 
-    fn simple_schema() -> Schema {
-        Schema {
-            columns: vec![
-                ColumnDef::new("id", DataType::Int),
-                ColumnDef::new("name", DataType::Text),
-            ],
+        for row in rows.iter_mut() {
+            row.truncate(width);
         }
+
+        Ok(())
     }
 
-    #[test]
-    fn test_create_and_drop_table() {
+    /// Sorts the output of a grouped query on the `ORDER BY` clauses.
+    ///
+    /// Groups come out in the order they are first seen; here each clause must name a
+    /// column of the result (a group key or an alias), since the rows of the groups are
+    /// gone by then.
+    ///
+    /// # Errors
+    /// Returns an error if a clause is an expression or names a column that is not
+    /// selected.
+    fn sort_groups(result: &mut QueryResult, order_by: &[OrderByClause]) -> Result<(), String> {
+        let sort = order_by
+            .iter()
+            .map(|clause| {
+                let idx = match &clause.expr {
+                    ScalarExpr::Column(name) => result.columns.iter().position(|col| col == name),
+                    _ => None,
+                }
+                .ok_or_else(|| {
+                    format!(
+                        "ORDER BY {} in a grouped query must name a selected column",
+                        clause.expr
+                    )
+                })?;
+                Ok((
+                    idx,
+                    clause.direction == SortDirection::Desc,
+                    clause.nulls_first(),
+                ))
+            })
+            .collect::<Result<Vec<(usize, bool, bool)>, String>>()?;
+        Self::sort_by_keys(&mut result.rows, &sort);
+        Ok(())
+    }
+
+    /// Stable sort of `rows` on `(index, descending, nulls_first)` keys, the first key
+    /// being the most significant. Values are compared with [Value::sort_cmp].
+    ///
+    /// `NULL` placement is decided for each key on its own, so a tiebreak key puts its
+    /// `NULL`s where its own clause asks, whatever the direction of the earlier keys.
+    fn sort_by_keys(rows: &mut [Vec<Value>], keys: &[(usize, bool, bool)]) {
+        rows.sort_by(|a, b| {
+            for &(idx, is_desc, nulls_first) in keys {
+                let ord = match (&a[idx], &b[idx]) {
+                    (Value::Null, Value::Null) => Ordering::Equal,
+                    (Value::Null, _) if nulls_first => Ordering::Less,
+                    (Value::Null, _) => Ordering::Greater,
+                    (_, Value::Null) if nulls_first => Ordering::Greater,
+                    (_, Value::Null) => Ordering::Less,
+                    (a, b) if is_desc => b.sort_cmp(a),
+                    (a, b) => a.sort_cmp(b),
+                };
+                // if it's not equal no need to compare more
+                if ord != Ordering::Equal {
+                    return ord;
+                }
+            }
+            Ordering::Equal
+        });
+    }
+
+    /// Compiles a WHERE clause expression into a predicate evaluated against full rows.
+    ///
+    /// The expression tree is walked once per query instead of once per row: column
+    /// names are resolved to indices and literal types are checked up front, so the
+    /// returned closure only reads values and compares them. Text literals are expected
+    /// to be bound to the table interner beforehand (see [Self::bind_expression]).
+    ///
+    /// The predicate handles:
+    /// - **Comparisons**: Column values compared to literals (`age > 18`)
+    /// - **BETWEEN**: Inclusive ranges whose bounds may read other columns of the row
+    ///   (`x BETWEEN low AND high`); a `NULL` value or bound never matches
+    /// - **AND**: Logical conjunction with short-circuit evaluation
+    /// - **OR**: Logical disjunction with short-circuit evaluation
+    /// - **Literals**: `TRUE`/`FALSE`, resolved without reading the row
+    ///
+    /// # Errors
+    /// Returns an error on an invalid column name, a literal whose type does not match
+    /// its column, or an aggregate (only allowed in `HAVING`). Comparison errors are
+    /// prefixed with the column and operator they come from.
+    fn compile_expr<'e>(
+        &'e self,
+        expr: &'e Expr,
+        schema: &Schema,
+    ) -> Result<RowPredicate<'e>, String> {
+        match expr {
+            Expr::Comparison { column, op, value } => {
+                let col_idx = schema.index_of(column)?;
+                let col_type = schema.columns[col_idx].data_type;
+                // errors name the column and operator, which the values alone don't tell
+                let context = move |err: String| {
+                    format!(
+                        "in WHERE clause, column `{}` with operator `{}`: {}",
+                        column, op, err
+                    )
+                };
+                if let Some(value_type) = value
+                    .data_type()
+                    .filter(|&t| !Self::comparable(t, col_type))
+                {
+                    return Err(context(format!(
+                        "cannot compare {:?} with {:?}",
+                        col_type, value_type
+                    )));
+                }
+
+                Ok(Box::new(move |row| {
+                    self.compare_values(&row[col_idx], op, value)
+                        .map_err(context)
+                }))
+            }
+            Expr::Between { column, low, high } => {
+                let col_idx = schema.index_of(column)?;
+                let col_type = schema.columns[col_idx].data_type;
+                let context = move |err: String| {
+                    format!("in WHERE clause, column `{}` BETWEEN: {}", column, err)
+                };
+                for bound in [low, high] {
+                    if let Some(bound_type) =
+                        Self::scalar_type(bound, schema).filter(|&t| !Self::comparable(t, col_type))
+                    {
+                        return Err(context(format!(
+                            "cannot compare {:?} with bound `{}` of type {:?}",
+                            col_type, bound, bound_type
+                        )));
+                    }
+                }
+                let low = Self::compile_scalar(low, schema)?;
+                let high = Self::compile_scalar(high, schema)?;
+
+                Ok(Box::new(move |row| {
+                    let value = &row[col_idx];
+                    let (low, high) = (low(row)?, high(row)?);
+                    if value.is_null() || low.is_null() || high.is_null() {
+                        return Ok(false);
+                    }
+                    Ok(!self
+                        .compare_values(value, &ComparisonOp::Lt, &low)
+                        .map_err(context)?
+                        && !self
+                            .compare_values(value, &ComparisonOp::Gt, &high)
+                            .map_err(context)?)
+                }))
+            }
+            Expr::ColumnComparison { left, op, right } => {
+                let left_idx = schema.index_of(left)?;
+                let right_idx = schema.index_of(right)?;
+                let context = move |err: String| {
+                    format!("in WHERE clause, `{} {} {}`: {}", left, op, right, err)
+                };
+                let (left_type, right_type) = (
+                    schema.columns[left_idx].data_type,
+                    schema.columns[right_idx].data_type,
+                );
+                if !Self::comparable(left_type, right_type) {
+                    return Err(context(format!(
+                        "cannot compare {:?} with {:?}",
+                        left_type, right_type
+                    )));
+                }
+
+                Ok(Box::new(move |row| {
+                    self.compare_values(&row[left_idx], op, &row[right_idx])
+                        .map_err(context)
+                }))
+            }
+            Expr::IsNull { column, negated } => {
+                let col_idx = schema.index_of(column)?;
+                let negated = *negated;
+                Ok(Box::new(move |row| Ok(row[col_idx].is_null() != negated)))
+            }
+            Expr::Or { left, right } => {
+                let left = self.compile_expr(left, schema)?;
+                let right = self.compile_expr(right, schema)?;
+                Ok(Box::new(move |row| Ok(left(row)? || right(row)?)))
+            }
+            Expr::And { left, right } => {
+                let left = self.compile_expr(left, schema)?;
+                let right = self.compile_expr(right, schema)?;
+                Ok(Box::new(move |row| Ok(left(row)? && right(row)?)))
+            }
+            Expr::InList {
+                column,
+                values,
+                negated,
+            } => self.compile_in_list(column, values, *negated, schema),
+            Expr::Like {
+                column,
+                pattern,
+                negated,
+            } => {
+                let col_idx = schema.index_of(column)?;
+                let col_type = schema.columns[col_idx].data_type;
+                if col_type != DataType::Text {
+                    return Err(format!(
+                        "in WHERE clause, column `{}` LIKE: expected a TEXT column, got {:?}",
+                        column, col_type
+                    ));
+                }
+                let negated = *negated;
+                Ok(Box::new(move |row| {
+                    self.like(&row[col_idx], pattern, negated)
+                }))
+            }
+            Expr::Not(inner) => self.compile_negated(inner, schema),
+            Expr::Literal(b) => {
+                let b = *b;
+                Ok(Box::new(move |_| Ok(b)))
+            }
+            Expr::AggregateComparison { aggregate, .. } => Err(format!(
+                "Aggregate {} is only allowed in a HAVING clause",
+                aggregate
+            )),
+            Expr::Subquery { column, .. } => Err(format!(
+                "Internal error: subquery on column {} was not resolved",
+                column
+            )),
+        }
+    }
+
+    /// Resolves the columns of a scalar expression to their row index once, returning a
+    /// closure that evaluates it against full rows (see [Self::evaluate_scalar]).
+    ///
+    /// # Errors
+    /// Returns an error if a column does not exist.
+    fn compile_scalar<'e>(expr: &'e ScalarExpr, schema: &Schema) -> Result<ScalarFn<'e>, String> {
+        match expr {
+            ScalarExpr::Column(name) => {
+                let idx = schema.index_of(name)?;
+                Ok(Box::new(move |row| Ok(row[idx].clone())))
+            }
+            ScalarExpr::Literal(value) => Ok(Box::new(move |_| Ok(value.clone()))),
+            ScalarExpr::Binary { left, op, right } => {
+                let left = Self::compile_scalar(left, schema)?;
+                let right = Self::compile_scalar(right, schema)?;
+                Ok(Box::new(move |row| {
+                    Self::apply_arithmetic(&left(row)?, *op, &right(row)?)
+                }))
+            }
+        }
+    }
+
+    /// Evaluates a `HAVING` expression against one group of rows.
+    ///
+    /// Unlike [Self::compile_expr], there is no single row to read from: aggregates are
+    /// computed over `group_rows`, and plain columns are resolved from the group key, so
+    /// they must appear in `group_by_cols`.
+    ///
+    /// # Errors
+    /// Returns an error if a column is neither grouped nor aggregated, or on type mismatch.
+    fn evaluate_having(
+        &self,
+        expr: &Expr,
+        group_rows: &[Vec<Value>],
+        group_by_cols: &[&str],
+        schema: &Schema,
+    ) -> Result<bool, String> {
+        match expr {
+            Expr::Comparison { column, op, value } => {
+                if !group_by_cols.contains(column) {
+                    return Err(format!(
+                        "Column {} in HAVING must appear in GROUP BY or be used in an aggregate",
+                        column
+                    ));
+                }
+                let idx = schema.index_of(column)?;
+                // a grouped column has the same value on every row of the group
+                let group_value = group_rows.first().map_or(&Value::Null, |row| &row[idx]);
+                self.compare_values(group_value, op, value)
+            }
+            // NOT is pushed down the tree, so a comparison on a NULL aggregate stays unknown
+            Expr::Not(inner)
+                if matches!(
+                    **inner,
+                    Expr::Not(_)
+                        | Expr::And { .. }
+                        | Expr::Or { .. }
+                        | Expr::AggregateComparison { .. }
+                ) =>
+            {
+                match &**inner {
+                    Expr::Not(inner) => {
+                        self.evaluate_having(inner, group_rows, group_by_cols, schema)
+                    }
+                    Expr::AggregateComparison {
+                        aggregate,
+                        op,
+                        value,
+                    } => {
+                        let result = Self::compute_single_aggregate(aggregate, group_rows, schema)?;
+                        let unknown = *op != ComparisonOp::NullSafeEq
+                            && (result.is_null() || value.is_null());
+                        Ok(!unknown && !self.compare_values(&result, op, value)?)
+                    }
+                    Expr::And { left, right } | Expr::Or { left, right } => {
+                        let [left, right] = [left, right].map(|side| {
+                            self.evaluate_having(
+                                &Expr::Not(side.clone()),
+                                group_rows,
+                                group_by_cols,
+                                schema,
+                            )
+                        });
+                        Ok(if matches!(**inner, Expr::And { .. }) {
+                            left? || right?
+                        } else {
+                            left? && right?
+                        })
+                    }
+                    _ => unreachable!(),
+                }
+            }
+            // conditions on grouped columns only, NOT included, are compiled
+            Expr::Between { .. }
+            | Expr::ColumnComparison { .. }
+            | Expr::IsNull { .. }
+            | Expr::InList { .. }
+            | Expr::Like { .. }
+            | Expr::Not(_) => {
+                if let Some(column) = expr
+                    .columns()
+                    .into_iter()
+                    .find(|c| !group_by_cols.contains(c))
+                {
+                    return Err(format!(
+                        "Column {} in HAVING must appear in GROUP BY or be used in an aggregate",
+                        column
+                    ));
+                }
+                // every column involved is grouped, so any row of the group gives the answer
+                match group_rows.first() {
+                    Some(row) => self.compile_expr(expr, schema)?(row),
+                    None => Ok(false),
+                }
+            }
+            Expr::AggregateComparison {
+                aggregate,
+                op,
+                value,
+            } => {
+                let result = Self::compute_single_aggregate(aggregate, group_rows, schema)?;
+                self.compare_values(&result, op, value)
+            }
+            Expr::Or { left, right } => {
+                Ok(
+                    self.evaluate_having(left, group_rows, group_by_cols, schema)?
+                        || self.evaluate_having(right, group_rows, group_by_cols, schema)?,
+                )
+            }
+            Expr::And { left, right } => {
+                Ok(
+                    self.evaluate_having(left, group_rows, group_by_cols, schema)?
+                        && self.evaluate_having(right, group_rows, group_by_cols, schema)?,
+                )
+            }
+            Expr::Literal(b) => Ok(*b),
+            Expr::Subquery { column, .. } => Err(format!(
+                "Internal error: subquery on column {} was not resolved",
+                column
+            )),
+        }
+    }
+
+    /// Compiles `column IN (values)`, or `column NOT IN (values)` when `negated`.
+    ///
+    /// # Errors
+    /// Returns an error if the column does not exist or a value cannot be compared with it.
+    fn compile_in_list<'e>(
+        &'e self,
+        column: &'e str,
+        values: &'e [Value],
+        negated: bool,
+        schema: &Schema,
+    ) -> Result<RowPredicate<'e>, String> {
+        let col_idx = schema.index_of(column)?;
+        let col_type = schema.columns[col_idx].data_type;
+        let context =
+            move |err: String| format!("in WHERE clause, column `{}` IN: {}", column, err);
+        if let Some(value_type) = values
+            .iter()
+            .filter_map(Value::data_type)
+            .find(|&t| !Self::comparable(t, col_type))
+        {
+            return Err(context(format!(
+                "cannot compare {:?} with {:?}",
+                col_type, value_type
+            )));
+        }
+        Ok(Box::new(move |row| {
+            self.in_list(&row[col_idx], values, negated)
+                .map_err(context)
+        }))
+    }
+
+    /// Evaluates `value IN (values)`, or `value NOT IN (values)` when `negated`, with the
+    /// equality of `=`.
+    ///
+    /// A `NULL` value, or a `NULL` in the list when no value is equal, makes the test
+    /// unknown, so both `IN` and `NOT IN` are false.
+    fn in_list(&self, value: &Value, values: &[Value], negated: bool) -> Result<bool, String> {
+        if value.is_null() {
+            return Ok(false);
+        }
+        for candidate in values {
+            if self.compare_values(value, &ComparisonOp::Eq, candidate)? {
+                return Ok(!negated);
+            }
+        }
+        Ok(negated && !values.iter().any(Value::is_null))
+    }
+
+    /// Evaluates `value LIKE pattern`, or `value NOT LIKE pattern` when `negated`. The
+    /// match ignores case when [ComparisonConfig::case_sensitive] is off.
+    ///
+    /// A `NULL` value matches neither `LIKE` nor `NOT LIKE`.
+    ///
+    /// # Errors
+    /// Returns an error if `value` is not text.
+    fn like(&self, value: &Value, pattern: &str, negated: bool) -> Result<bool, String> {
+        let matched = match value {
+            Value::Null => return Ok(false),
+            Value::Text(text) if self.comparison_config.case_sensitive => like_match(text, pattern),
+            Value::Text(text) => like_match(&text.to_lowercase(), &pattern.to_lowercase()),
+            other => return Err(format!("LIKE applies to text, got {:?}", other)),
+        };
+        Ok(matched != negated)
+    }
+
+    /// Compiles the negation of `expr`, for `NOT expr`.
+    ///
+    /// The negation is pushed down to the comparisons (`NOT (a AND b)` is
+    /// `NOT a OR NOT b`), where a `NULL` operand keeps the condition unknown, hence false:
+    /// `NOT age > 18` does not match a row whose `age` is `NULL`. `IS NULL` and `<=>`,
+    /// which are never unknown, are simply inverted.
+    fn compile_negated<'e>(
+        &'e self,
+        expr: &'e Expr,
+        schema: &Schema,
+    ) -> Result<RowPredicate<'e>, String> {
+        match expr {
+            Expr::Not(inner) => self.compile_expr(inner, schema),
+            // NOT IN is its own test, as a NULL in the list makes both IN and NOT IN unknown
+            Expr::InList {
+                column,
+                values,
+                negated,
+            } => self.compile_in_list(column, values, !negated, schema),
+            Expr::And { left, right } => {
+                let left = self.compile_negated(left, schema)?;
+                let right = self.compile_negated(right, schema)?;
+                Ok(Box::new(move |row| Ok(left(row)? || right(row)?)))
+            }
+            Expr::Or { left, right } => {
+                let left = self.compile_negated(left, schema)?;
+                let right = self.compile_negated(right, schema)?;
+                Ok(Box::new(move |row| Ok(left(row)? && right(row)?)))
+            }
+            Expr::IsNull { .. }
+            | Expr::Literal(_)
+            | Expr::Comparison {
+                op: ComparisonOp::NullSafeEq,
+                ..
+            }
+            | Expr::ColumnComparison {
+                op: ComparisonOp::NullSafeEq,
+                ..
+            } => {
+                let inner = self.compile_expr(expr, schema)?;
+                Ok(Box::new(move |row| Ok(!inner(row)?)))
+            }
+            _ => {
+                let inner = self.compile_expr(expr, schema)?;
+                // a NULL literal makes the comparison unknown on every row
+                let null_literal = match expr {
+                    Expr::Comparison { value, .. } => value.is_null(),
+                    Expr::Between { low, high, .. } => [low, high]
+                        .iter()
+                        .any(|bound| matches!(bound, ScalarExpr::Literal(Value::Null))),
+                    _ => false,
+                };
+                let operands = expr
+                    .columns()
+                    .into_iter()
+                    .map(|column| schema.index_of(column))
+                    .collect::<Result<Vec<_>, String>>()?;
+                Ok(Box::new(move |row| {
+                    Ok(!null_literal
+                        && operands.iter().all(|&idx| !row[idx].is_null())
+                        && !inner(row)?)
+                }))
+            }
+        }
+    }
+
+    /// Returns `true` if values of types `a` and `b` can be compared: the same type, or
+    /// two numeric types (see [Self::compare_values]).
+    fn comparable(a: DataType, b: DataType) -> bool {
+        a == b || (a.is_numeric() && b.is_numeric())
+    }
+
+    /// Compares two values using a comparison operator.
+    ///
+    /// # SQL NULL Semantics
+    /// - `NULL` compared to anything (including `NULL`) always returns `false`.
+    /// - This matches standard SQL three-valued logic.
+    /// - The exception is `<=>`, for which two `NULL`s are equal.
+    ///
+    /// # Supported Comparisons
+    /// - **Integers**: `>`, `<`, `=`. `INT` and `UINT` can be mixed: both sides are
+    ///   widened to `i128`, so `-1 < 0` holds even against an unsigned column.
+    /// - **Floats**: `>`, `<`, `=` (within [ComparisonConfig::float_epsilon]). An integer
+    ///   can be compared with a float, so `price > 9` works on a `FLOAT` column and
+    ///   `qty = 3.0` on an `INT` one: `>` and `<` are exact, like [Value::sort_cmp], and `=`
+    ///   converts the integer to `f64` to apply the tolerance.
+    /// - **Text**: `=` (exact string match) and `>`, `<` (lexicographic byte order),
+    ///   ignoring case when [ComparisonConfig::case_sensitive] is off
+    /// - **Booleans**: `=`
+    /// - **Blobs**: `=` (byte-wise match)
+    ///
+    /// # Errors
+    /// Returns an error if comparing incompatible types (e.g., `Int` vs `Text`).
+    fn compare_values(
+        &self,
+        left: &Value,
+        op: &ComparisonOp,
+        right: &Value,
+    ) -> Result<bool, String> {
+        if *op == ComparisonOp::NullSafeEq {
+            return match (left, right) {
+                (Value::Null, Value::Null) => Ok(true),
+                (Value::Null, _) | (_, Value::Null) => Ok(false),
+                _ => self.compare_values(left, &ComparisonOp::Eq, right),
+            };
+        }
+
+        // NULL handling : NULL comparé à quoi que ce soit = false
+        if matches!(left, Value::Null) || matches!(right, Value::Null) {
+            return Ok(false);
+        }
+
+        if *op == ComparisonOp::Neq {
+            return Ok(!self.compare_values(left, &ComparisonOp::Eq, right)?);
+        }
+        // `>=` and `<=` are the strict comparison or equality, so they follow the float
+        // tolerance and text case settings of both
+        if let ComparisonOp::Gte | ComparisonOp::Lte = op {
+            let strict = match op {
+                ComparisonOp::Gte => ComparisonOp::Gt,
+                _ => ComparisonOp::Lt,
+            };
+            return Ok(self.compare_values(left, &strict, right)?
+                || self.compare_values(left, &ComparisonOp::Eq, right)?);
+        }
+
+        match (left, op, right) {
+            // Int comparisons
+            (Value::Int(l), ComparisonOp::Gt, Value::Int(r)) => Ok(l > r),
+            (Value::Int(l), ComparisonOp::Lt, Value::Int(r)) => Ok(l < r),
+            (Value::Int(l), ComparisonOp::Eq, Value::Int(r)) => Ok(l == r),
+
+            // UInt comparisons, alone or mixed with Int
+            (Value::Int(_) | Value::UInt(_), _, Value::Int(_) | Value::UInt(_)) => {
+                let (l, r) = (widen_integer(left), widen_integer(right));
+                Ok(match op {
+                    ComparisonOp::Gt => l > r,
+                    ComparisonOp::Lt => l < r,
+                    ComparisonOp::Gte => l >= r,
+                    ComparisonOp::Lte => l <= r,
+                    ComparisonOp::Neq => l != r,
+                    ComparisonOp::Eq | ComparisonOp::NullSafeEq => l == r,
+                })
+            }
+
+            // Float comparisons
+            (Value::Float(l), ComparisonOp::Gt, Value::Float(r)) => Ok(l > r),
+            (Value::Float(l), ComparisonOp::Lt, Value::Float(r)) => Ok(l < r),
+            (Value::Float(l), ComparisonOp::Eq, Value::Float(r)) => {
+                Ok((l - r).abs() <= self.comparison_config.float_epsilon)
+            }
+            // an integer against a float: equal within the float tolerance, but ordered
+            // exactly, as `ORDER BY` does, where converting the integer could round it
+            (Value::Int(_) | Value::UInt(_), _, Value::Float(_))
+            | (Value::Float(_), _, Value::Int(_) | Value::UInt(_)) => match op {
+                ComparisonOp::Eq => {
+                    let to_f64 = |v: &Value| match v {
+                        Value::Int(i) => *i as f64,
+                        Value::UInt(u) => *u as f64,
+                        Value::Float(f) => *f,
+                        _ => unreachable!(),
+                    };
+                    self.compare_values(
+                        &Value::Float(to_f64(left)),
+                        op,
+                        &Value::Float(to_f64(right)),
+                    )
+                }
+                // NaN is unordered, as between two floats
+                _ if [left, right]
+                    .iter()
+                    .any(|v| matches!(v, Value::Float(f) if f.is_nan())) =>
+                {
+                    Ok(false)
+                }
+                ComparisonOp::Gt => Ok(left.sort_cmp(right) == Ordering::Greater),
+                _ => Ok(left.sort_cmp(right) == Ordering::Less),
+            },
+
+            // Text comparisons: interned strings share one allocation, so the pointer
+            // check settles most of them; strings from different tables need the bytes
+            (Value::Text(l), ComparisonOp::Eq, Value::Text(r)) => Ok(Arc::ptr_eq(l, r)
+                || if self.comparison_config.case_sensitive {
+                    l == r
+                } else {
+                    l.to_lowercase() == r.to_lowercase()
+                }),
+            // `>` and `<` are lexicographic on the UTF-8 bytes, like `ORDER BY`
+            (Value::Text(l), ComparisonOp::Gt | ComparisonOp::Lt, Value::Text(r)) => {
+                let ordering = if self.comparison_config.case_sensitive {
+                    l.cmp(r)
+                } else {
+                    l.to_lowercase().cmp(&r.to_lowercase())
+                };
+                Ok(match op {
+                    ComparisonOp::Gt => ordering == Ordering::Greater,
+                    _ => ordering == Ordering::Less,
+                })
+            }
+
+            // Bool comparisons
+            (Value::Bool(l), ComparisonOp::Eq, Value::Bool(r)) => Ok(l == r),
+
+            // Blob comparisons
+            (Value::Blob(l), ComparisonOp::Eq, Value::Blob(r)) => Ok(l == r),
+
+            // Timestamp comparisons
+            (Value::Timestamp(l), ComparisonOp::Gt, Value::Timestamp(r)) => Ok(l > r),
+            (Value::Timestamp(l), ComparisonOp::Lt, Value::Timestamp(r)) => Ok(l < r),
+            (Value::Timestamp(l), ComparisonOp::Eq, Value::Timestamp(r)) => Ok(l == r),
+
+            // Type mismatch
+            _ => Err(format!(
+                "Type mismatch: cannot compare {:?} with {:?}",
+                left, right
+            )),
+        }
+    }
+
+    /// Internal helper to check if a table needs vacuuming and execute it.
+    fn maybe_auto_vacuum(&mut self, table_name: &str) -> Result<(), String> {
+        // don't vacuum during snapshot to be consistent in case of rollback
+        if self.transaction_snapshot.is_some() {
+            return Ok(());
+        }
+        let needs_vacuum = self
+            .tables
+            .get(table_name)
+            .map(|t| t.should_vacuum(&self.vacuum_config))
+            .unwrap_or(false);
+
+        if needs_vacuum {
+            self.vacuum(Some(table_name))?;
+        }
+        Ok(())
+    }
+
+    /// Starts an explicit transaction by cloning the entire table map as a rollback point.
+    ///
+    /// The clone is a full deep copy, so memory usage doubles for the snapshot duration.
+    /// Nested transactions are not supported: calling `BEGIN` while one is already active
+    /// returns an error instead of creating a savepoint.
+    ///
+    /// # Errors
+    /// Returns an error if a transaction is already in progress.
+    fn begin_transaction(&mut self) -> Result<(), String> {
+        if self.transaction_snapshot.is_some() {
+            return Err("Transaction already in progress".to_string());
+        }
+        self.transaction_snapshot = Some(self.tables.clone());
+        Ok(())
+    }
+
+    /// Makes the current transaction permanent by dropping the rollback snapshot.
+    ///
+    /// After `COMMIT`, all mutations since `BEGIN` are durably part of the live state.
+    /// The snapshot is simply discarded — no additional work is needed because writes
+    /// were applied directly to `self.tables` throughout the transaction.
+    ///
+    /// # Errors
+    /// Returns an error if no transaction is in progress.
+    fn commit_transaction(&mut self) -> Result<(), String> {
+        self.transaction_snapshot
+            .take()
+            .ok_or_else(|| "No transaction in progress".to_string())?;
+        Ok(())
+    }
+
+    /// Cancels the current transaction by replacing the live table map with the snapshot
+    /// taken at `BEGIN`, atomically undoing every mutation since then.
+    ///
+    /// Uses `Option::take` to move the snapshot out of `self.transaction_snapshot` in one
+    /// step, leaving it `None` (transaction closed) before the swap completes.
+    ///
+    /// # Errors
+    /// Returns an error if no transaction is in progress.
+    fn rollback_transaction(&mut self) -> Result<(), String> {
+        match self.transaction_snapshot.take() {
+            Some(snapshot) => {
+                self.tables = snapshot;
+                Ok(())
+            }
+            None => Err("No transaction in progress".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_type::DataType;
+    use crate::table::{ColumnDef, Schema};
+    use crate::value::Value;
+
+    fn simple_schema() -> Schema {
+        Schema::builder()
+            .column("id", DataType::Int)
+            .column("name", DataType::Text)
+            .build()
+    }
+
+    /// Runs `sql` and returns the first column of its rows.
+    fn first_column(db: &Database, sql: &str) -> Vec<Value> {
+        db.query(sql)
+            .unwrap()
+            .rows
+            .into_iter()
+            .map(|mut row| row.remove(0))
+            .collect()
+    }
+
+    /// Wraps each string in a [Value::Text].
+    fn texts(values: &[&str]) -> Vec<Value> {
+        values
+            .iter()
+            .map(|value| Value::Text((*value).into()))
+            .collect()
+    }
+
+    #[test]
+    fn test_create_and_drop_table() {
+        let mut db = Database::new();
+
+        assert!(db.create_table("users", simple_schema()).is_ok());
+        assert!(db.get_table("users").is_some());
+
+        assert!(db.drop_table("users").is_ok());
+        assert!(db.get_table("users").is_none());
+    }
+
+    #[test]
+    fn test_drop_table_statement() {
+        let mut db = Database::new();
+        db.execute("CREATE TABLE users (id INT)").unwrap();
+        db.execute("INSERT INTO users VALUES (1)").unwrap();
+
+        db.execute("DROP TABLE users").unwrap();
+        assert!(db.get_table("users").is_none());
+        let err = db.execute("DROP TABLE users").unwrap_err();
+        assert!(err.contains("does not exist"), "{err}");
+        db.execute("DROP TABLE IF EXISTS users").unwrap();
+
+        // the name is free again, and a rolled back drop restores the rows
+        db.execute("CREATE TABLE users (name TEXT)").unwrap();
+        db.execute("INSERT INTO users VALUES ('Ada')").unwrap();
+        db.execute("BEGIN").unwrap();
+        db.execute("DROP TABLE IF EXISTS users").unwrap();
+        assert!(db.query("SELECT * FROM users").is_err());
+        db.execute("ROLLBACK").unwrap();
+        assert_eq!(
+            db.query("SELECT name FROM users").unwrap().rows,
+            vec![vec![Value::Text("Ada".into())]]
+        );
+    }
+
+    #[test]
+    fn test_truncate_table_statement() {
+        let mut db = Database::new();
+        db.enable_string_pool();
+        db.execute("CREATE TABLE users (id INT, name TEXT)")
+            .unwrap();
+        db.execute("INSERT INTO users VALUES (1, 'Ada'), (2, 'Bob')")
+            .unwrap();
+
+        db.execute("TRUNCATE TABLE users").unwrap();
+        assert!(db.query("SELECT * FROM users").unwrap().rows.is_empty());
+        assert_eq!(db.string_pool.as_ref().map(HashSet::len), Some(0));
+        let err = db.execute("TRUNCATE TABLE missing").unwrap_err();
+        assert!(err.contains("does not exist"), "{err}");
+
+        // the schema is kept, and a rolled back truncate restores the rows
+        db.execute("INSERT INTO users VALUES (3, 'Cy')").unwrap();
+        db.execute("BEGIN").unwrap();
+        db.execute("TRUNCATE users").unwrap();
+        db.execute("ROLLBACK").unwrap();
+        assert_eq!(
+            db.query("SELECT * FROM users").unwrap().rows,
+            vec![vec![Value::Int(3), Value::Text("Cy".into())]]
+        );
+    }
+
+    #[test]
+    fn test_alter_table_drop_column() {
+        let mut db = Database::new();
+        db.execute(
+            "CREATE TABLE users (id INT, name TEXT, age INT CHECK (age < cap), cap INT, \
+             twice INT GENERATED ALWAYS AS (id * 2))",
+        )
+        .unwrap();
+        db.execute("INSERT INTO users (id, name, age, cap) VALUES (1, 'Ada', 36, 99)")
+            .unwrap();
+        db.execute("INSERT INTO users (id, name, age, cap) VALUES (2, 'Bob', 25, 99)")
+            .unwrap();
+
+        db.execute("ALTER TABLE users DROP COLUMN name").unwrap();
+        let res = db.query("SELECT * FROM users").unwrap();
+        assert_eq!(res.columns, vec!["id", "age", "cap", "twice"]);
+        assert_eq!(
+            res.rows,
+            vec![
+                vec![Value::Int(1), Value::Int(36), Value::Int(99), Value::Int(2)],
+                vec![Value::Int(2), Value::Int(25), Value::Int(99), Value::Int(4)],
+            ]
+        );
+        assert_eq!(db.get_table("users").unwrap().row_count, 2);
+        assert!(db.query("SELECT name FROM users").is_err());
+
+        // columns still used by a constraint or a generated column stay
+        let err = db.execute("ALTER TABLE users DROP COLUMN cap").unwrap_err();
+        assert!(
+            err.starts_with("Cannot drop column \"cap\": CHECK"),
+            "{err}"
+        );
+        let err = db.execute("ALTER TABLE users DROP id").unwrap_err();
+        assert!(err.contains("GENERATED ALWAYS AS"), "{err}");
+
+        // dropping the constrained column drops its constraint with it
+        db.execute("ALTER TABLE users DROP COLUMN age").unwrap();
+        db.execute("ALTER TABLE users DROP COLUMN cap").unwrap();
+        db.execute("INSERT INTO users (id) VALUES (5)").unwrap();
+        assert_eq!(
+            db.query_scalar("SELECT twice FROM users WHERE id = 5"),
+            Ok(Value::Int(10))
+        );
+
+        let err = db
+            .execute("ALTER TABLE users DROP COLUMN nope")
+            .unwrap_err();
+        assert_eq!(err, "Column \"nope\" does not exist in table \"users\"");
+        assert!(db.execute("ALTER TABLE missing DROP COLUMN id").is_err());
+        db.execute("CREATE TABLE single (id INT)").unwrap();
+        let err = db.execute("ALTER TABLE single DROP COLUMN id").unwrap_err();
+        assert!(err.contains("only column"), "{err}");
+    }
+
+    #[test]
+    fn test_duplicate_table_error() {
+        let mut db = Database::new();
+
+        assert!(db.create_table("users", simple_schema()).is_ok());
+        let err = db.create_table("users", simple_schema());
+
+        assert!(err.is_err());
+    }
+
+    #[test]
+    fn test_create_table_validates_schema() {
+        let mut db = Database::new();
+
+        let err = db.create_table("empty", Schema { columns: vec![] });
+        assert!(err.unwrap_err().contains("at least one column"));
+
+        let duplicated = Schema {
+            columns: vec![
+                ColumnDef::new("id", DataType::Int),
+                ColumnDef::new("id", DataType::Text),
+            ],
+        };
+        assert!(db.create_table("dup", duplicated).is_err());
+        assert!(db.execute("CREATE TABLE dup (a INT, a TEXT)").is_err());
+        assert!(db.list_tables().is_empty());
+
+        assert!(db.create_table("users", simple_schema()).is_ok());
+    }
+
+    #[test]
+    fn test_create_table_as_select() {
+        let mut db = setup_employees();
+        db.execute(
+            "CREATE TABLE high_earners AS SELECT name, salary * 2 AS doubled, bonus \
+             FROM employees WHERE salary > 2500",
+        )
+        .unwrap();
+
+        let table = db.get_table("high_earners").unwrap();
+        let schema: Vec<_> = table
+            .schema
+            .columns
+            .iter()
+            .map(|c| (c.name.as_str(), c.data_type))
+            .collect();
+        assert_eq!(
+            schema,
+            vec![
+                ("name", DataType::Text),
+                ("doubled", DataType::Int),
+                ("bonus", DataType::Float),
+            ]
+        );
+
+        let res = db.query("SELECT * FROM high_earners").unwrap();
+        assert_eq!(
+            res.rows,
+            vec![
+                vec![
+                    Value::Text("Alice".into()),
+                    Value::Int(6000),
+                    Value::Float(500.0)
+                ],
+                vec![
+                    Value::Text("Bob".into()),
+                    Value::Int(8000),
+                    Value::Float(750.5)
+                ],
+            ]
+        );
+
+        // the derived table is independent from its source
+        db.execute("INSERT INTO high_earners VALUES ('Dave', 1, 0.0)")
+            .unwrap();
+        assert_eq!(db.query("SELECT * FROM employees").unwrap().rows.len(), 3);
+
+        assert!(
+            db.execute("CREATE TABLE high_earners AS SELECT name FROM employees")
+                .is_err()
+        );
+        assert!(
+            db.execute("CREATE TABLE dup AS SELECT name, name FROM employees")
+                .is_err()
+        );
+        assert!(db.get_table("dup").is_none());
+    }
+
+    #[test]
+    fn test_copy_table() {
+        let mut db = setup_employees();
+        db.copy_table("employees", "backup").unwrap();
+        assert_eq!(db.get_table("backup").unwrap().name, "backup");
+
+        db.execute("UPDATE backup SET salary = 0 WHERE name = 'Alice'")
+            .unwrap();
+        db.execute("DELETE FROM backup WHERE name = 'Bob'").unwrap();
+        db.execute("INSERT INTO backup VALUES ('Dave', 1000, 0.0)")
+            .unwrap();
+
+        let original = db.query("SELECT name, salary FROM employees").unwrap();
+        assert_eq!(
+            original.rows,
+            vec![
+                vec![Value::Text("Alice".into()), Value::Int(3000)],
+                vec![Value::Text("Bob".into()), Value::Int(4000)],
+                vec![Value::Text("Carol".into()), Value::Int(2000)],
+            ]
+        );
+        let copy = db.query("SELECT name, salary FROM backup").unwrap();
+        assert_eq!(
+            copy.rows,
+            vec![
+                vec![Value::Text("Alice".into()), Value::Int(0)],
+                vec![Value::Text("Carol".into()), Value::Int(2000)],
+                vec![Value::Text("Dave".into()), Value::Int(1000)],
+            ]
+        );
+
+        assert!(db.copy_table("employees", "backup").is_err());
+        assert!(db.copy_table("missing", "other").is_err());
+        assert!(db.get_table("other").is_none());
+    }
+
+    #[test]
+    fn test_clone_database_is_independent() {
+        let mut db = setup_employees();
+        let events = Arc::new(std::sync::Mutex::new(0));
+        let counter = Arc::clone(&events);
+        db.on_change(Box::new(move |_| *counter.lock().unwrap() += 1));
+
+        let mut snapshot = db.clone();
+        snapshot
+            .execute("DELETE FROM employees WHERE salary > 2500")
+            .unwrap();
+        snapshot.execute("CREATE TABLE extra (id INT)").unwrap();
+
+        assert_eq!(db.query("SELECT * FROM employees").unwrap().rows.len(), 3);
+        assert!(db.get_table("extra").is_none());
+        assert_eq!(
+            snapshot
+                .query("SELECT * FROM employees")
+                .unwrap()
+                .rows
+                .len(),
+            1
+        );
+        // hooks stay with the original database
+        assert_eq!(*events.lock().unwrap(), 0);
+
+        db.execute("DELETE FROM employees WHERE name = 'Carol'")
+            .unwrap();
+        assert_eq!(*events.lock().unwrap(), 1);
+        assert_eq!(
+            snapshot.query("SELECT name FROM employees").unwrap().rows,
+            vec![vec![Value::Text("Carol".into())]]
+        );
+    }
+
+    #[test]
+    fn test_insert_returning_id() {
+        let mut db = Database::new();
+        assert!(
+            db.run("CREATE TABLE users (id INT AUTO_INCREMENT, name TEXT)")
+                .unwrap()
+                .is_none()
+        );
+        db.execute("INSERT INTO users (name) VALUES ('Alice')")
+            .unwrap();
+
+        let res = db
+            .run("INSERT INTO users (name) VALUES ('Bob') RETURNING id, name")
+            .unwrap()
+            .unwrap();
+        assert_eq!(res.columns, vec!["id", "name"]);
+        assert_eq!(
+            res.rows,
+            vec![vec![Value::Int(2), Value::Text("Bob".into())]]
+        );
+        assert!(
+            db.run("INSERT INTO users (name) VALUES ('Carol')")
+                .unwrap()
+                .is_none()
+        );
+
+        // an invalid projection is rejected before the row is inserted
+        assert!(
+            db.run("INSERT INTO users (name) VALUES ('Dave') RETURNING nope")
+                .is_err()
+        );
+        let all = db.run("SELECT name FROM users").unwrap().unwrap();
+        assert_eq!(all.rows.len(), 3);
+    }
+
+    #[test]
+    fn test_update_with_expressions() {
+        let mut db = Database::new();
+        db.execute(
+            "CREATE TABLE counters (id INT, hits INT, rate FLOAT, label TEXT, \
+             double INT GENERATED ALWAYS AS (hits * 2))",
+        )
+        .unwrap();
+        db.execute("INSERT INTO counters (id, hits, rate, label) VALUES (5, 10, 1.5, 'a')")
+            .unwrap();
+        db.execute("INSERT INTO counters (id, hits, rate, label) VALUES (6, 0, 0.5, 'b')")
+            .unwrap();
+        let rows = |db: &Database| db.query("SELECT * FROM counters").unwrap().rows;
+
+        db.execute("UPDATE counters SET hits = hits + 1 WHERE id = 5")
+            .unwrap();
+        // every expression reads the row as it was before the update
+        db.execute("UPDATE counters SET hits = id, id = hits, rate = hits * 2 WHERE id = 5")
+            .unwrap();
+        assert_eq!(
+            rows(&db)[0],
+            vec![
+                Value::Int(11),
+                Value::Int(5),
+                Value::Float(22.0),
+                Value::Text("a".into()),
+                Value::Int(10),
+            ]
+        );
+
+        // failures leave every row untouched
+        let before = rows(&db);
+        for (sql, expected) in [
+            (
+                "UPDATE counters SET hits = 100 / hits WHERE id > 0",
+                "Division by zero",
+            ),
+            (
+                "UPDATE counters SET hits = label + 1 WHERE id > 0",
+                "Type mismatch",
+            ),
+            ("UPDATE counters SET label = hits WHERE id > 0", "mismatch"),
+            ("UPDATE counters SET hits = nope * 2 WHERE id > 99", "nope"),
+        ] {
+            let err = db.execute(sql).unwrap_err();
+            assert!(err.contains(expected), "{sql}: {err}");
+        }
+        assert_eq!(rows(&db), before);
+    }
+
+    #[test]
+    fn test_update_and_delete_returning() {
+        let mut db = setup_employees();
+
+        let updated = db
+            .run("UPDATE employees SET salary = 5000 WHERE salary > 2500 RETURNING name, salary * 2 AS doubled")
+            .unwrap()
+            .unwrap();
+        assert_eq!(updated.columns, vec!["name", "doubled"]);
+        assert_eq!(
+            updated.rows,
+            vec![
+                vec![Value::Text("Alice".into()), Value::Int(10000)],
+                vec![Value::Text("Bob".into()), Value::Int(10000)],
+            ]
+        );
+
+        let deleted = db
+            .run("DELETE FROM employees WHERE name = 'Carol' RETURNING *")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            deleted.rows,
+            vec![vec![
+                Value::Text("Carol".into()),
+                Value::Int(2000),
+                Value::Float(250.0)
+            ]]
+        );
+        assert_eq!(db.query("SELECT * FROM employees").unwrap().rows.len(), 2);
+
+        assert!(
+            db.run("DELETE FROM employees WHERE name = 'Alice' RETURNING nope")
+                .is_err()
+        );
+        assert!(
+            db.run("UPDATE employees SET salary = 1 WHERE name = 'Alice' RETURNING nope")
+                .is_err()
+        );
+        let res = db
+            .query("SELECT salary FROM employees WHERE name = 'Alice'")
+            .unwrap();
+        assert_eq!(res.rows, vec![vec![Value::Int(5000)]]);
+    }
+
+    #[test]
+    fn test_failing_returning_leaves_table_untouched() {
+        let mut db = Database::new();
+        db.execute("CREATE TABLE t (id INT AUTO_INCREMENT, x INT)")
+            .unwrap();
+        db.execute("INSERT INTO t (x) VALUES (5)").unwrap();
+        let events = Arc::new(std::sync::Mutex::new(0));
+        let counter = Arc::clone(&events);
+        db.on_change(Box::new(move |_| *counter.lock().unwrap() += 1));
+        let rows = |db: &Database| db.query("SELECT * FROM t").unwrap().rows;
+        let before = rows(&db);
+
+        let err = db
+            .run("INSERT INTO t (x) VALUES (0) RETURNING 10 / x")
+            .unwrap_err();
+        assert!(err.contains("Division by zero"), "{err}");
+        assert_eq!(rows(&db), before);
+
+        let err = db
+            .run("UPDATE t SET x = 0 WHERE id = 1 RETURNING 10 / x")
+            .unwrap_err();
+        assert!(err.contains("Division by zero"), "{err}");
+        assert_eq!(rows(&db), before);
+
+        db.execute("UPDATE t SET x = 0 WHERE id = 1").unwrap();
+        let before = rows(&db);
+        let err = db
+            .run("DELETE FROM t WHERE id = 1 RETURNING 10 / x")
+            .unwrap_err();
+        assert!(err.contains("Division by zero"), "{err}");
+        assert_eq!(rows(&db), before);
+        assert_eq!(*events.lock().unwrap(), 1);
+
+        // the ids returned before the insert are the ones stored
+        let res = db
+            .run("INSERT INTO t (x) VALUES (1), (2) RETURNING id")
+            .unwrap()
+            .unwrap();
+        assert_eq!(res.rows, vec![vec![Value::Int(2)], vec![Value::Int(3)]]);
+        assert_eq!(
+            first_column(&db, "SELECT id FROM t"),
+            [1, 2, 3].map(Value::Int)
+        );
+    }
+
+    #[test]
+    fn test_load_table() {
+        let mut db = setup_employees();
+        let table = db.get_table("employees").unwrap().clone();
+        assert!(db.load_table(table.clone()).is_err());
+
+        let mut copy = table.clone();
+        copy.name = "loaded".into();
+        db.load_table(copy).unwrap();
+        assert_eq!(db.query("SELECT * FROM loaded").unwrap().rows.len(), 3);
+
+        // a column whose storage drifted from the schema is rejected
+        let mut corrupted = table.clone();
+        corrupted.name = "corrupted".into();
+        corrupted.columns[1].data_type = DataType::Float;
+        let err = db.load_table(corrupted).unwrap_err();
+        assert!(err.contains("Cannot load table corrupted"), "{err}");
+        let mut corrupted = table;
+        corrupted.name = "corrupted".into();
+        corrupted.columns[0]
+            .push(Value::Text("Eve".into()))
+            .unwrap();
+        assert!(db.load_table(corrupted).is_err());
+        assert!(db.get_table("corrupted").is_none());
+    }
+
+    #[test]
+    fn test_drop_nonexistent_table() {
+        let mut db = Database::new();
+
+        let err = db.drop_table("unknown");
+        assert!(err.is_err());
+    }
+
+    #[test]
+    fn test_list_tables() {
+        let mut db = Database::new();
+
+        db.create_table("users", simple_schema()).unwrap();
+        db.create_table("posts", simple_schema()).unwrap();
+
+        let mut tables = db.list_tables();
+        tables.sort();
+
+        assert_eq!(tables, vec!["posts", "users"]);
+    }
+
+    #[test]
+    fn test_get_table_mut() {
+        let mut db = Database::new();
+        db.create_table("users", simple_schema()).unwrap();
+
+        {
+            let table = db.get_table_mut("users").unwrap();
+            table
+                .insert(vec![Value::Int(1), Value::Text("Alice".into())])
+                .unwrap();
+            table
+                .insert(vec![Value::Int(2), Value::Text("Bob".into())])
+                .unwrap();
+        }
+
+        let table = db.get_table("users").unwrap();
+        assert_eq!(table.row_count, 2);
+        assert_eq!(
+            table.get_row(0),
+            Some(vec![Value::Int(1), Value::Text("Alice".into())])
+        );
+        assert_eq!(
+            table.get_row(1),
+            Some(vec![Value::Int(2), Value::Text("Bob".into())])
+        );
+    }
+
+    #[test]
+    fn test_execute_insert_and_query_star() {
+        let mut db = Database::new();
+        db.execute("CREATE TABLE users (id INT, name TEXT)")
+            .unwrap();
+
+        db.execute("INSERT INTO users VALUES (1, 'Alice')").unwrap();
+        db.execute("INSERT INTO users VALUES (2, 'Bob')").unwrap();
+
+        let result = db.query("SELECT * FROM users").unwrap();
+
+        assert_eq!(result.columns, vec!["id", "name"]);
+
+        assert_eq!(result.rows.len(), 2);
+        assert_eq!(
+            result.rows[0],
+            vec![Value::Int(1), Value::Text("Alice".into())]
+        );
+        assert_eq!(
+            result.rows[1],
+            vec![Value::Int(2), Value::Text("Bob".into())]
+        );
+    }
+
+    #[test]
+    fn test_query_column_types() {
+        let mut db = Database::new();
+        db.execute("CREATE TABLE users (id INT, name TEXT, score FLOAT)")
+            .unwrap();
+        db.execute("INSERT INTO users VALUES (1, 'Alice', 1.5)")
+            .unwrap();
+
+        let result = db.query("SELECT name, id FROM users").unwrap();
+        assert_eq!(
+            result.column_types,
+            vec![Some(DataType::Text), Some(DataType::Int)]
+        );
+
+        let result = db.query("SELECT * FROM users").unwrap();
+        assert_eq!(
+            result.column_types,
+            vec![
+                Some(DataType::Int),
+                Some(DataType::Text),
+                Some(DataType::Float)
+            ]
+        );
+
+        let result = db
+            .query("SELECT name, COUNT(*), AVG(id), MAX(score) FROM users GROUP BY name")
+            .unwrap();
+        assert_eq!(
+            result.column_types,
+            vec![
+                Some(DataType::Text),
+                Some(DataType::Int),
+                Some(DataType::Float),
+                Some(DataType::Float)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_insert_with_column_reordering() {
+        let mut db = Database::new();
+        db.execute("CREATE TABLE users (id INT, name TEXT)")
+            .unwrap();
+
+        db.execute("INSERT INTO users (name, id) VALUES ('Charlie', 3)")
+            .unwrap();
+
+        let result = db.query("SELECT id, name FROM users").unwrap();
+
+        // Check first line, the order must be order of the schema
+        assert_eq!(
+            result.rows[0],
+            vec![Value::Int(3), Value::Text("Charlie".into())]
+        );
+    }
+
+    #[test]
+    fn test_insert_default_current_timestamp() {
+        use std::time::{SystemTime, UNIX_EPOCH};
+        let now = || {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_micros() as i64
+        };
+
+        let mut db = Database::new();
+        db.execute("CREATE TABLE events (id INT, created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP)")
+            .unwrap();
+        let before = now();
+        db.execute("INSERT INTO events (id) VALUES (1)").unwrap();
+        let after = now();
+
+        let res = db.query("SELECT created_at FROM events").unwrap();
+        assert_eq!(res.column_types, vec![Some(DataType::Timestamp)]);
+        let stored = res.rows[0][0].as_timestamp().unwrap();
+        assert!(before <= stored && stored <= after);
+
+        // an explicit NULL is kept as NULL
+        db.execute("INSERT INTO events (id, created_at) VALUES (2, NULL)")
+            .unwrap();
+        let res = db
+            .query("SELECT created_at FROM events WHERE id = 2")
+            .unwrap();
+        assert_eq!(res.rows[0][0], Value::Null);
+
+        assert!(
+            db.execute("CREATE TABLE bad (n INT DEFAULT CURRENT_TIMESTAMP)")
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_insert_default_values() {
+        let mut db = Database::new();
+        db.execute(
+            "CREATE TABLE items (id INT, qty INT DEFAULT 1, tag TEXT DEFAULT 'new', note TEXT)",
+        )
+        .unwrap();
+
+        db.execute("INSERT INTO items (id, note) VALUES (1, 'a')")
+            .unwrap();
+        db.execute("INSERT INTO items (note, qty, id) VALUES ('b', 5, 2)")
+            .unwrap();
+        // the trailing columns of a positional list may be left out when they all have a default
+        assert!(db.execute("INSERT INTO items VALUES (3)").is_err());
+        db.execute("ALTER TABLE items DROP COLUMN note").unwrap();
+        db.execute("INSERT INTO items VALUES (3)").unwrap();
+        db.execute("INSERT INTO items VALUES (4, 7)").unwrap();
+        assert_eq!(
+            db.query("SELECT id, qty, tag FROM items").unwrap().rows,
+            vec![
+                vec![Value::Int(1), Value::Int(1), Value::Text("new".into())],
+                vec![Value::Int(2), Value::Int(5), Value::Text("new".into())],
+                vec![Value::Int(3), Value::Int(1), Value::Text("new".into())],
+                vec![Value::Int(4), Value::Int(7), Value::Text("new".into())],
+            ]
+        );
+
+        // a schema built in code is checked the same way as a parsed one
+        let schema = Schema::builder()
+            .column_def(
+                ColumnDef::new("n", DataType::Int).default(ColumnDefault::Value(Value::Bool(true))),
+            )
+            .build();
+        let err = db.create_table("bad", schema).unwrap_err();
+        assert!(err.contains("must be of type Int"), "{err}");
+    }
+
+    #[test]
+    fn test_insert_partial_columns() {
+        let mut db = Database::new();
+        db.execute("CREATE TABLE users (id INT, name TEXT)")
+            .unwrap();
+
+        db.execute("INSERT INTO users (id) VALUES (4)").unwrap();
+
+        let result = db.query("SELECT name, id FROM users").unwrap();
+
+        // Row must be [Null, 4] as we asked name before id in the query
+        assert_eq!(result.rows[0], vec![Value::Null, Value::Int(4)]);
+    }
+
+    #[test]
+    fn test_insert_multiple_rows() {
+        let mut db = Database::new();
+        db.execute(
+            "CREATE TABLE users (id INT AUTO_INCREMENT, name TEXT, age INT CHECK (age >= 0))",
+        )
+        .unwrap();
+
+        let res = db
+            .run("INSERT INTO users (name, age) VALUES ('Ada', 36), ('Bob', NULL) RETURNING id")
+            .unwrap()
+            .unwrap();
+        assert_eq!(res.rows, vec![vec![Value::Int(1)], vec![Value::Int(2)]]);
+        db.execute("INSERT INTO users VALUES (NULL, 'Cy', 5), (10, 'Di', 7)")
+            .unwrap();
+
+        // a bad tuple anywhere in the list rejects the whole statement
+        for sql in [
+            "INSERT INTO users (name, age) VALUES ('Eve', 1), ('Fay', 'old')",
+            "INSERT INTO users (name, age) VALUES ('Eve', 1), ('Fay', -1)",
+            "INSERT INTO users VALUES (NULL, 'Eve', 1), (NULL, 'Fay')",
+        ] {
+            assert!(db.execute(sql).is_err(), "{sql}");
+        }
+
+        let res = db.query("SELECT id, name FROM users").unwrap();
+        assert_eq!(
+            res.rows,
+            vec![
+                vec![Value::Int(1), Value::Text("Ada".into())],
+                vec![Value::Int(2), Value::Text("Bob".into())],
+                vec![Value::Int(3), Value::Text("Cy".into())],
+                vec![Value::Int(10), Value::Text("Di".into())],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_insert_invalid_named_column_inserts_nothing() {
+        let mut db = Database::new();
+        db.execute("CREATE TABLE users (id INT, name TEXT, age INT)")
+            .unwrap();
+
+        let err = db
+            .execute("INSERT INTO users (id, unknown, age) VALUES (1, 'Alice', 30)")
+            .unwrap_err();
+        assert!(err.contains("unknown"));
+
+        let table = db.get_table("users").unwrap();
+        assert_eq!(table.row_count, 0);
+        assert!(table.columns.iter().all(|c| c.is_empty()));
+    }
+
+    #[test]
+    fn test_insert_named_columns_arity_and_duplicates() {
+        let mut db = Database::new();
+        db.execute("CREATE TABLE users (id INT, name TEXT)")
+            .unwrap();
+
+        assert!(
+            db.execute("INSERT INTO users (id, name) VALUES (1)")
+                .is_err()
+        );
+        assert!(
+            db.execute("INSERT INTO users (id, id) VALUES (1, 2)")
+                .is_err()
+        );
+        assert_eq!(db.get_table("users").unwrap().row_count, 0);
+    }
+
+    #[test]
+    fn test_query_specific_columns_subset() {
+        let mut db = Database::new();
+        db.execute("CREATE TABLE users (id INT, name TEXT, age INT)")
+            .unwrap();
+        db.execute("INSERT INTO users VALUES (1, 'Alice', 30)")
+            .unwrap();
+
+        let result = db.query("SELECT name FROM users").unwrap();
+
+        assert_eq!(result.columns, vec!["name"]);
+        assert_eq!(result.rows[0], vec![Value::Text("Alice".into())]);
+    }
+
+    #[test]
+    fn test_query_with_where_simple() {
+        let mut db = Database::new();
+        db.execute("CREATE TABLE users (id INT, name TEXT, age INT)")
+            .unwrap();
+        db.execute("INSERT INTO users VALUES (1, 'Alice', 30)")
+            .unwrap();
+        db.execute("INSERT INTO users VALUES (2, 'Bob', 17)")
+            .unwrap();
+        db.execute("INSERT INTO users VALUES (3, 'Charlie', 25)")
+            .unwrap();
+
+        let result = db.query("SELECT name FROM users WHERE age > 18").unwrap();
+
+        assert_eq!(result.columns, vec!["name"]);
+        assert_eq!(result.rows.len(), 2);
+        assert_eq!(result.rows[0], vec![Value::Text("Alice".into())]);
+        assert_eq!(result.rows[1], vec![Value::Text("Charlie".into())]);
+    }
+
+    #[test]
+    fn test_query_with_where_and() {
+        let mut db = Database::new();
+        db.execute("CREATE TABLE users (id INT, age INT, active INT)")
+            .unwrap();
+        db.execute("INSERT INTO users VALUES (1, 30, 1)").unwrap();
+        db.execute("INSERT INTO users VALUES (2, 17, 1)").unwrap();
+        db.execute("INSERT INTO users VALUES (3, 25, 0)").unwrap();
+
+        // WHERE age > 18 AND active = 1
+        let result = db
+            .query("SELECT id FROM users WHERE age > 18 AND active = 1")
+            .unwrap();
+
+        assert_eq!(result.rows.len(), 1);
+        assert_eq!(result.rows[0], vec![Value::Int(1)]);
+    }
+
+    #[test]
+    fn test_where_errors_detected_before_scan() {
+        let mut db = Database::new();
+        db.execute("CREATE TABLE t (id INT, name TEXT)").unwrap();
+
+        // no rows to evaluate, the compiled predicate still rejects the query
+        assert!(db.query("SELECT * FROM t WHERE name = 1").is_err());
+        assert!(db.query("SELECT * FROM t WHERE missing = 1").is_err());
+        assert!(
+            db.query("SELECT * FROM t WHERE id = 1 OR name = 'a'")
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_query_where_true_returns_all_rows() {
+        let mut db = Database::new();
+        db.execute("CREATE TABLE users (id INT)").unwrap();
+        db.execute("INSERT INTO users VALUES (1)").unwrap();
+        db.execute("INSERT INTO users VALUES (2)").unwrap();
+
+        let result = db.query("SELECT id FROM users WHERE TRUE").unwrap();
+        assert_eq!(result.rows.len(), 2);
+    }
+
+    #[test]
+    fn test_query_where_false_returns_no_rows() {
+        let mut db = Database::new();
+        db.execute("CREATE TABLE users (id INT)").unwrap();
+        db.execute("INSERT INTO users VALUES (1)").unwrap();
+        db.execute("INSERT INTO users VALUES (2)").unwrap();
+
+        let result = db.query("SELECT id FROM users WHERE FALSE").unwrap();
+        assert!(result.rows.is_empty());
+    }
+
+    #[test]
+    fn test_query_with_null_comparison() {
+        let mut db = Database::new();
+        db.execute("CREATE TABLE users (id INT, age INT)").unwrap();
+        db.execute("INSERT INTO users (id) VALUES (1)").unwrap(); // age = NULL
+        db.execute("INSERT INTO users VALUES (2, 25)").unwrap();
+
+        // NULL > 18 should be false (SQL semantics)
+        let result = db.query("SELECT id FROM users WHERE age > 18").unwrap();
+
+        assert_eq!(result.rows.len(), 1);
+        assert_eq!(result.rows[0], vec![Value::Int(2)]);
+    }
+
+    #[test]
+    fn test_query_null_safe_equality() {
+        let mut db = Database::new();
+        db.execute("CREATE TABLE t (id INT, x INT)").unwrap();
+        db.execute("INSERT INTO t (id) VALUES (1)").unwrap();
+        db.execute("INSERT INTO t VALUES (2, 5)").unwrap();
+        db.execute("INSERT INTO t VALUES (3, 6)").unwrap();
+
+        // both null
+        assert_eq!(
+            first_column(&db, "SELECT id FROM t WHERE x <=> NULL"),
+            vec![Value::Int(1)]
+        );
+        // one null: the NULL row never equals a value
+        assert_eq!(
+            first_column(&db, "SELECT id FROM t WHERE x <=> 5"),
+            vec![Value::Int(2)]
+        );
+        // both equal non-null, while `=` still never matches NULL
+        assert_eq!(
+            first_column(&db, "SELECT id FROM t WHERE x <=> 6"),
+            vec![Value::Int(3)]
+        );
+        assert!(first_column(&db, "SELECT id FROM t WHERE x = NULL").is_empty());
+    }
+
+    #[test]
+    fn test_query_left_join() {
+        let mut db = Database::new();
+        db.execute("CREATE TABLE users (id INT, name TEXT)")
+            .unwrap();
+        db.execute("CREATE TABLE orders (user_id INT, total INT)")
+            .unwrap();
+        db.execute("INSERT INTO users VALUES (1, 'Alice')").unwrap();
+        db.execute("INSERT INTO users VALUES (2, 'Bob')").unwrap();
+        db.execute("INSERT INTO orders VALUES (1, 30)").unwrap();
+        db.execute("INSERT INTO orders VALUES (1, 20)").unwrap();
+
+        let res = db
+            .query(
+                "SELECT users.name, orders.total FROM users \
+                 LEFT JOIN orders ON users.id = orders.user_id",
+            )
+            .unwrap();
+        assert_eq!(res.columns, vec!["users.name", "orders.total"]);
+        assert_eq!(
+            res.rows,
+            vec![
+                vec![Value::Text("Alice".into()), Value::Int(30)],
+                vec![Value::Text("Alice".into()), Value::Int(20)],
+                // Bob has no order but is still returned
+                vec![Value::Text("Bob".into()), Value::Null],
+            ]
+        );
+
+        // an inner join drops Bob; unambiguous columns need no qualifier
+        let res = db
+            .query("SELECT name, total FROM users JOIN orders ON user_id = id WHERE total > 25")
+            .unwrap();
+        assert_eq!(
+            res.rows,
+            vec![vec![Value::Text("Alice".into()), Value::Int(30)]]
+        );
+    }
+
+    #[test]
+    fn test_query_table_alias() {
+        let mut db = Database::new();
+        db.execute("CREATE TABLE users (id INT, name TEXT)")
+            .unwrap();
+        db.execute("INSERT INTO users VALUES (1, 'Ada')").unwrap();
+        db.execute("INSERT INTO users VALUES (2, 'Bob')").unwrap();
+
+        // a single table answers to its name and to its alias
+        for sql in [
+            "SELECT u.id, u.name FROM users u WHERE u.id = 1",
+            "SELECT id, name FROM users AS u WHERE u.name = 'Ada'",
+            "SELECT users.id, u.name FROM users u WHERE users.id < 2 ORDER BY u.id",
+        ] {
+            let res = db.query(sql).unwrap();
+            assert_eq!(res.columns, vec!["id", "name"], "{sql}");
+            assert_eq!(
+                res.rows,
+                vec![vec![Value::Int(1), Value::Text("Ada".into())]],
+                "{sql}"
+            );
+        }
+        let res = db
+            .query("SELECT COUNT(u.id) FROM users u GROUP BY u.name HAVING COUNT(u.id) = 1")
+            .unwrap();
+        assert_eq!(res.rows.len(), 2);
+
+        // a table created from a join keeps the qualified names as its column names
+        db.execute("CREATE TABLE orders (user_id INT, total INT)")
+            .unwrap();
+        db.execute("INSERT INTO orders VALUES (1, 30)").unwrap();
+        db.execute(
+            "CREATE TABLE j AS SELECT u.name, o.total FROM users u \
+             JOIN orders o ON u.id = o.user_id",
+        )
+        .unwrap();
+        let res = db
+            .query("SELECT u.name, j.o.total FROM j WHERE o.total > 10")
+            .unwrap();
+        assert_eq!(
+            res.rows,
+            vec![vec![Value::Text("Ada".into()), Value::Int(30)]]
+        );
+
+        let err = db.query("SELECT x.id FROM users u").unwrap_err();
+        assert!(err.contains("'x'"), "{err}");
+        let err = db
+            .query("SELECT id FROM users u WHERE x.id = 1")
+            .unwrap_err();
+        assert!(err.contains("'x.id'"), "{err}");
+    }
+
+    #[test]
+    fn test_query_join_table_alias() {
+        let mut db = Database::new();
+        db.execute("CREATE TABLE staff (id INT, name TEXT, boss INT)")
+            .unwrap();
+        db.execute("INSERT INTO staff (id, name) VALUES (1, 'Ada')")
+            .unwrap();
+        db.execute("INSERT INTO staff VALUES (2, 'Bob', 1)")
+            .unwrap();
+
+        // the alias replaces the table name as qualifier, which allows a self-join
+        let res = db
+            .query("SELECT s.name, staff.name FROM staff AS s JOIN staff ON s.boss = staff.id")
+            .unwrap();
+        assert_eq!(res.columns, vec!["s.name", "staff.name"]);
+        assert_eq!(
+            res.rows,
+            vec![vec![Value::Text("Bob".into()), Value::Text("Ada".into())]]
+        );
+
+        let err = db
+            .query("SELECT name FROM staff s JOIN staff ON s.boss = staff.id")
+            .unwrap_err();
+        assert!(err.contains("ambiguous"), "{err}");
+        let err = db
+            .query("SELECT staff.name FROM staff JOIN staff ON boss = id")
+            .unwrap_err();
+        assert!(err.contains("itself"), "{err}");
+    }
+
+    #[test]
+    fn test_set_session_variables() {
+        let mut db = Database::new();
+        db.execute("CREATE TABLE t (id INT, x FLOAT, name TEXT)")
+            .unwrap();
+        db.execute("INSERT INTO t VALUES (1, 1.0, 'Alice')")
+            .unwrap();
+        db.execute("INSERT INTO t VALUES (2, 1.05, 'BOB')").unwrap();
+
+        assert_eq!(first_column(&db, "SELECT id FROM t WHERE x = 1.0").len(), 1);
+        db.execute("SET float_epsilon = 0.1").unwrap();
+        assert_eq!(db.comparison_config.float_epsilon, 0.1);
+        assert_eq!(first_column(&db, "SELECT id FROM t WHERE x = 1.0").len(), 2);
+        db.execute("SET FLOAT_EPSILON = 0").unwrap();
+        assert_eq!(
+            first_column(&db, "SELECT id FROM t WHERE x = 1.05").len(),
+            1
+        );
+
+        assert_eq!(
+            first_column(&db, "SELECT id FROM t WHERE name = 'bob'").len(),
+            0
+        );
+        db.execute("SET case_sensitive = FALSE").unwrap();
+        assert_eq!(
+            first_column(&db, "SELECT id FROM t WHERE name = 'bob'").len(),
+            1
+        );
+
+        let err = db.execute("SET float_epsilon = 'big'").unwrap_err();
+        assert!(err.contains("Invalid value"), "{err}");
+        let err = db.execute("SET verbose = TRUE").unwrap_err();
+        assert!(err.contains("Unknown setting"), "{err}");
+    }
+
+    #[test]
+    fn test_query_unicode_column_names() {
+        let mut db = Database::new();
+        db.execute("CREATE TABLE café (名前 TEXT, naïve INT)")
+            .unwrap();
+        db.execute("INSERT INTO café (名前, naïve) VALUES ('Zoë', 3)")
+            .unwrap();
+
+        let res = db
+            .query("SELECT 名前 FROM café WHERE naïve > 2 ORDER BY 名前")
+            .unwrap();
+        assert_eq!(res.columns, vec!["名前"]);
+        assert_eq!(res.rows, vec![vec![Value::Text("Zoë".into())]]);
+
+        let err = db.query("SELECT prénom FROM café").unwrap_err();
+        assert!(err.contains("prénom"), "{err}");
+    }
+
+    #[test]
+    fn test_explain_plan() {
+        let mut db = Database::new();
+        db.execute("CREATE TABLE users (id INT, age INT)").unwrap();
+        db.execute("CREATE TABLE orders (user_id INT, total INT)")
+            .unwrap();
+
+        let plan = db
+            .explain_plan("SELECT id FROM users WHERE age > 18 ORDER BY age DESC LIMIT 5")
+            .unwrap();
+        let scan = PlanNode::Scan {
+            table: "users".into(),
+            alias: None,
+        };
+        assert_eq!(
+            plan,
+            PlanNode::Limit {
+                limit: Some(5),
+                offset: 0,
+                input: Box::new(PlanNode::Sort {
+                    keys: vec!["age DESC".into()],
+                    input: Box::new(PlanNode::Filter {
+                        condition: "age > 18".into(),
+                        input: Box::new(scan),
+                    }),
+                }),
+            }
+        );
+
+        let sql = "EXPLAIN SELECT u.id, COUNT(*) FROM users u \
+                   LEFT JOIN orders ON u.id = user_id GROUP BY u.id HAVING COUNT(*) > 1";
+        let expected = [
+            "Aggregate COUNT(*) by u.id having COUNT(*) > 1",
+            "  Left Join on u.id = user_id",
+            "    Scan users AS u",
+            "    Scan orders",
+        ]
+        .join("\n");
+        assert_eq!(db.explain(sql).unwrap(), expected);
+        let res = db.run(sql).unwrap().unwrap();
+        assert_eq!(res.columns, vec!["plan"]);
+        assert_eq!(res.rows.len(), 4);
+        assert_eq!(res.rows[3], vec![Value::Text("    Scan orders".into())]);
+
+        assert!(db.explain_plan("SELECT * FROM missing").is_err());
+        assert!(db.explain_plan("DELETE FROM users WHERE id = 1").is_err());
+    }
+
+    #[test]
+    fn test_query_join_with_aliases() {
+        let mut db = Database::new();
+        db.execute("CREATE TABLE users (id INT, name TEXT)")
+            .unwrap();
+        db.execute("CREATE TABLE orders (id INT, user_id INT, total INT)")
+            .unwrap();
+        db.execute("INSERT INTO users VALUES (1, 'Ada')").unwrap();
+        db.execute("INSERT INTO users VALUES (2, 'Bob')").unwrap();
+        db.execute("INSERT INTO orders VALUES (10, 1, 5)").unwrap();
+        db.execute("INSERT INTO orders VALUES (11, 1, 7)").unwrap();
+
+        let res = db
+            .query(
+                "SELECT u.name, o.total FROM users u JOIN orders AS o ON u.id = o.user_id \
+                 WHERE o.total > 5",
+            )
+            .unwrap();
+        assert_eq!(res.columns, vec!["u.name", "o.total"]);
+        assert_eq!(
+            res.rows,
+            vec![vec![Value::Text("Ada".into()), Value::Int(7)]]
+        );
+        assert_eq!(
+            db.explain("SELECT u.name FROM users u JOIN orders o ON u.id = o.user_id")
+                .unwrap(),
+            "Inner Join on u.id = o.user_id\n  Scan users AS u\n  Scan orders AS o"
+        );
+
+        // with both sides aliased, a table can be joined with itself
+        let res = db
+            .query("SELECT a.name, b.name FROM users a JOIN users b ON a.id = b.id")
+            .unwrap();
+        assert_eq!(res.rows.len(), 2);
+
+        // the alias replaces the table name as qualifier
+        let err = db
+            .query("SELECT orders.total FROM users JOIN orders o ON users.id = o.user_id")
+            .unwrap_err();
+        assert!(err.contains("orders.total"), "{err}");
+        let err = db
+            .query("SELECT u.name FROM users u JOIN orders u ON u.id = u.user_id")
+            .unwrap_err();
+        assert!(err.contains("itself"), "{err}");
+        let err = db
+            .query("SELECT u.name FROM users u JOIN orders o ON u.id > o.user_id")
+            .unwrap_err();
+        assert!(err.contains("only supports an equality"), "{err}");
+    }
+
+    #[test]
+    fn test_query_join_errors() {
+        let mut db = Database::new();
+        db.execute("CREATE TABLE a (id INT, name TEXT)").unwrap();
+        db.execute("CREATE TABLE b (id INT, label TEXT)").unwrap();
+
+        let err = db
+            .query("SELECT id FROM a JOIN b ON a.id = b.id")
+            .unwrap_err();
+        assert!(err.contains("ambiguous"), "{err}");
+        let err = db
+            .query("SELECT a.id FROM a JOIN b ON a.id = a.name")
+            .unwrap_err();
+        assert!(err.contains("a column of a with a column of b"), "{err}");
+        let err = db
+            .query("SELECT a.id FROM a JOIN b ON a.id = b.label")
+            .unwrap_err();
+        assert!(err.contains("compares Int with Text"), "{err}");
+        assert!(
+            db.query("SELECT a.id FROM a JOIN c ON a.id = c.id")
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_query_correlated_subquery_in_projection() {
+        let mut db = Database::new();
+        db.execute("CREATE TABLE users (id INT, name TEXT)")
+            .unwrap();
+        db.execute("CREATE TABLE orders (id INT, user_id INT, total INT)")
+            .unwrap();
+        for (id, name) in [(1, "alice"), (2, "bob"), (3, "carol")] {
+            db.execute(&format!("INSERT INTO users VALUES ({id}, '{name}')"))
+                .unwrap();
+        }
+        for (id, user_id, total) in [(1, 1, 10), (2, 1, 20), (3, 3, 5), (4, 1, 7)] {
+            db.execute(&format!(
+                "INSERT INTO orders VALUES ({id}, {user_id}, {total})"
+            ))
+            .unwrap();
+        }
+
+        let res = db
+            .query(
+                "SELECT name, (SELECT COUNT(*) FROM orders o WHERE o.user_id = u.id) AS order_count \
+                 FROM users u ORDER BY id",
+            )
+            .unwrap();
+        assert_eq!(res.columns, vec!["name", "order_count"]);
+        let text = |s: &str| Value::Text(s.into());
+        assert_eq!(
+            res.rows,
+            vec![
+                vec![text("alice"), Value::Int(3)],
+                vec![text("bob"), Value::Int(0)],
+                vec![text("carol"), Value::Int(1)],
+            ]
+        );
+
+        // `name` only exists in users, `id > user_id` compares two columns of orders
+        let res = db
+            .query(
+                "SELECT id, (SELECT MAX(total) FROM orders WHERE user_id = 1 AND name = 'alice' \
+                 AND id > user_id) FROM users",
+            )
+            .unwrap();
+        assert_eq!(
+            res.rows.iter().map(|r| r[1].clone()).collect::<Vec<_>>(),
+            vec![Value::Int(20), Value::Null, Value::Null]
+        );
+
+        // the outer column on the left side, qualified with the table name
+        let res = db
+            .query(
+                "SELECT name, (SELECT MAX(total) FROM orders WHERE users.id = user_id AND total < 15) \
+                 FROM users",
+            )
+            .unwrap();
+        assert_eq!(
+            res.rows.iter().map(|r| r[1].clone()).collect::<Vec<_>>(),
+            vec![Value::Int(10), Value::Null, Value::Int(5)]
+        );
+
+        let err = db
+            .query("SELECT name, (SELECT total FROM orders o WHERE o.user_id = u.id) FROM users u")
+            .unwrap_err();
+        assert!(err.contains("at most one row"), "{err}");
+
+        // outer columns are bound in BETWEEN bounds, and may be tested between inner ones
+        let counts = |sql: &str| {
+            db.query(sql)
+                .unwrap()
+                .rows
+                .into_iter()
+                .map(|mut row| row.swap_remove(1))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            counts(
+                "SELECT name, (SELECT COUNT(*) FROM orders o WHERE o.total BETWEEN u.id AND u.id * 5) \
+                 FROM users u"
+            ),
+            vec![Value::Int(1), Value::Int(3), Value::Int(3)]
+        );
+        assert_eq!(
+            counts(
+                "SELECT name, (SELECT COUNT(*) FROM orders o WHERE u.id BETWEEN o.user_id AND o.id) \
+                 FROM users u"
+            ),
+            vec![Value::Int(3), Value::Int(2), Value::Int(2)]
+        );
+
+        // a qualifier naming neither query is not an outer column
+        let err = db
+            .query(
+                "SELECT name, (SELECT COUNT(*) FROM orders o WHERE o.user_id = x.id) FROM users u",
+            )
+            .unwrap_err();
+        assert_eq!(err, "Unknown table or alias 'x' in column 'x.id'");
+    }
+
+    #[test]
+    fn test_query_scalar_subquery() {
+        let mut db = Database::new();
+        db.execute("CREATE TABLE orders (id INT, total FLOAT)")
+            .unwrap();
+        for (id, total) in [(1, 10.0), (2, 50.0), (3, 30.0), (4, 70.0)] {
+            db.execute(&format!("INSERT INTO orders VALUES ({id}, {total:.1})"))
+                .unwrap();
+        }
+
+        let res = db
+            .query("SELECT id FROM orders WHERE total > (SELECT AVG(total) FROM orders)")
+            .unwrap();
+        assert_eq!(res.rows, vec![vec![Value::Int(2)], vec![Value::Int(4)]]);
+
+        // a subquery with no row compares against NULL
+        let res = db
+            .query("SELECT id FROM orders WHERE total = (SELECT total FROM orders WHERE id = 9)")
+            .unwrap();
+        assert!(res.rows.is_empty());
+
+        let err = db
+            .query("SELECT id FROM orders WHERE total > (SELECT total FROM orders)")
+            .unwrap_err();
+        assert!(err.contains("at most one row"), "{err}");
+        let err = db
+            .query(
+                "SELECT id FROM orders WHERE total > (SELECT id, total FROM orders WHERE id = 1)",
+            )
+            .unwrap_err();
+        assert!(err.contains("one column"), "{err}");
+
+        db.execute("DELETE FROM orders WHERE total < (SELECT MAX(total) FROM orders)")
+            .unwrap();
+        let res = db.query("SELECT id FROM orders").unwrap();
+        assert_eq!(res.rows, vec![vec![Value::Int(4)]]);
+    }
+
+    #[test]
+    fn test_query_between_column_bounds() {
+        let mut db = Database::new();
+        db.execute("CREATE TABLE ranges (id INT, x INT, low INT, high INT)")
+            .unwrap();
+        db.execute("INSERT INTO ranges VALUES (1, 5, 1, 10)")
+            .unwrap();
+        db.execute("INSERT INTO ranges VALUES (2, 50, 1, 10)")
+            .unwrap();
+        // bounds are inclusive
+        db.execute("INSERT INTO ranges VALUES (3, 10, 10, 10)")
+            .unwrap();
+        db.execute("INSERT INTO ranges (id, x, low) VALUES (4, 5, 1)")
+            .unwrap();
+
+        assert_eq!(
+            first_column(&db, "SELECT id FROM ranges WHERE x BETWEEN low AND high"),
+            vec![Value::Int(1), Value::Int(3)]
+        );
+        // literal and column bounds can be mixed, and bounds can be computed
+        assert_eq!(
+            first_column(
+                &db,
+                "SELECT id FROM ranges WHERE x BETWEEN 5 AND high * 5 AND id > 1"
+            ),
+            vec![Value::Int(2), Value::Int(3)]
+        );
+
+        let err = db
+            .query("SELECT id FROM ranges WHERE x BETWEEN low AND missing")
+            .unwrap_err();
+        assert!(err.contains("missing"), "{err}");
+    }
+
+    #[test]
+    fn test_uint_insert_and_filter() {
+        let mut db = Database::new();
+        db.execute("CREATE TABLE counters (name TEXT, hits UINT)")
+            .unwrap();
+        db.execute("INSERT INTO counters VALUES ('a', 5)").unwrap();
+        // above i64::MAX, the literal is read as unsigned
+        db.execute("INSERT INTO counters VALUES ('b', 18446744073709551615)")
+            .unwrap();
+        db.execute("INSERT INTO counters VALUES ('c', 0)").unwrap();
+        assert!(
+            db.execute("INSERT INTO counters VALUES ('d', 'many')")
+                .is_err()
+        );
+
+        assert_eq!(
+            first_column(
+                &db,
+                "SELECT name FROM counters WHERE hits > 9223372036854775807"
+            ),
+            vec![Value::Text("b".into())]
+        );
+        assert_eq!(
+            first_column(&db, "SELECT name FROM counters WHERE hits = 5"),
+            vec![Value::Text("a".into())]
+        );
+        assert_eq!(
+            first_column(&db, "SELECT name FROM counters WHERE hits < 1"),
+            vec![Value::Text("c".into())]
+        );
+
+        // mixing signed and unsigned gives a signed result, two unsigned stay unsigned
+        let res = db
+            .query("SELECT hits + 1, hits + hits FROM counters WHERE name = 'a'")
+            .unwrap();
+        assert_eq!(res.rows, vec![vec![Value::Int(6), Value::UInt(10)]]);
+        assert_eq!(
+            res.column_types,
+            vec![Some(DataType::Int), Some(DataType::UInt)]
+        );
+        assert!(
+            db.query("SELECT hits * hits FROM counters WHERE name = 'b'")
+                .is_err()
+        );
+        assert!(db.query("SELECT SUM(hits) FROM counters").is_err());
+
+        db.execute("DELETE FROM counters WHERE name = 'b'").unwrap();
+        let res = db
+            .query("SELECT SUM(hits), AVG(hits) FROM counters")
+            .unwrap();
+        assert_eq!(res.rows, vec![vec![Value::UInt(5), Value::Float(2.5)]]);
+    }
+
+    #[test]
+    fn test_blob_insert_and_filter() {
+        let mut db = Database::new();
+        db.execute("CREATE TABLE files (name TEXT, hash BYTES)")
+            .unwrap();
+        db.execute("INSERT INTO files VALUES ('a.txt', X'deadbeef')")
+            .unwrap();
+        db.execute("INSERT INTO files VALUES ('b.txt', X'00ff')")
+            .unwrap();
+        db.execute("INSERT INTO files (name) VALUES ('c.txt')")
+            .unwrap();
+
+        let res = db
+            .query("SELECT name, hash FROM files WHERE hash = X'DEADBEEF'")
+            .unwrap();
+        assert_eq!(
+            res.rows,
+            vec![vec![
+                Value::Text("a.txt".into()),
+                Value::Blob(Arc::from([0xde, 0xad, 0xbe, 0xef])),
+            ]]
+        );
+
+        // blobs sort byte by byte, NULL first
+        let res = db.query("SELECT name FROM files ORDER BY hash").unwrap();
+        let names: Vec<_> = res.rows.iter().map(|r| r[0].as_str().unwrap()).collect();
+        assert_eq!(names, vec!["c.txt", "b.txt", "a.txt"]);
+
+        // only equality is defined on blobs
+        assert!(
+            db.query("SELECT name FROM files WHERE hash > X'00'")
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_query_bare_bool_column() {
+        let mut db = Database::new();
+        db.execute("CREATE TABLE users (id INT, active BOOL)")
+            .unwrap();
+        db.execute("INSERT INTO users VALUES (1, TRUE)").unwrap();
+        db.execute("INSERT INTO users VALUES (2, FALSE)").unwrap();
+        db.execute("INSERT INTO users (id) VALUES (3)").unwrap();
+
+        let res = db.query("SELECT id FROM users WHERE active").unwrap();
+        assert_eq!(res.rows, vec![vec![Value::Int(1)]]);
+        // NULL is neither true nor false
+        let res = db.query("SELECT id FROM users WHERE NOT active").unwrap();
+        assert_eq!(res.rows, vec![vec![Value::Int(2)]]);
+        let res = db
+            .query("SELECT id FROM users WHERE NOT active OR id = 1 ORDER BY id DESC")
+            .unwrap();
+        assert_eq!(res.rows, vec![vec![Value::Int(2)], vec![Value::Int(1)]]);
+
+        let err = db.query("SELECT id FROM users WHERE id").unwrap_err();
+        assert!(err.contains("column `id`"), "{err}");
+        assert!(db.query("SELECT id FROM users WHERE NOT id").is_err());
+    }
+
+    #[test]
+    fn test_where_not() {
+        let mut db = Database::new();
+        db.execute("CREATE TABLE t (id INT, age INT, city TEXT)")
+            .unwrap();
+        db.execute("INSERT INTO t VALUES (1, 30, 'Paris')").unwrap();
+        db.execute("INSERT INTO t VALUES (2, 15, 'Lyon')").unwrap();
+        db.execute("INSERT INTO t VALUES (3, NULL, 'Paris')")
+            .unwrap();
+        db.execute("INSERT INTO t VALUES (4, 40, NULL)").unwrap();
+
+        // the negation of an unknown comparison is unknown
+        assert_eq!(
+            first_column(&db, "SELECT id FROM t WHERE NOT age > 18"),
+            [Value::Int(2)]
+        );
+        assert_eq!(
+            first_column(&db, "SELECT id FROM t WHERE NOT NOT age > 18"),
+            [1, 4].map(Value::Int)
+        );
+        // NOT (a AND b) is NOT a OR NOT b: row 3 is unknown AND false, so it matches
+        assert_eq!(
+            first_column(
+                &db,
+                "SELECT id FROM t WHERE NOT (age > 18 AND city = 'Lyon')"
+            ),
+            [1, 2, 3].map(Value::Int)
+        );
+        assert_eq!(
+            first_column(
+                &db,
+                "SELECT id FROM t WHERE NOT (age > 18 OR city = 'Lyon')"
+            ),
+            [] as [Value; 0]
+        );
+        assert_eq!(
+            first_column(&db, "SELECT id FROM t WHERE NOT age BETWEEN 20 AND 35"),
+            [2, 4].map(Value::Int)
+        );
+        assert_eq!(
+            first_column(
+                &db,
+                "SELECT id FROM t WHERE NOT city IS NULL AND NOT id = 1"
+            ),
+            [2, 3].map(Value::Int)
+        );
+        assert_eq!(
+            first_column(&db, "SELECT id FROM t WHERE NOT age <=> NULL"),
+            [1, 2, 4].map(Value::Int)
+        );
+
+        let res = db
+            .query("SELECT city, COUNT(*) FROM t GROUP BY city HAVING NOT COUNT(*) > 1")
+            .unwrap();
+        assert_eq!(
+            res.rows,
+            vec![
+                vec![Value::Text("Lyon".into()), Value::Int(1)],
+                vec![Value::Null, Value::Int(1)],
+            ]
+        );
+
+        db.execute("DELETE FROM t WHERE NOT city = 'Paris'")
+            .unwrap();
+        assert_eq!(
+            first_column(&db, "SELECT id FROM t"),
+            [1, 3, 4].map(Value::Int)
+        );
+    }
+
+    #[test]
+    fn test_where_parentheses() {
+        let mut db = Database::new();
+        db.execute("CREATE TABLE t (id INT, a INT, b INT)").unwrap();
+        for (id, a, b) in [(1, 1, 0), (2, 0, 1), (3, 1, 1), (4, 0, 0)] {
+            db.execute(&format!("INSERT INTO t VALUES ({id}, {a}, {b})"))
+                .unwrap();
+        }
+
+        assert_eq!(
+            first_column(&db, "SELECT id FROM t WHERE a = 1 OR b = 1 AND id = 4").len(),
+            2
+        );
+        assert_eq!(
+            first_column(&db, "SELECT id FROM t WHERE (a = 1 OR b = 1) AND id = 4").len(),
+            0
+        );
+        assert_eq!(
+            first_column(
+                &db,
+                "SELECT id FROM t WHERE (a = 1 OR b = 1) AND (id = 2 OR id = 3)"
+            )
+            .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_where_in_list() {
+        let mut db = Database::new();
+        db.execute("CREATE TABLE t (id INT, name TEXT, price FLOAT)")
+            .unwrap();
+        db.execute("INSERT INTO t VALUES (1, 'ann', 10.0)").unwrap();
+        db.execute("INSERT INTO t VALUES (2, 'bob', 12.5)").unwrap();
+        db.execute("INSERT INTO t VALUES (3, NULL, NULL)").unwrap();
+        db.execute("INSERT INTO t VALUES (4, 'eve', 7.0)").unwrap();
+
+        assert_eq!(
+            first_column(&db, "SELECT id FROM t WHERE id IN (4, 2, 9)"),
+            [2, 4].map(Value::Int)
+        );
+        assert_eq!(
+            first_column(&db, "SELECT id FROM t WHERE name IN ('ann', 'eve')"),
+            [1, 4].map(Value::Int)
+        );
+        // integer literals are coerced to the FLOAT column
+        assert_eq!(
+            first_column(&db, "SELECT id FROM t WHERE price IN (10, 7)"),
+            [1, 4].map(Value::Int)
+        );
+        // a NULL column is never in or out of the list
+        assert_eq!(
+            first_column(&db, "SELECT id FROM t WHERE name NOT IN ('ann')"),
+            [2, 4].map(Value::Int)
+        );
+        assert_eq!(
+            first_column(&db, "SELECT id FROM t WHERE NOT name IN ('ann')"),
+            [2, 4].map(Value::Int)
+        );
+        // a NULL in the list makes NOT IN unknown for the values not found
+        assert_eq!(
+            first_column(&db, "SELECT id FROM t WHERE name NOT IN ('ann', NULL)"),
+            [] as [Value; 0]
+        );
+        assert_eq!(
+            first_column(&db, "SELECT id FROM t WHERE name IN ('ann', NULL)"),
+            [Value::Int(1)]
+        );
+        assert_eq!(
+            first_column(&db, "SELECT id FROM t WHERE NOT name NOT IN ('ann', NULL)"),
+            [Value::Int(1)]
+        );
+
+        let err = db
+            .query("SELECT id FROM t WHERE id IN (1, 'x')")
+            .unwrap_err();
+        assert!(err.contains("column `id` IN"), "{err}");
+
+        db.execute("DELETE FROM t WHERE id IN (1, 3)").unwrap();
+        assert_eq!(
+            first_column(&db, "SELECT id FROM t"),
+            [2, 4].map(Value::Int)
+        );
+    }
+
+    #[test]
+    fn test_like_match() {
+        assert!(like_match("Alice", "Al%"));
+        assert!(like_match("Alice", "%ice"));
+        assert!(like_match("Alice", "%l%c%"));
+        assert!(like_match("Alice", "A_ic_"));
+        assert!(like_match("Alice", "%"));
+        assert!(like_match("", "%%"));
+        assert!(like_match("aab", "%ab"));
+        assert!(like_match("naïve", "na_ve"));
+        assert!(!like_match("Alice", "al%"));
+        assert!(!like_match("Alice", "A_ce"));
+        assert!(!like_match("Alice", "Alic"));
+        assert!(!like_match("", "_"));
+        assert!(!like_match("abc", "%b"));
+    }
+
+    #[test]
+    fn test_where_like() {
+        let mut db = Database::new();
+        db.execute("CREATE TABLE users (id INT, name TEXT)")
+            .unwrap();
+        for (id, name) in [(1, "'Alice'"), (2, "'Albert'"), (3, "'bob'"), (4, "NULL")] {
+            db.execute(&format!("INSERT INTO users VALUES ({id}, {name})"))
+                .unwrap();
+        }
+
+        assert_eq!(
+            first_column(&db, "SELECT id FROM users WHERE name LIKE 'Al%'"),
+            [1, 2].map(Value::Int)
+        );
+        assert_eq!(
+            first_column(&db, "SELECT id FROM users WHERE name LIKE '_o_'"),
+            [Value::Int(3)]
+        );
+        // NULL matches neither LIKE nor NOT LIKE
+        assert_eq!(
+            first_column(&db, "SELECT id FROM users WHERE name NOT LIKE 'Al%'"),
+            [Value::Int(3)]
+        );
+        assert_eq!(
+            first_column(&db, "SELECT id FROM users WHERE NOT name LIKE '%e%'"),
+            [Value::Int(3)]
+        );
+        db.execute("SET case_sensitive = FALSE").unwrap();
+        assert_eq!(
+            first_column(&db, "SELECT id FROM users WHERE name LIKE 'AL%'"),
+            [1, 2].map(Value::Int)
+        );
+
+        let err = db
+            .query("SELECT id FROM users WHERE id LIKE '1%'")
+            .unwrap_err();
+        assert!(err.contains("expected a TEXT column"), "{err}");
+    }
+
+    #[test]
+    fn test_negative_literals() {
+        let mut db = Database::new();
+        db.execute("CREATE TABLE accounts (id INT, balance INT, rate FLOAT)")
+            .unwrap();
+        db.execute("INSERT INTO accounts VALUES (1, -5, -0.5), (2, 300, 1.5), (3, -150, 2)")
+            .unwrap();
+
+        assert_eq!(
+            first_column(&db, "SELECT id FROM accounts WHERE balance > -100"),
+            [1, 2].map(Value::Int)
+        );
+        assert_eq!(
+            first_column(&db, "SELECT id FROM accounts WHERE rate < -0.25"),
+            [Value::Int(1)]
+        );
+        assert_eq!(
+            first_column(
+                &db,
+                "SELECT id FROM accounts WHERE balance BETWEEN -200 AND -10"
+            ),
+            [Value::Int(3)]
+        );
+
+        // `-` right after a column is still a subtraction
+        db.execute("UPDATE accounts SET balance = balance-5 WHERE id = 1")
+            .unwrap();
+        assert_eq!(
+            db.query("SELECT balance - -10 FROM accounts WHERE id = 1")
+                .unwrap()
+                .rows,
+            vec![vec![Value::Int(0)]]
+        );
+
+        // a row count cannot be negative
+        let err = db.query("SELECT id FROM accounts LIMIT -1").unwrap_err();
+        assert!(err.contains("LIMIT cannot be negative"), "{err}");
+    }
+
+    #[test]
+    fn test_where_mixed_numeric_comparisons() {
+        let mut db = Database::new();
+        db.execute("CREATE TABLE items (id INT, price FLOAT, qty INT, stock UINT, name TEXT)")
+            .unwrap();
+        db.execute("INSERT INTO items VALUES (1, 9.5, 3, 4, 'a'), (2, 3.0, 7, 0, 'b')")
+            .unwrap();
+
+        assert_eq!(
+            first_column(&db, "SELECT id FROM items WHERE price > 9"),
+            [Value::Int(1)]
+        );
+        assert_eq!(
+            first_column(&db, "SELECT id FROM items WHERE price = 3"),
+            [Value::Int(2)]
+        );
+        assert_eq!(
+            first_column(&db, "SELECT id FROM items WHERE qty < 3.5"),
+            [Value::Int(1)]
+        );
+        assert_eq!(
+            first_column(&db, "SELECT id FROM items WHERE qty = 7.0"),
+            [Value::Int(2)]
+        );
+        assert_eq!(
+            first_column(&db, "SELECT id FROM items WHERE stock >= 0.5"),
+            [Value::Int(1)]
+        );
+        assert_eq!(
+            first_column(&db, "SELECT id FROM items WHERE price > qty"),
+            [Value::Int(1)]
+        );
+        assert_eq!(
+            first_column(&db, "SELECT id FROM items WHERE qty BETWEEN 2.5 AND price"),
+            [Value::Int(1)]
+        );
+        assert_eq!(
+            first_column(&db, "SELECT id FROM items WHERE qty IN (7.0, 8.5)"),
+            [Value::Int(2)]
+        );
+        assert_eq!(
+            first_column(
+                &db,
+                "SELECT id, SUM(qty) FROM items GROUP BY id HAVING SUM(qty) > 5.5"
+            ),
+            [Value::Int(2)]
+        );
+
+        // ordering is exact beyond 2^53, where the integer has no exact float, and agrees
+        // with ORDER BY
+        db.execute("INSERT INTO items VALUES (3, 1.0, 9007199254740993, 1, 'c')")
+            .unwrap();
+        assert_eq!(
+            first_column(&db, "SELECT id FROM items WHERE qty > 9007199254740992.0"),
+            [Value::Int(3)]
+        );
+        assert_eq!(
+            first_column(&db, "SELECT id FROM items WHERE qty < 9007199254740992.0"),
+            [1, 2].map(Value::Int)
+        );
+        db.execute("DELETE FROM items WHERE id = 3").unwrap();
+
+        // equality between an integer and a float follows the float tolerance
+        db.execute("SET float_epsilon = 0.1").unwrap();
+        assert_eq!(
+            first_column(&db, "SELECT id FROM items WHERE qty = 3.05"),
+            [Value::Int(1)]
+        );
+
+        // other mismatches are still errors
+        for sql in [
+            "SELECT id FROM items WHERE price > 'a'",
+            "SELECT id FROM items WHERE name = 1.5",
+            "SELECT id FROM items WHERE price = TRUE",
+        ] {
+            let err = db.query(sql).unwrap_err();
+            assert!(err.contains("cannot compare"), "{sql}: {err}");
+        }
+    }
+
+    #[test]
+    fn test_where_column_comparison() {
+        let mut db = Database::new();
+        db.execute("CREATE TABLE staff (id INT, salary INT, bonus UINT, name TEXT, nick TEXT)")
+            .unwrap();
+        db.execute("INSERT INTO staff VALUES (1, 3000, 500, 'ann', 'ann')")
+            .unwrap();
+        db.execute("INSERT INTO staff VALUES (2, 400, 500, 'bob', 'bobby')")
+            .unwrap();
+        db.execute("INSERT INTO staff VALUES (3, NULL, 100, 'eve', NULL)")
+            .unwrap();
+
+        // INT and UINT columns compare as integers; a NULL operand matches nothing
+        assert_eq!(
+            first_column(&db, "SELECT id FROM staff WHERE salary > bonus"),
+            [Value::Int(1)]
+        );
+        assert_eq!(
+            first_column(&db, "SELECT id FROM staff WHERE salary <= bonus"),
+            [Value::Int(2)]
+        );
+        assert_eq!(
+            first_column(&db, "SELECT id FROM staff WHERE name = nick"),
+            [Value::Int(1)]
+        );
+        assert_eq!(
+            first_column(
+                &db,
+                "SELECT id FROM staff WHERE name != nick OR salary < bonus"
+            ),
+            [Value::Int(2)]
+        );
+
+        db.execute("UPDATE staff SET bonus = 0 WHERE salary < bonus")
+            .unwrap();
+        db.execute("DELETE FROM staff WHERE name = nick").unwrap();
+        let res = db.query("SELECT id, bonus FROM staff").unwrap();
+        assert_eq!(
+            res.rows,
+            vec![
+                vec![Value::Int(2), Value::UInt(0)],
+                vec![Value::Int(3), Value::UInt(100)],
+            ]
+        );
+
+        let err = db
+            .query("SELECT id FROM staff WHERE salary > name")
+            .unwrap_err();
+        assert!(err.contains("`salary > name`"), "{err}");
+        assert!(err.contains("cannot compare Int with Text"), "{err}");
+        assert!(
+            db.query("SELECT id FROM staff WHERE salary > missing")
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_query_with_limit() {
+        let mut db = Database::new();
+        db.execute("CREATE TABLE users (id INT, age INT, active INT)")
+            .unwrap();
+        db.execute("INSERT INTO users VALUES (1, 30, 1)").unwrap();
+        db.execute("INSERT INTO users VALUES (2, 17, 1)").unwrap();
+        db.execute("INSERT INTO users VALUES (3, 25, 0)").unwrap();
+
+        // WHERE age > 18 AND active = 1
+        let result = db.query("SELECT id FROM users LIMIT 2").unwrap();
+
+        assert_eq!(result.rows.len(), 2);
+        assert_eq!(result.rows[0], vec![Value::Int(1)]);
+        assert_eq!(result.rows[1], vec![Value::Int(2)]);
+    }
+
+    #[test]
+    fn test_query_offset_without_limit() {
+        let mut db = Database::new();
+        db.execute("CREATE TABLE t (id INT)").unwrap();
+        for id in [3, 1, 5, 2, 4] {
+            db.execute(&format!("INSERT INTO t VALUES ({id})")).unwrap();
+        }
+
+        let res = db.query("SELECT id FROM t OFFSET 3").unwrap();
+        assert_eq!(res.rows, vec![vec![Value::Int(2)], vec![Value::Int(4)]]);
+
+        assert_eq!(
+            first_column(&db, "SELECT id FROM t ORDER BY id DESC OFFSET 1 "),
+            [4, 3, 2, 1].map(Value::Int)
+        );
+
+        // an offset landing exactly on the end is empty, not an error
+        assert!(
+            db.query("SELECT id FROM t OFFSET 5")
+                .unwrap()
+                .rows
+                .is_empty()
+        );
+        assert!(
+            db.query("SELECT id FROM t LIMIT 2 OFFSET 5")
+                .unwrap()
+                .rows
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_query_huge_offset_is_empty() {
+        let mut db = Database::new();
+        db.execute("CREATE TABLE t (id INT)").unwrap();
+        for id in 1..=5 {
+            db.execute(&format!("INSERT INTO t VALUES ({id})")).unwrap();
+        }
+        let huge = i64::MAX;
+
+        let res = db.query("SELECT id FROM t LIMIT 2 OFFSET 3").unwrap();
+        assert_eq!(res.rows, vec![vec![Value::Int(4)], vec![Value::Int(5)]]);
+
+        for sql in [
+            format!("SELECT id FROM t OFFSET {huge}"),
+            format!("SELECT id FROM t LIMIT {huge} OFFSET {huge}"),
+            format!("SELECT * FROM t LIMIT {huge} OFFSET {huge}"),
+            format!("SELECT DISTINCT id FROM t LIMIT 1 OFFSET {huge}"),
+            format!("SELECT COUNT(*) FROM t OFFSET {huge}"),
+        ] {
+            assert!(db.query(&sql).unwrap().rows.is_empty(), "{sql}");
+        }
+
+        let sql = format!("SELECT id FROM t LIMIT {huge} OFFSET 4");
+        let res = db.query(&sql).unwrap();
+        assert_eq!(res.rows, vec![vec![Value::Int(5)]]);
+    }
+
+    #[test]
+    fn test_query_fetch_first() {
+        let mut db = Database::new();
+        db.execute("CREATE TABLE t (id INT)").unwrap();
+        for id in 1..=5 {
+            db.execute(&format!("INSERT INTO t VALUES ({id})")).unwrap();
+        }
+
+        let res = db
+            .query("SELECT id FROM t ORDER BY id DESC FETCH FIRST 3 ROWS ONLY")
+            .unwrap();
+        assert_eq!(
+            res.rows,
+            vec![
+                vec![Value::Int(5)],
+                vec![Value::Int(4)],
+                vec![Value::Int(3)]
+            ]
+        );
+
+        let res = db.query("SELECT id FROM t FETCH FIRST ROW ONLY").unwrap();
+        assert_eq!(res.rows, vec![vec![Value::Int(1)]]);
+    }
+
+    #[test]
+    fn test_query_with_stats() {
+        let mut db = Database::new();
+        db.execute("CREATE TABLE t (id INT, age INT)").unwrap();
+        for id in 1..=10 {
+            db.execute(&format!("INSERT INTO t VALUES ({id}, {})", id * 10))
+                .unwrap();
+        }
+        db.vacuum_config.enabled = false;
+        db.execute("DELETE FROM t WHERE id = 1").unwrap();
+
+        let (res, stats) = db
+            .query_with_stats("SELECT id FROM t WHERE age > 50 LIMIT 2")
+            .unwrap();
+        assert_eq!(res.rows.len(), 2);
+        assert_eq!(
+            stats,
+            QueryStats {
+                rows_scanned: 6,
+                rows_matched: 2,
+                rows_returned: 2,
+            }
+        );
+
+        // ordering needs every match, so the whole table is scanned
+        let (_, stats) = db
+            .query_with_stats("SELECT id FROM t WHERE age > 50 ORDER BY id LIMIT 2")
+            .unwrap();
+        assert_eq!((stats.rows_scanned, stats.rows_matched), (9, 5));
+    }
+
+    #[test]
+    fn test_unordered_limit_stops_scan() {
+        let mut db = Database::new();
+        db.execute("CREATE TABLE t (id INT)").unwrap();
+        let table = db.get_table_mut("t").unwrap();
+        for id in 0..10_000 {
+            table.insert(vec![Value::Int(id)]).unwrap();
+        }
+
+        let (res, stats) = db
+            .query_with_stats("SELECT id FROM t WHERE id > 99 LIMIT 5 OFFSET 1")
+            .unwrap();
+        let ids: Vec<Value> = [101, 102, 103, 104, 105].map(Value::Int).into();
+        assert_eq!(
+            res.rows,
+            ids.into_iter().map(|v| vec![v]).collect::<Vec<_>>()
+        );
+        assert_eq!(stats.rows_scanned, 106);
+        assert_eq!(stats.rows_matched, 6);
+
+        let (res, stats) = db.query_with_stats("SELECT * FROM t LIMIT 0").unwrap();
+        assert!(res.rows.is_empty());
+        assert_eq!(stats.rows_scanned, 0);
+
+        // DISTINCT and aggregates still see every row
+        let (_, stats) = db
+            .query_with_stats("SELECT DISTINCT id FROM t LIMIT 5")
+            .unwrap();
+        assert_eq!(stats.rows_scanned, 10_000);
+        let (_, stats) = db
+            .query_with_stats("SELECT COUNT(*) FROM t LIMIT 1")
+            .unwrap();
+        assert_eq!(stats.rows_scanned, 10_000);
+    }
+
+    #[test]
+    fn test_table_hash() {
+        let mut db = Database::new();
+        db.execute("CREATE TABLE t (id INT, name TEXT)").unwrap();
+        db.execute("INSERT INTO t VALUES (1, 'a')").unwrap();
+        db.execute("INSERT INTO t VALUES (2, 'b')").unwrap();
+        let initial = db.table_hash("t").unwrap();
+        assert_eq!(db.table_hash("t"), Some(initial));
+        assert_eq!(db.table_hash("missing"), None);
+
+        db.execute("INSERT INTO t VALUES (3, 'c')").unwrap();
+        let inserted = db.table_hash("t").unwrap();
+        assert_ne!(inserted, initial);
+
+        db.execute("UPDATE t SET name = 'z' WHERE id = 3").unwrap();
+        let updated = db.table_hash("t").unwrap();
+        assert_ne!(updated, inserted);
+
+        // back to the initial rows: same hash, even with a deleted row not vacuumed
+        db.execute("DELETE FROM t WHERE id = 3").unwrap();
+        assert_eq!(db.table_hash("t"), Some(initial));
+
+        // same rows inserted in another order in an identical table
+        db.execute("CREATE TABLE u (id INT, name TEXT)").unwrap();
+        db.execute("INSERT INTO u VALUES (2, 'b')").unwrap();
+        db.execute("INSERT INTO u VALUES (1, 'a')").unwrap();
+        assert_eq!(db.table_hash("u"), Some(initial));
+
+        // same rows, different column names
+        db.execute("CREATE TABLE v (key INT, name TEXT)").unwrap();
+        db.execute("INSERT INTO v VALUES (1, 'a')").unwrap();
+        db.execute("INSERT INTO v VALUES (2, 'b')").unwrap();
+        assert_ne!(db.table_hash("v"), Some(initial));
+    }
+
+    #[test]
+    fn test_query_scalar() {
         let mut db = Database::new();
+        db.execute("CREATE TABLE t (id INT, name TEXT)").unwrap();
+        assert_eq!(db.query_scalar("SELECT COUNT(*) FROM t"), Ok(Value::Int(0)));
+        db.execute("INSERT INTO t VALUES (1, 'a')").unwrap();
+        db.execute("INSERT INTO t VALUES (2, 'b')").unwrap();
 
-        assert!(db.create_table("users", simple_schema()).is_ok());
-        assert!(db.get_table("users").is_some());
+        assert_eq!(db.query_scalar("SELECT COUNT(*) FROM t"), Ok(Value::Int(2)));
+        assert_eq!(
+            db.query_scalar("SELECT name FROM t WHERE id = 2"),
+            Ok(Value::Text("b".into()))
+        );
 
-        assert!(db.drop_table("users").is_ok());
-        assert!(db.get_table("users").is_none());
+        let err = db.query_scalar("SELECT id FROM t").unwrap_err();
+        assert_eq!(err, "Expected a single value, got 2 row(s) of 1 column(s)");
+        let err = db
+            .query_scalar("SELECT id, name FROM t WHERE id = 1")
+            .unwrap_err();
+        assert_eq!(err, "Expected a single value, got 1 row(s) of 2 column(s)");
+        assert!(db.query_scalar("SELECT id FROM t WHERE id = 9").is_err());
+        assert!(db.query_scalar("SELECT COUNT(*) FROM missing").is_err());
     }
 
     #[test]
-    fn test_duplicate_table_error() {
+    fn test_query_limit_offset_comma() {
         let mut db = Database::new();
+        db.execute("CREATE TABLE t (id INT)").unwrap();
+        for id in 0..50 {
+            db.execute(&format!("INSERT INTO t VALUES ({id})")).unwrap();
+        }
 
-        assert!(db.create_table("users", simple_schema()).is_ok());
-        let err = db.create_table("users", simple_schema());
-
-        assert!(err.is_err());
+        let ids = |range: std::ops::Range<i64>| -> Vec<Vec<Value>> {
+            range.map(|id| vec![Value::Int(id)]).collect()
+        };
+        let res = db
+            .query("SELECT id FROM t ORDER BY id LIMIT 20, 10")
+            .unwrap();
+        assert_eq!(res.rows, ids(20..30));
+        assert_eq!(
+            db.query("SELECT id FROM t ORDER BY id LIMIT 10 OFFSET 20")
+                .unwrap()
+                .rows,
+            res.rows
+        );
+        let res = db.query("SELECT id FROM t ORDER BY id LIMIT 20").unwrap();
+        assert_eq!(res.rows, ids(0..20));
+        let res = db
+            .query("SELECT id FROM t ORDER BY id LIMIT 45, 10")
+            .unwrap();
+        assert_eq!(res.rows, ids(45..50));
     }
 
     #[test]
-    fn test_drop_nonexistent_table() {
+    fn test_query_table_sample() {
         let mut db = Database::new();
+        db.execute("CREATE TABLE t (id INT)").unwrap();
+        let table = db.get_table_mut("t").unwrap();
+        for id in 0..10_000 {
+            table.insert(vec![Value::Int(id)]).unwrap();
+        }
 
-        let err = db.drop_table("unknown");
-        assert!(err.is_err());
+        let sql = "SELECT id FROM t TABLESAMPLE (10 PERCENT) REPEATABLE (42)";
+        let sample = db.query(sql).unwrap();
+        // about 10% of the rows, always the same ones for a given seed
+        assert_eq!(sample.rows.len(), 961);
+        assert_eq!(db.query(sql).unwrap().rows, sample.rows);
+        let other = db
+            .query("SELECT id FROM t TABLESAMPLE (10 PERCENT) REPEATABLE (43)")
+            .unwrap();
+        assert_ne!(other.rows, sample.rows);
+
+        // WHERE filters the sampled rows
+        let res = db
+            .query("SELECT id FROM t TABLESAMPLE (10 PERCENT) REPEATABLE (42) WHERE id > 4999")
+            .unwrap();
+        let expected: Vec<_> = sample
+            .rows
+            .iter()
+            .filter(|row| row[0] > Value::Int(4999))
+            .cloned()
+            .collect();
+        assert_eq!(res.rows, expected);
+
+        let res = db
+            .query("SELECT id FROM t TABLESAMPLE (0 PERCENT)")
+            .unwrap();
+        assert!(res.rows.is_empty());
+        let res = db
+            .query("SELECT id FROM t TABLESAMPLE (100 PERCENT)")
+            .unwrap();
+        assert_eq!(res.rows.len(), 10_000);
+
+        let plan = db.explain(sql).unwrap();
+        assert_eq!(plan, "Sample 10% seed 42\n  Scan t");
     }
 
     #[test]
-    fn test_list_tables() {
+    fn test_query_with_where_no_match() {
         let mut db = Database::new();
+        db.execute("CREATE TABLE users (id INT, age INT)").unwrap();
+        db.execute("INSERT INTO users VALUES (1, 10)").unwrap();
+        db.execute("INSERT INTO users VALUES (2, 15)").unwrap();
 
-        db.create_table("users", simple_schema()).unwrap();
-        db.create_table("posts", simple_schema()).unwrap();
-
-        let mut tables = db.list_tables();
-        tables.sort();
+        let result = db.query("SELECT id FROM users WHERE age > 100").unwrap();
 
-        assert_eq!(tables, vec!["posts", "users"]);
+        assert_eq!(result.rows.len(), 0);
     }
 
     #[test]
-    fn test_get_table_mut() {
+    fn test_query_order_by_asc_desc() {
         let mut db = Database::new();
-        db.create_table("users", simple_schema()).unwrap();
+        db.execute("CREATE TABLE users (id INT, age INT)").unwrap();
+        db.execute("INSERT INTO users VALUES (1, 30)").unwrap();
+        db.execute("INSERT INTO users VALUES (2, 20)").unwrap();
+        db.execute("INSERT INTO users VALUES (3, 25)").unwrap();
 
-        {
-            let table = db.get_table_mut("users").unwrap();
-            table
-                .insert(vec![Value::Int(1), Value::Text("Alice".into())])
-                .unwrap();
-            table
-                .insert(vec![Value::Int(2), Value::Text("Bob".into())])
-                .unwrap();
-        }
+        // Test ASC
+        let res_asc = db.query("SELECT age FROM users ORDER BY age ASC").unwrap();
+        assert_eq!(res_asc.rows[0][0], Value::Int(20));
+        assert_eq!(res_asc.rows[2][0], Value::Int(30));
 
-        let table = db.get_table("users").unwrap();
-        assert_eq!(table.row_count, 2);
-        assert_eq!(
-            table.get_row(0),
-            Some(vec![Value::Int(1), Value::Text("Alice".into())])
-        );
-        assert_eq!(
-            table.get_row(1),
-            Some(vec![Value::Int(2), Value::Text("Bob".into())])
-        );
+        // Test DESC
+        let res_desc = db.query("SELECT age FROM users ORDER BY age DESC").unwrap();
+        assert_eq!(res_desc.rows[0][0], Value::Int(30));
+        assert_eq!(res_desc.rows[2][0], Value::Int(20));
     }
 
     #[test]
-    fn test_execute_insert_and_query_star() {
+    fn test_query_order_by_multiple_columns() {
         let mut db = Database::new();
-        db.execute("CREATE TABLE users (id INT, name TEXT)")
+        db.execute("CREATE TABLE users (name TEXT, score INT)")
+            .unwrap();
+        db.execute("INSERT INTO users VALUES ('Alice', 100)")
+            .unwrap();
+        db.execute("INSERT INTO users VALUES ('Bob', 100)").unwrap();
+        db.execute("INSERT INTO users VALUES ('Charlie', 50)")
             .unwrap();
 
-        db.execute("INSERT INTO users VALUES (1, 'Alice')").unwrap();
-        db.execute("INSERT INTO users VALUES (2, 'Bob')").unwrap();
+        let res = db
+            .query("SELECT name, score FROM users ORDER BY score DESC, name ASC")
+            .unwrap();
 
-        let result = db.query("SELECT * FROM users").unwrap();
+        assert_eq!(res.rows[0][0], Value::Text("Alice".into()));
+        assert_eq!(res.rows[1][0], Value::Text("Bob".into()));
+        assert_eq!(res.rows[2][0], Value::Text("Charlie".into()));
+    }
 
-        assert_eq!(result.columns, vec!["id", "name"]);
+    #[test]
+    fn test_query_order_by_nulls_per_key() {
+        let mut db = Database::new();
+        db.execute("CREATE TABLE t (id INT, team TEXT, score INT)")
+            .unwrap();
+        db.execute("INSERT INTO t VALUES (1, 'a', 10)").unwrap();
+        db.execute("INSERT INTO t (id, team) VALUES (2, 'a')")
+            .unwrap();
+        db.execute("INSERT INTO t VALUES (3, 'b', 5)").unwrap();
+        db.execute("INSERT INTO t (id, score) VALUES (4, 7)")
+            .unwrap();
+        db.execute("INSERT INTO t VALUES (5, 'a', 20)").unwrap();
 
-        assert_eq!(result.rows.len(), 2);
+        // default: NULL is the smallest value, first in ASC and last in DESC, per key
         assert_eq!(
-            result.rows[0],
-            vec![Value::Int(1), Value::Text("Alice".into())]
+            first_column(&db, "SELECT id FROM t ORDER BY team DESC, score ASC"),
+            [3, 2, 1, 5, 4].map(Value::Int)
         );
+        // the tiebreak key puts its NULLs last even though the first key is DESC
         assert_eq!(
-            result.rows[1],
-            vec![Value::Int(2), Value::Text("Bob".into())]
+            first_column(
+                &db,
+                "SELECT id FROM t ORDER BY team DESC NULLS FIRST, score NULLS LAST"
+            ),
+            [4, 3, 1, 5, 2].map(Value::Int)
+        );
+        assert_eq!(
+            first_column(
+                &db,
+                "SELECT id FROM t ORDER BY team, score DESC NULLS FIRST"
+            ),
+            [4, 2, 5, 1, 3].map(Value::Int)
         );
     }
 
     #[test]
-    fn test_insert_with_column_reordering() {
+    fn test_query_order_by_nulls_all_combinations() {
         let mut db = Database::new();
-        db.execute("CREATE TABLE users (id INT, name TEXT)")
+        db.execute("CREATE TABLE t (id INT, age INT)").unwrap();
+        db.execute("INSERT INTO t VALUES (1, 30), (2, NULL), (3, 20), (4, NULL), (5, 40)")
             .unwrap();
 
-        db.execute("INSERT INTO users (name, id) VALUES ('Charlie', 3)")
-            .unwrap();
-
-        let result = db.query("SELECT id, name FROM users").unwrap();
+        for (order, expected) in [
+            ("age ASC NULLS FIRST", [2, 4, 3, 1, 5]),
+            ("age ASC NULLS LAST", [3, 1, 5, 2, 4]),
+            ("age DESC NULLS FIRST", [2, 4, 5, 1, 3]),
+            ("age DESC NULLS LAST", [5, 1, 3, 2, 4]),
+            // without the option, NULL is the smallest value
+            ("age ASC", [2, 4, 3, 1, 5]),
+            ("age DESC", [5, 1, 3, 2, 4]),
+        ] {
+            assert_eq!(
+                first_column(&db, &format!("SELECT id FROM t ORDER BY {order}")),
+                expected.map(Value::Int),
+                "{order}"
+            );
+        }
 
-        // Check first line, the order must be order of the schema
+        // the groups of a grouped query follow the same rules
         assert_eq!(
-            result.rows[0],
-            vec![Value::Int(3), Value::Text("Charlie".into())]
+            first_column(
+                &db,
+                "SELECT age, COUNT(*) FROM t GROUP BY age ORDER BY age DESC NULLS FIRST"
+            ),
+            vec![Value::Null, Value::Int(40), Value::Int(30), Value::Int(20)]
         );
     }
 
     #[test]
-    fn test_insert_partial_columns() {
+    fn test_query_order_by_float_with_nulls() {
         let mut db = Database::new();
-        db.execute("CREATE TABLE users (id INT, name TEXT)")
+        db.execute("CREATE TABLE t (id INT, x FLOAT)").unwrap();
+        db.execute("INSERT INTO t VALUES (1, 2.5), (2, NULL), (3, 0), (4, 0.5)")
             .unwrap();
 
-        db.execute("INSERT INTO users (id) VALUES (4)").unwrap();
-
-        let result = db.query("SELECT name, id FROM users").unwrap();
-
-        // Row must be [Null, 4] as we asked name before id in the query
-        assert_eq!(result.rows[0], vec![Value::Null, Value::Int(4)]);
+        assert_eq!(
+            first_column(&db, "SELECT id FROM t ORDER BY x"),
+            [2, 3, 4, 1].map(Value::Int)
+        );
+        assert_eq!(
+            first_column(&db, "SELECT id FROM t ORDER BY x DESC"),
+            [1, 4, 3, 2].map(Value::Int)
+        );
+        // a computed key of floats sorts by value as well
+        assert_eq!(
+            first_column(&db, "SELECT id FROM t ORDER BY x * id DESC"),
+            [1, 4, 3, 2].map(Value::Int)
+        );
     }
 
     #[test]
-    fn test_query_specific_columns_subset() {
+    fn test_order_by_text_desc_and_float_ranges() {
         let mut db = Database::new();
-        db.execute("CREATE TABLE users (id INT, name TEXT, age INT)")
-            .unwrap();
-        db.execute("INSERT INTO users VALUES (1, 'Alice', 30)")
+        db.execute("CREATE TABLE products (name TEXT, price FLOAT)")
             .unwrap();
+        db.execute(
+            "INSERT INTO products VALUES ('pear', 2.5), ('Apple', 1.25), ('apple', 10.0), \
+             ('fig', 4.75)",
+        )
+        .unwrap();
 
-        let result = db.query("SELECT name FROM users").unwrap();
+        // byte order: uppercase letters sort before lowercase ones
+        assert_eq!(
+            first_column(&db, "SELECT name FROM products ORDER BY name DESC"),
+            texts(&["pear", "fig", "apple", "Apple"])
+        );
+        assert_eq!(
+            first_column(&db, "SELECT name FROM products ORDER BY price DESC"),
+            texts(&["apple", "fig", "pear", "Apple"])
+        );
+        assert_eq!(
+            first_column(
+                &db,
+                "SELECT name FROM products WHERE price > 1.25 AND price <= 4.75 ORDER BY price"
+            ),
+            texts(&["pear", "fig"])
+        );
+        assert_eq!(
+            first_column(
+                &db,
+                "SELECT name FROM products WHERE price BETWEEN 2 AND 5 ORDER BY name DESC"
+            ),
+            texts(&["pear", "fig"])
+        );
 
-        assert_eq!(result.columns, vec!["name"]);
-        assert_eq!(result.rows[0], vec![Value::Text("Alice".into())]);
+        // NaN cannot come from SQL, but a row stored through the API sorts before every
+        // number and matches no range
+        db.get_table_mut("products")
+            .unwrap()
+            .insert(vec![Value::Text("nan".into()), Value::Float(f64::NAN)])
+            .unwrap();
+        assert_eq!(
+            first_column(&db, "SELECT name FROM products ORDER BY price DESC"),
+            texts(&["apple", "fig", "pear", "Apple", "nan"])
+        );
+        assert_eq!(
+            first_column(
+                &db,
+                "SELECT name FROM products WHERE price < 2 OR price > 5 ORDER BY name"
+            ),
+            texts(&["Apple", "apple"])
+        );
     }
 
     #[test]
-    fn test_query_with_where_simple() {
+    fn test_query_order_by_hidden_column() {
         let mut db = Database::new();
-        db.execute("CREATE TABLE users (id INT, name TEXT, age INT)")
-            .unwrap();
-        db.execute("INSERT INTO users VALUES (1, 'Alice', 30)")
-            .unwrap();
-        db.execute("INSERT INTO users VALUES (2, 'Bob', 17)")
-            .unwrap();
-        db.execute("INSERT INTO users VALUES (3, 'Charlie', 25)")
-            .unwrap();
+        db.execute("CREATE TABLE users (id INT, age INT)").unwrap();
+        db.execute("INSERT INTO users VALUES (1, 30)").unwrap();
+        db.execute("INSERT INTO users VALUES (2, 20)").unwrap();
 
-        let result = db.query("SELECT name FROM users WHERE age > 18").unwrap();
+        let res = db.query("SELECT id FROM users ORDER BY age ASC").unwrap();
 
-        assert_eq!(result.columns, vec!["name"]);
-        assert_eq!(result.rows.len(), 2);
-        assert_eq!(result.rows[0], vec![Value::Text("Alice".into())]);
-        assert_eq!(result.rows[1], vec![Value::Text("Charlie".into())]);
+        assert_eq!(res.columns, vec!["id"]);
+        assert_eq!(res.rows[0][0], Value::Int(2)); // age 20
+        assert_eq!(res.rows[1][0], Value::Int(1)); // age 30
     }
 
     #[test]
-    fn test_query_with_where_and() {
+    fn test_query_order_by_computed_alias() {
         let mut db = Database::new();
-        db.execute("CREATE TABLE users (id INT, age INT, active INT)")
-            .unwrap();
-        db.execute("INSERT INTO users VALUES (1, 30, 1)").unwrap();
-        db.execute("INSERT INTO users VALUES (2, 17, 1)").unwrap();
-        db.execute("INSERT INTO users VALUES (3, 25, 0)").unwrap();
+        db.execute("CREATE TABLE t (name TEXT, age INT)").unwrap();
+        db.execute("INSERT INTO t VALUES ('Alice', 30)").unwrap();
+        db.execute("INSERT INTO t VALUES ('Bob', 25)").unwrap();
+        db.execute("INSERT INTO t VALUES ('Carol', 35)").unwrap();
 
-        // WHERE age > 18 AND active = 1
-        let result = db
-            .query("SELECT id FROM users WHERE age > 18 AND active = 1")
+        let res = db
+            .query("SELECT name, age * 2 AS double_age FROM t ORDER BY double_age DESC")
             .unwrap();
-
-        assert_eq!(result.rows.len(), 1);
-        assert_eq!(result.rows[0], vec![Value::Int(1)]);
+        assert_eq!(res.columns, vec!["name", "double_age"]);
+        assert_eq!(
+            res.column_types,
+            vec![Some(DataType::Text), Some(DataType::Int)]
+        );
+        assert_eq!(
+            res.rows,
+            vec![
+                vec![Value::Text("Carol".into()), Value::Int(70)],
+                vec![Value::Text("Alice".into()), Value::Int(60)],
+                vec![Value::Text("Bob".into()), Value::Int(50)],
+            ]
+        );
     }
 
     #[test]
-    fn test_query_with_null_comparison() {
+    fn test_query_projection_aliases() {
         let mut db = Database::new();
-        db.execute("CREATE TABLE users (id INT, age INT)").unwrap();
-        db.execute("INSERT INTO users (id) VALUES (1)").unwrap(); // age = NULL
-        db.execute("INSERT INTO users VALUES (2, 25)").unwrap();
-
-        // NULL > 18 should be false (SQL semantics)
-        let result = db.query("SELECT id FROM users WHERE age > 18").unwrap();
+        db.execute("CREATE TABLE staff (dept TEXT, salary INT)")
+            .unwrap();
+        for (dept, salary) in [("ops", 3000), ("dev", 4000), ("ops", 2000), ("dev", 5000)] {
+            db.execute(&format!("INSERT INTO staff VALUES ('{dept}', {salary})"))
+                .unwrap();
+        }
 
-        assert_eq!(result.rows.len(), 1);
-        assert_eq!(result.rows[0], vec![Value::Int(2)]);
-    }
+        // the alias renames the result column, WHERE still reads the table column
+        let res = db
+            .query("SELECT salary AS pay FROM staff WHERE salary > 2500 ORDER BY pay DESC")
+            .unwrap();
+        assert_eq!(res.columns, vec!["pay"]);
+        assert_eq!(res.column_types, vec![Some(DataType::Int)]);
+        assert_eq!(
+            res.rows,
+            vec![
+                vec![Value::Int(5000)],
+                vec![Value::Int(4000)],
+                vec![Value::Int(3000)],
+            ]
+        );
 
-    #[test]
-    fn test_query_with_limit() {
-        let mut db = Database::new();
-        db.execute("CREATE TABLE users (id INT, age INT, active INT)")
+        let res = db
+            .query(
+                "SELECT dept, COUNT(*) AS headcount, MIN(salary) AS low FROM staff \
+                 GROUP BY dept ORDER BY low",
+            )
             .unwrap();
-        db.execute("INSERT INTO users VALUES (1, 30, 1)").unwrap();
-        db.execute("INSERT INTO users VALUES (2, 17, 1)").unwrap();
-        db.execute("INSERT INTO users VALUES (3, 25, 0)").unwrap();
+        assert_eq!(res.columns, vec!["dept", "headcount", "low"]);
+        assert_eq!(
+            res.rows,
+            vec![
+                vec![Value::Text("ops".into()), Value::Int(2), Value::Int(2000)],
+                vec![Value::Text("dev".into()), Value::Int(2), Value::Int(4000)],
+            ]
+        );
 
-        // WHERE age > 18 AND active = 1
-        let result = db.query("SELECT id FROM users LIMIT 2").unwrap();
+        let res = db
+            .query("SELECT COUNT(*) FILTER (WHERE salary > 3500) AS rich FROM staff")
+            .unwrap();
+        assert_eq!(res.columns, vec!["rich"]);
+        assert_eq!(res.rows, vec![vec![Value::Int(2)]]);
+        assert_eq!(
+            db.explain("SELECT COUNT(*) AS n FROM staff").unwrap(),
+            "Aggregate COUNT(*) AS n\n  Scan staff"
+        );
 
-        assert_eq!(result.rows.len(), 2);
-        assert_eq!(result.rows[0], vec![Value::Int(1)]);
-        assert_eq!(result.rows[1], vec![Value::Int(2)]);
+        assert!(db.query("SELECT COUNT(*) AS FROM staff").is_err());
     }
 
     #[test]
-    fn test_query_with_where_no_match() {
+    fn test_query_star_with_extra_column() {
         let mut db = Database::new();
-        db.execute("CREATE TABLE users (id INT, age INT)").unwrap();
-        db.execute("INSERT INTO users VALUES (1, 10)").unwrap();
-        db.execute("INSERT INTO users VALUES (2, 15)").unwrap();
+        db.execute("CREATE TABLE t (id INT, name TEXT)").unwrap();
+        db.execute("INSERT INTO t VALUES (1, 'Alice')").unwrap();
+        db.execute("INSERT INTO t VALUES (2, 'Bob')").unwrap();
 
-        let result = db.query("SELECT id FROM users WHERE age > 100").unwrap();
+        let res = db.query("SELECT *, id FROM t").unwrap();
+        assert_eq!(res.columns, vec!["id", "name", "id"]);
+        assert_eq!(
+            res.column_types,
+            vec![
+                Some(DataType::Int),
+                Some(DataType::Text),
+                Some(DataType::Int)
+            ]
+        );
+        assert_eq!(
+            res.rows,
+            vec![
+                vec![Value::Int(1), Value::Text("Alice".into()), Value::Int(1)],
+                vec![Value::Int(2), Value::Text("Bob".into()), Value::Int(2)],
+            ]
+        );
 
-        assert_eq!(result.rows.len(), 0);
+        let res = db.query("SELECT name, * FROM t LIMIT 1").unwrap();
+        assert_eq!(res.columns, vec!["name", "id", "name"]);
+        assert!(db.query("SELECT *, COUNT(*) FROM t").is_err());
     }
 
     #[test]
-    fn test_query_order_by_asc_desc() {
+    fn test_query_computed_projection() {
         let mut db = Database::new();
-        db.execute("CREATE TABLE users (id INT, age INT)").unwrap();
-        db.execute("INSERT INTO users VALUES (1, 30)").unwrap();
-        db.execute("INSERT INTO users VALUES (2, 20)").unwrap();
-        db.execute("INSERT INTO users VALUES (3, 25)").unwrap();
+        db.execute("CREATE TABLE t (a INT, b FLOAT)").unwrap();
+        db.execute("INSERT INTO t VALUES (7, 0.5)").unwrap();
+        db.execute("INSERT INTO t (a) VALUES (2)").unwrap();
 
-        // Test ASC
-        let res_asc = db.query("SELECT age FROM users ORDER BY age ASC").unwrap();
-        assert_eq!(res_asc.rows[0][0], Value::Int(20));
-        assert_eq!(res_asc.rows[2][0], Value::Int(30));
+        let res = db
+            .query("SELECT a + 1 * 2, (a + 1) * 2, a / 2, a * b FROM t")
+            .unwrap();
+        assert_eq!(
+            res.columns,
+            vec!["a + (1 * 2)", "(a + 1) * 2", "a / 2", "a * b"]
+        );
+        assert_eq!(
+            res.rows[0],
+            vec![
+                Value::Int(9),
+                Value::Int(16),
+                Value::Int(3),
+                Value::Float(3.5)
+            ]
+        );
+        // NULL propagates through arithmetic
+        assert_eq!(res.rows[1][3], Value::Null);
 
-        // Test DESC
-        let res_desc = db.query("SELECT age FROM users ORDER BY age DESC").unwrap();
-        assert_eq!(res_desc.rows[0][0], Value::Int(30));
-        assert_eq!(res_desc.rows[2][0], Value::Int(20));
+        assert!(db.query("SELECT a / 0 FROM t").is_err());
+        assert!(db.query("SELECT a * 'x' FROM t").is_err());
+        assert!(db.query("SELECT missing + 1 FROM t").is_err());
     }
 
     #[test]
-    fn test_query_order_by_multiple_columns() {
+    fn test_query_order_by_expression() {
         let mut db = Database::new();
-        db.execute("CREATE TABLE users (name TEXT, score INT)")
+        db.execute("CREATE TABLE items (name TEXT, price INT, qty INT)")
             .unwrap();
-        db.execute("INSERT INTO users VALUES ('Alice', 100)")
+        db.execute("INSERT INTO items VALUES ('pen', 2, 10)")
             .unwrap();
-        db.execute("INSERT INTO users VALUES ('Bob', 100)").unwrap();
-        db.execute("INSERT INTO users VALUES ('Charlie', 50)")
+        db.execute("INSERT INTO items VALUES ('book', 15, 3)")
+            .unwrap();
+        db.execute("INSERT INTO items VALUES ('mug', 8, 2)")
             .unwrap();
 
         let res = db
-            .query("SELECT name, score FROM users ORDER BY score DESC, name ASC")
+            .query("SELECT name FROM items ORDER BY price * qty DESC")
             .unwrap();
+        assert_eq!(
+            res.rows,
+            vec![
+                vec![Value::Text("book".into())],
+                vec![Value::Text("pen".into())],
+                vec![Value::Text("mug".into())],
+            ]
+        );
 
-        assert_eq!(res.rows[0][0], Value::Text("Alice".into()));
-        assert_eq!(res.rows[1][0], Value::Text("Bob".into()));
-        assert_eq!(res.rows[2][0], Value::Text("Charlie".into()));
+        let res = db
+            .query("SELECT * FROM items ORDER BY (qty - price) * 2, name")
+            .unwrap();
+        assert_eq!(res.rows[0][0], Value::Text("book".into()));
+        assert_eq!(res.rows[0].len(), 3);
+
+        assert!(db.query("SELECT * FROM items ORDER BY nope * 2").is_err());
+        assert!(db.query("SELECT * FROM items ORDER BY name * 2").is_err());
     }
 
     #[test]
-    fn test_query_order_by_hidden_column() {
+    fn test_query_result_page() {
         let mut db = Database::new();
-        db.execute("CREATE TABLE users (id INT, age INT)").unwrap();
-        db.execute("INSERT INTO users VALUES (1, 30)").unwrap();
-        db.execute("INSERT INTO users VALUES (2, 20)").unwrap();
-
-        let res = db.query("SELECT id FROM users ORDER BY age ASC").unwrap();
+        db.execute("CREATE TABLE t (id INT)").unwrap();
+        for i in 0..10 {
+            db.execute(&format!("INSERT INTO t VALUES ({})", i))
+                .unwrap();
+        }
+        let res = db.query("SELECT id FROM t").unwrap();
+        let ids = |page: &QueryResult| -> Vec<i64> {
+            page.rows.iter().map(|r| r[0].as_int().unwrap()).collect()
+        };
 
-        assert_eq!(res.columns, vec!["id"]);
-        assert_eq!(res.rows[0][0], Value::Int(2)); // age 20
-        assert_eq!(res.rows[1][0], Value::Int(1)); // age 30
+        let first = res.page(0, 3);
+        assert_eq!(first.columns, vec!["id"]);
+        assert_eq!(first.column_types, vec![Some(DataType::Int)]);
+        assert_eq!(ids(&first), vec![0, 1, 2]);
+        assert_eq!(ids(&res.page(2, 3)), vec![6, 7, 8]);
+        // the last page is partial
+        assert_eq!(ids(&res.page(3, 3)), vec![9]);
+        assert!(res.page(4, 3).rows.is_empty());
+        assert!(res.page(usize::MAX, usize::MAX).rows.is_empty());
+        assert_eq!(res.rows.len(), 10);
     }
 
     #[test]
@@ -1307,6 +6617,103 @@ mod tests {
         assert_eq!(res.rows[2][0], Value::Int(8));
     }
 
+    #[test]
+    fn test_query_order_by_deterministic_order() {
+        let mut db = Database::new();
+        db.execute("CREATE TABLE scores (id INT, score INT)")
+            .unwrap();
+        for id in 0..200 {
+            db.execute(&format!("INSERT INTO scores VALUES ({id}, {})", id % 2))
+                .unwrap();
+        }
+        let sql = "SELECT id FROM scores ORDER BY score DESC";
+        let odd_then_even: Vec<Value> = (0..200)
+            .filter(|id| id % 2 == 1)
+            .chain((0..200).filter(|id| id % 2 == 0))
+            .map(Value::Int)
+            .collect();
+
+        // the sort is stable, so ties keep the scan order with or without the rowid key
+        assert_eq!(first_column(&db, sql), odd_then_even);
+        db.execute("SET deterministic_order = TRUE").unwrap();
+        assert!(db.comparison_config.deterministic_order);
+        assert_eq!(first_column(&db, sql), odd_then_even);
+        // groups have no rowid and keep the order they were first seen in
+        assert_eq!(
+            first_column(
+                &db,
+                "SELECT id, score FROM scores GROUP BY id, score ORDER BY score DESC"
+            ),
+            odd_then_even
+        );
+        let plan = db
+            .explain("SELECT id FROM scores ORDER BY score DESC")
+            .unwrap();
+        assert!(plan.contains("score DESC, rowid ASC"), "{plan}");
+
+        assert!(db.execute("SET deterministic_order = 1").is_err());
+        db.execute("SET deterministic_order = FALSE").unwrap();
+        let plan = db
+            .explain("SELECT id FROM scores ORDER BY score DESC")
+            .unwrap();
+        assert!(!plan.contains("rowid"), "{plan}");
+    }
+
+    #[test]
+    fn test_where_is_null() {
+        let mut db = Database::new();
+        db.execute("CREATE TABLE users (id INT, email TEXT)")
+            .unwrap();
+        for id in 1..=100 {
+            let email = if id % 10 == 0 {
+                "NULL".to_string()
+            } else {
+                format!("'u{id}@x.org'")
+            };
+            db.execute(&format!("INSERT INTO users VALUES ({id}, {email})"))
+                .unwrap();
+        }
+        db.execute("DELETE FROM users WHERE id = 20").unwrap();
+
+        // the null bitmap gives the rows directly, the deleted one is skipped
+        let (res, stats) = db
+            .query_with_stats("SELECT id FROM users WHERE email IS NULL")
+            .unwrap();
+        let expected: Vec<Vec<Value>> = [10, 30, 40, 50, 60, 70, 80, 90, 100]
+            .map(|id| vec![Value::Int(id)])
+            .into();
+        assert_eq!(res.rows, expected);
+        assert_eq!(stats.rows_scanned, 9);
+
+        let (res, stats) = db
+            .query_with_stats("SELECT id FROM users WHERE email IS NOT NULL LIMIT 3")
+            .unwrap();
+        assert_eq!(res.rows.len(), 3);
+        assert_eq!(stats.rows_scanned, 3);
+        assert_eq!(
+            db.query_scalar("SELECT COUNT(*) FROM users WHERE email IS NOT NULL"),
+            Ok(Value::Int(90))
+        );
+        // inside a larger condition the test runs on every row
+        assert_eq!(
+            db.query_scalar("SELECT COUNT(*) FROM users WHERE email IS NULL AND id > 50"),
+            Ok(Value::Int(5))
+        );
+
+        db.execute("UPDATE users SET email = 'none' WHERE email IS NULL")
+            .unwrap();
+        db.execute("DELETE FROM users WHERE email IS NOT NULL LIMIT 5")
+            .unwrap();
+        assert_eq!(
+            db.query_scalar("SELECT COUNT(*) FROM users"),
+            Ok(Value::Int(94))
+        );
+        assert_eq!(
+            db.query_scalar("SELECT COUNT(*) FROM users WHERE email IS NULL"),
+            Ok(Value::Int(0))
+        );
+    }
+
     #[test]
     fn test_delete_specific_row() {
         let mut db = Database::new();
@@ -1343,6 +6750,270 @@ mod tests {
         assert_eq!(result.rows[1][0], Value::Int(4));
     }
 
+    #[test]
+    fn test_group_by_rollup() {
+        let mut db = Database::new();
+        db.execute("CREATE TABLE sales (region TEXT, product TEXT, amount INT)")
+            .unwrap();
+        for (region, product, amount) in [
+            ("north", "apple", 10),
+            ("north", "pear", 5),
+            ("south", "apple", 7),
+            ("north", "apple", 3),
+        ] {
+            db.execute(&format!(
+                "INSERT INTO sales VALUES ('{region}', '{product}', {amount})"
+            ))
+            .unwrap();
+        }
+
+        let res = db
+            .query(
+                "SELECT region, product, SUM(amount) FROM sales GROUP BY ROLLUP(region, product)",
+            )
+            .unwrap();
+        let text = |s: &str| Value::Text(s.into());
+        assert_eq!(
+            res.rows,
+            vec![
+                vec![text("north"), text("apple"), Value::Int(13)],
+                vec![text("north"), text("pear"), Value::Int(5)],
+                vec![text("south"), text("apple"), Value::Int(7)],
+                // subtotals per region
+                vec![text("north"), Value::Null, Value::Int(18)],
+                vec![text("south"), Value::Null, Value::Int(7)],
+                // grand total
+                vec![Value::Null, Value::Null, Value::Int(25)],
+            ]
+        );
+
+        // ORDER BY and HAVING apply to every level
+        let res = db
+            .query(
+                "SELECT region, COUNT(*) FROM sales GROUP BY ROLLUP(region) \
+                 HAVING COUNT(*) > 1 ORDER BY region",
+            )
+            .unwrap();
+        assert_eq!(
+            res.rows,
+            vec![
+                vec![Value::Null, Value::Int(4)],
+                vec![text("north"), Value::Int(3)],
+            ]
+        );
+
+        let err = db
+            .query("SELECT region, COUNT(*) FROM sales GROUP BY ROLLUP(region) HAVING region = 'x'")
+            .unwrap_err();
+        assert!(err.contains("rolled up"), "{err}");
+        assert_eq!(
+            db.explain("SELECT region, COUNT(*) FROM sales GROUP BY ROLLUP(region)")
+                .unwrap(),
+            "Aggregate COUNT(*) by ROLLUP(region)\n  Scan sales"
+        );
+    }
+
+    #[test]
+    fn test_not_null_constraints() {
+        let mut db = Database::new();
+        db.execute(
+            "CREATE TABLE users (id INT AUTO_INCREMENT NOT NULL, name TEXT NOT NULL, age INT)",
+        )
+        .unwrap();
+        db.execute("INSERT INTO users (name) VALUES ('Ada')")
+            .unwrap();
+
+        for sql in [
+            "INSERT INTO users (age) VALUES (30)",
+            "INSERT INTO users VALUES (NULL, NULL, 30)",
+            "INSERT INTO users (name) VALUES ('Bob'), (NULL)",
+            "UPDATE users SET name = NULL WHERE id = 1",
+        ] {
+            let err = db.execute(sql).unwrap_err();
+            assert!(err.contains("\"name\" is NOT NULL"), "{sql}: {err}");
+        }
+        assert_eq!(
+            db.query("SELECT * FROM users").unwrap().rows,
+            vec![vec![Value::Int(1), Value::Text("Ada".into()), Value::Null]]
+        );
+
+        // a column without the constraint still defaults to NULL
+        db.execute("UPDATE users SET age = NULL, name = 'Bob' WHERE id = 1")
+            .unwrap();
+    }
+
+    #[test]
+    fn test_check_constraints() {
+        let mut db = Database::new();
+        db.execute(
+            "CREATE TABLE people (id INT, age INT CHECK (age BETWEEN 0 AND 150), \
+             name TEXT CHECK (name = 'ann' OR name = 'bob'))",
+        )
+        .unwrap();
+
+        db.execute("INSERT INTO people VALUES (1, 30, 'ann')")
+            .unwrap();
+        let err = db
+            .execute("INSERT INTO people VALUES (2, 200, 'bob')")
+            .unwrap_err();
+        assert!(
+            err.contains("CHECK (age BETWEEN 0 AND 150) on column age"),
+            "{err}"
+        );
+        assert!(
+            db.execute("INSERT INTO people VALUES (3, 20, 'eve')")
+                .is_err()
+        );
+        // NULL does not violate a constraint
+        db.execute("INSERT INTO people (id) VALUES (4)").unwrap();
+        // float bounds of any magnitude read back from the stored condition
+        db.execute("CREATE TABLE m (f FLOAT CHECK (f > -0.000001 AND f < 10000000000000000.0))")
+            .unwrap();
+        db.execute("INSERT INTO m VALUES (0.5)").unwrap();
+        assert!(db.execute("INSERT INTO m VALUES (-0.5)").is_err());
+
+        let err = db
+            .execute("UPDATE people SET age = 151 WHERE id = 1")
+            .unwrap_err();
+        assert!(err.contains("violates CHECK"), "{err}");
+        db.execute("UPDATE people SET age = 31 WHERE id = 1")
+            .unwrap();
+        let res = db.query("SELECT id, age FROM people").unwrap();
+        assert_eq!(
+            res.rows,
+            vec![
+                vec![Value::Int(1), Value::Int(31)],
+                vec![Value::Int(4), Value::Null],
+            ]
+        );
+
+        // constraints are validated when the table is created
+        let err = db
+            .execute("CREATE TABLE bad (n INT CHECK (missing > 1))")
+            .unwrap_err();
+        assert!(err.contains("CHECK (missing > 1) on column n"), "{err}");
+        assert!(
+            db.execute("CREATE TABLE bad (n INT CHECK (n > (SELECT MAX(id) FROM people)))")
+                .is_err()
+        );
+        assert!(db.get_table("bad").is_none());
+    }
+
+    #[test]
+    fn test_generated_columns() {
+        let mut db = Database::new();
+        db.execute(
+            "CREATE TABLE rects (id INT, w INT, h INT, \
+             area INT GENERATED ALWAYS AS (w * h), half FLOAT GENERATED ALWAYS AS (w / 2))",
+        )
+        .unwrap();
+
+        db.execute("INSERT INTO rects VALUES (1, 2, 3)").unwrap();
+        db.execute("INSERT INTO rects VALUES (2, 4, 5, NULL, NULL)")
+            .unwrap();
+        db.execute("INSERT INTO rects (id, w) VALUES (3, 6)")
+            .unwrap();
+        let res = db.query("SELECT id, area, half FROM rects").unwrap();
+        assert_eq!(
+            res.rows,
+            vec![
+                vec![Value::Int(1), Value::Int(6), Value::Float(1.0)],
+                vec![Value::Int(2), Value::Int(20), Value::Float(2.0)],
+                vec![Value::Int(3), Value::Null, Value::Float(3.0)],
+            ]
+        );
+
+        // updating an input recomputes the generated values
+        db.execute("UPDATE rects SET h = 10 WHERE id > 1").unwrap();
+        let res = db.query("SELECT id, area FROM rects").unwrap();
+        assert_eq!(
+            res.rows,
+            vec![
+                vec![Value::Int(1), Value::Int(6)],
+                vec![Value::Int(2), Value::Int(40)],
+                vec![Value::Int(3), Value::Int(60)],
+            ]
+        );
+
+        // direct writes are rejected
+        for sql in [
+            "INSERT INTO rects VALUES (4, 1, 1, 7, NULL)",
+            "INSERT INTO rects (id, area) VALUES (4, 7)",
+            "UPDATE rects SET area = 7 WHERE id = 1",
+        ] {
+            let err = db.execute(sql).unwrap_err();
+            assert!(err.contains("generated column \"area\""), "{sql}: {err}");
+        }
+        assert_eq!(
+            db.query_scalar("SELECT COUNT(*) FROM rects"),
+            Ok(Value::Int(3))
+        );
+
+        // the expression is validated when the table is created
+        let err = db
+            .execute("CREATE TABLE bad (n INT, m INT GENERATED ALWAYS AS (missing + 1))")
+            .unwrap_err();
+        assert!(err.contains("on column m"), "{err}");
+        assert!(
+            db.execute(
+                "CREATE TABLE bad (n INT, a INT GENERATED ALWAYS AS (n), \
+                 b INT GENERATED ALWAYS AS (a))"
+            )
+            .is_err()
+        );
+        for sql in [
+            "CREATE TABLE bad (w INT, s TEXT GENERATED ALWAYS AS (w))",
+            "CREATE TABLE bad (w FLOAT, n INT GENERATED ALWAYS AS (w * 2))",
+            "CREATE TABLE bad (s TEXT, n INT GENERATED ALWAYS AS (s + 1))",
+        ] {
+            let err = db.execute(sql).unwrap_err();
+            assert!(err.contains("GENERATED ALWAYS AS"), "{sql}: {err}");
+        }
+        assert!(db.get_table("bad").is_none());
+
+        // float literals of any magnitude read back from the stored expression
+        db.execute("CREATE TABLE h (w INT, s FLOAT GENERATED ALWAYS AS (w * 0.00001))")
+            .unwrap();
+        db.execute("INSERT INTO h (w) VALUES (2)").unwrap();
+        assert_eq!(
+            db.query_scalar("SELECT s FROM h"),
+            Ok(Value::Float(2.0 * 0.00001))
+        );
+    }
+
+    #[test]
+    fn test_delete_limit() {
+        let mut db = Database::new();
+        db.execute("CREATE TABLE logs (id INT, level TEXT)")
+            .unwrap();
+        for id in 1..=10 {
+            let level = if id % 2 == 0 { "debug" } else { "info" };
+            db.execute(&format!("INSERT INTO logs VALUES ({id}, '{level}')"))
+                .unwrap();
+        }
+
+        // at most 3 of the 5 debug rows go, the first ones in storage order
+        let deleted = db
+            .run("DELETE FROM logs WHERE level = 'debug' LIMIT 3 RETURNING id")
+            .unwrap()
+            .unwrap();
+        let ids = |values: &[i64]| -> Vec<Vec<Value>> {
+            values.iter().map(|&id| vec![Value::Int(id)]).collect()
+        };
+        assert_eq!(deleted.rows, ids(&[2, 4, 6]));
+        let res = db
+            .query("SELECT id FROM logs WHERE level = 'debug'")
+            .unwrap();
+        assert_eq!(res.rows, ids(&[8, 10]));
+        assert_eq!(db.query("SELECT id FROM logs").unwrap().rows.len(), 7);
+
+        db.execute("DELETE FROM logs WHERE level = 'debug' LIMIT 10")
+            .unwrap();
+        db.execute("DELETE FROM logs WHERE id > 0 LIMIT 0").unwrap();
+        let res = db.query("SELECT id FROM logs").unwrap();
+        assert_eq!(res.rows, ids(&[1, 3, 5, 7, 9]));
+    }
+
     #[test]
     fn test_delete_no_match() {
         let mut db = Database::new();
@@ -1414,32 +7085,241 @@ mod tests {
         db.execute("UPDATE products SET name = 'Mechanical Keyboard', price = 120 WHERE id = 1")
             .unwrap();
 
-        let result = db.query("SELECT name, price FROM products").unwrap();
-        assert_eq!(result.rows[0][0], Value::Text("Mechanical Keyboard".into()));
-        assert_eq!(result.rows[0][1], Value::Int(120));
+        let result = db.query("SELECT name, price FROM products").unwrap();
+        assert_eq!(result.rows[0][0], Value::Text("Mechanical Keyboard".into()));
+        assert_eq!(result.rows[0][1], Value::Int(120));
+    }
+
+    #[test]
+    fn test_update_with_complex_where() {
+        let mut db = Database::new();
+        db.execute("CREATE TABLE employees (id INT, dept TEXT, salary INT)")
+            .unwrap();
+        db.execute("INSERT INTO employees VALUES (1, 'IT', 3000)")
+            .unwrap();
+        db.execute("INSERT INTO employees VALUES (2, 'HR', 2500)")
+            .unwrap();
+        db.execute("INSERT INTO employees VALUES (3, 'IT', 3500)")
+            .unwrap();
+
+        db.execute("UPDATE employees SET salary = 3300 WHERE dept = 'IT' AND salary < 3200")
+            .unwrap();
+
+        let result = db
+            .query("SELECT id, salary FROM employees ORDER BY id ASC")
+            .unwrap();
+        assert_eq!(result.rows[0][1], Value::Int(3300));
+        assert_eq!(result.rows[1][1], Value::Int(2500));
+        assert_eq!(result.rows[2][1], Value::Int(3500));
+    }
+
+    #[test]
+    fn test_int_literal_coerced_for_float_column() {
+        let mut db = setup_employees();
+        let table = db.get_table("employees").unwrap();
+
+        let Statement::Select(mut select) = Parser::new(
+            Tokenizer::new("SELECT name FROM employees WHERE bonus > 300")
+                .tokenize()
+                .unwrap(),
+        )
+        .parse()
+        .unwrap() else {
+            panic!("Expected Select");
+        };
+        db.bind_expression(
+            select.where_clause.as_mut().unwrap(),
+            &table.schema,
+            Some(table),
+        );
+        assert_eq!(
+            select.where_clause,
+            Some(Expr::Comparison {
+                column: "bonus",
+                op: ComparisonOp::Gt,
+                value: Value::Float(300.0),
+            })
+        );
+
+        let res = db
+            .query("SELECT name FROM employees WHERE bonus > 300")
+            .unwrap();
+        assert_eq!(
+            res.rows,
+            vec![
+                vec![Value::Text("Alice".into())],
+                vec![Value::Text("Bob".into())]
+            ]
+        );
+        let res = db
+            .query("SELECT name FROM employees GROUP BY name HAVING AVG(salary) > 2500")
+            .unwrap();
+        assert_eq!(res.rows.len(), 2);
+        db.execute("UPDATE employees SET salary = 0 WHERE bonus = 250")
+            .unwrap();
+        let res = db
+            .query("SELECT salary FROM employees WHERE name = 'Carol'")
+            .unwrap();
+        assert_eq!(res.rows, vec![vec![Value::Int(0)]]);
+
+        // incompatible literals are still rejected before any row is read
+        let err = db
+            .query("SELECT name FROM employees WHERE name > 5")
+            .unwrap_err();
+        assert!(err.contains("cannot compare Text with Int"), "{err}");
+    }
+
+    #[test]
+    fn test_where_rejects_select_alias() {
+        let db = setup_employees();
+
+        let err = db
+            .query("SELECT salary * 2 AS d FROM employees WHERE d > 10")
+            .unwrap_err();
+        assert_eq!(
+            err,
+            "Column `d` not found (aliases cannot be used in WHERE)"
+        );
+
+        // an alias shadowing a real column leaves WHERE on the table column
+        let res = db
+            .query("SELECT salary * 2 AS salary FROM employees WHERE salary > 3500")
+            .unwrap();
+        assert_eq!(res.rows, vec![vec![Value::Int(8000)]]);
+    }
+
+    #[test]
+    fn test_where_text_range() {
+        let mut db = Database::new();
+        db.execute("CREATE TABLE users (name TEXT)").unwrap();
+        for name in ["Alice", "Mallory", "Zoe", "Bob", "M", "mike"] {
+            db.execute(&format!("INSERT INTO users VALUES ('{name}')"))
+                .unwrap();
+        }
+
+        // uppercase letters sort before lowercase ones
+        assert_eq!(
+            first_column(&db, "SELECT name FROM users WHERE name > 'M' ORDER BY name"),
+            texts(&["Mallory", "Zoe", "mike"])
+        );
+        assert_eq!(
+            first_column(&db, "SELECT name FROM users WHERE name < 'M' ORDER BY name"),
+            texts(&["Alice", "Bob"])
+        );
+        assert_eq!(
+            first_column(
+                &db,
+                "SELECT name FROM users WHERE name > 'B' AND name < 'N' ORDER BY name"
+            ),
+            texts(&["Bob", "M", "Mallory"])
+        );
+
+        db.execute("SET case_sensitive = FALSE").unwrap();
+        assert_eq!(
+            first_column(&db, "SELECT name FROM users WHERE name < 'n' ORDER BY name"),
+            texts(&["Alice", "Bob", "M", "Mallory", "mike"])
+        );
+    }
+
+    #[test]
+    fn test_where_greater_lower_or_equal() {
+        let mut db = Database::new();
+        db.execute("CREATE TABLE items (id INT, price FLOAT, name TEXT)")
+            .unwrap();
+        for (id, price, name) in [(1, 9.5, "apple"), (2, 10.0, "kiwi"), (3, 12.0, "pear")] {
+            db.execute(&format!(
+                "INSERT INTO items VALUES ({id}, {price:.1}, '{name}')"
+            ))
+            .unwrap();
+        }
+        db.execute("INSERT INTO items VALUES (4, NULL, NULL)")
+            .unwrap();
+
+        assert_eq!(
+            first_column(&db, "SELECT id FROM items WHERE id >= 2"),
+            [2, 3, 4].map(Value::Int)
+        );
+        assert_eq!(
+            first_column(&db, "SELECT id FROM items WHERE id <= 2"),
+            [1, 2].map(Value::Int)
+        );
+        assert_eq!(
+            first_column(&db, "SELECT id FROM items WHERE price >= 10.0"),
+            [2, 3].map(Value::Int)
+        );
+        assert_eq!(
+            first_column(&db, "SELECT id FROM items WHERE price <= 10.0"),
+            [1, 2].map(Value::Int)
+        );
+        assert_eq!(
+            first_column(&db, "SELECT id FROM items WHERE name >= 'kiwi'"),
+            [2, 3].map(Value::Int)
+        );
+        assert_eq!(
+            first_column(&db, "SELECT id FROM items WHERE name <= 'kiwi'"),
+            [1, 2].map(Value::Int)
+        );
+        // a column compared to another one of the same row
+        assert_eq!(
+            first_column(&db, "SELECT id FROM items WHERE id <= id"),
+            [1, 2, 3, 4].map(Value::Int)
+        );
+
+        let err = db.query("SELECT id FROM items WHERE id >== 2").unwrap_err();
+        assert!(err.contains("\">==\" is not supported"), "{err}");
     }
 
     #[test]
-    fn test_update_with_complex_where() {
+    fn test_where_not_equal() {
         let mut db = Database::new();
-        db.execute("CREATE TABLE employees (id INT, dept TEXT, salary INT)")
+        db.execute("CREATE TABLE t (id INT, name TEXT, ok BOOL, score FLOAT)")
             .unwrap();
-        db.execute("INSERT INTO employees VALUES (1, 'IT', 3000)")
+        db.execute("INSERT INTO t VALUES (1, 'a', TRUE, 1.5)")
             .unwrap();
-        db.execute("INSERT INTO employees VALUES (2, 'HR', 2500)")
+        db.execute("INSERT INTO t VALUES (2, 'b', FALSE, 2.5)")
             .unwrap();
-        db.execute("INSERT INTO employees VALUES (3, 'IT', 3500)")
+        db.execute("INSERT INTO t VALUES (3, NULL, NULL, NULL)")
             .unwrap();
 
-        db.execute("UPDATE employees SET salary = 3300 WHERE dept = 'IT' AND salary < 3200")
-            .unwrap();
+        // NULL is neither equal nor different
+        let count = |db: &Database, sql: &str| match db.query_scalar(sql) {
+            Ok(Value::Int(n)) => n,
+            other => panic!("{sql}: {other:?}"),
+        };
+        assert_eq!(count(&db, "SELECT COUNT(*) FROM t WHERE name != 'a'"), 1);
+        assert_eq!(count(&db, "SELECT COUNT(*) FROM t WHERE ok <> TRUE"), 1);
+        assert_eq!(count(&db, "SELECT COUNT(*) FROM t WHERE score != 2.5"), 1);
+        assert_eq!(count(&db, "SELECT COUNT(*) FROM t WHERE id <> id"), 0);
+
+        db.execute("DELETE FROM t WHERE id != 1").unwrap();
+        let res = db.query("SELECT id FROM t").unwrap();
+        assert_eq!(res.rows, vec![vec![Value::Int(1)]]);
+    }
 
-        let result = db
-            .query("SELECT id, salary FROM employees ORDER BY id ASC")
+    #[test]
+    fn test_where_type_mismatch_names_column() {
+        let mut db = Database::new();
+        db.execute("CREATE TABLE users (name TEXT, age INT, active BOOL)")
             .unwrap();
-        assert_eq!(result.rows[0][1], Value::Int(3300));
-        assert_eq!(result.rows[1][1], Value::Int(2500));
-        assert_eq!(result.rows[2][1], Value::Int(3500));
+        db.execute("INSERT INTO users VALUES ('Alice', 30, TRUE)")
+            .unwrap();
+
+        let err = db
+            .query("SELECT name FROM users WHERE name = 'Alice' AND age > 'x'")
+            .unwrap_err();
+        assert_eq!(
+            err,
+            "in WHERE clause, column `age` with operator `>`: cannot compare Int with Text"
+        );
+
+        // errors raised while evaluating rows carry the same context
+        let err = db
+            .query("SELECT name FROM users WHERE active > FALSE")
+            .unwrap_err();
+        assert!(
+            err.starts_with("in WHERE clause, column `active` with operator `>`:"),
+            "{err}"
+        );
     }
 
     #[test]
@@ -1498,6 +7378,43 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_update_set_null_every_type() {
+        let mut db = Database::new();
+        db.execute(
+            "CREATE TABLE t (id INT, i INT, u UINT, f FLOAT, b BOOL, ts TIMESTAMP, \
+             raw BYTES, txt TEXT)",
+        )
+        .unwrap();
+        db.execute(
+            "INSERT INTO t (id, i, u, f, b, raw, txt) VALUES (1, 3, 2, 0.5, TRUE, X'ff', 'x')",
+        )
+        .unwrap();
+        db.get_table_mut("t")
+            .unwrap()
+            .dictionary_encode("txt")
+            .unwrap();
+
+        db.execute(
+            "UPDATE t SET i = NULL, u = NULL, f = NULL, b = NULL, ts = NULL, \
+             raw = NULL, txt = NULL WHERE id = 1",
+        )
+        .unwrap();
+        let res = db.query("SELECT * FROM t").unwrap();
+        let mut expected = vec![Value::Int(1)];
+        expected.extend(std::iter::repeat_n(Value::Null, 7));
+        assert_eq!(res.rows, vec![expected]);
+
+        // the columns keep their type: a typed value can be written back
+        db.execute("UPDATE t SET u = 7, txt = 'y' WHERE id = 1")
+            .unwrap();
+        let res = db.query("SELECT u, txt FROM t").unwrap();
+        assert_eq!(
+            res.rows,
+            vec![vec![Value::UInt(7), Value::Text("y".into())]]
+        );
+    }
+
     #[test]
     fn test_insert_with_null_literal() {
         let mut db = Database::new();
@@ -1667,6 +7584,134 @@ mod tests {
         assert_eq!(table.deletion_vector.count_ones(), 1);
     }
 
+    #[test]
+    fn test_change_hooks_observe_insert_and_delete() {
+        use std::sync::Mutex;
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let mut db = Database::new();
+        db.vacuum_config.enabled = false;
+        db.execute("CREATE TABLE t (id INT)").unwrap();
+        db.execute("INSERT INTO t VALUES (1)").unwrap();
+
+        let seen = Arc::clone(&events);
+        db.on_change(Box::new(move |event| {
+            seen.lock().unwrap().push(event.clone())
+        }));
+
+        db.execute("INSERT INTO t VALUES (2)").unwrap();
+        db.execute("INSERT INTO t VALUES (3)").unwrap();
+        db.execute("DELETE FROM t WHERE id > 1").unwrap();
+        // failed or no-op statements are not reported
+        assert!(db.execute("INSERT INTO t VALUES ('x')").is_err());
+        db.execute("DELETE FROM t WHERE id = 42").unwrap();
+
+        let events = events.lock().unwrap();
+        assert_eq!(
+            *events,
+            vec![
+                ChangeEvent {
+                    table: "t".into(),
+                    kind: ChangeKind::Insert,
+                    rows: vec![1],
+                },
+                ChangeEvent {
+                    table: "t".into(),
+                    kind: ChangeKind::Insert,
+                    rows: vec![2],
+                },
+                ChangeEvent {
+                    table: "t".into(),
+                    kind: ChangeKind::Delete,
+                    rows: vec![1, 2],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_string_pool_shares_allocations_across_tables() {
+        let mut db = Database::new();
+        db.enable_string_pool();
+        db.execute("CREATE TABLE emp (id INT, dept TEXT)").unwrap();
+        db.execute("CREATE TABLE teams (dept TEXT)").unwrap();
+
+        for i in 0..50 {
+            db.execute(&format!("INSERT INTO emp VALUES ({}, 'Engineering')", i))
+                .unwrap();
+        }
+        db.execute("INSERT INTO teams VALUES ('Engineering')")
+            .unwrap();
+        db.execute("UPDATE emp SET dept = 'Engineering' WHERE id = 0")
+            .unwrap();
+
+        let first = match db.get_table("teams").unwrap().get_row(0).unwrap()[0] {
+            Value::Text(ref s) => Arc::clone(s),
+            _ => panic!("Expected Text"),
+        };
+        let emp = db.get_table("emp").unwrap();
+        for i in 0..50 {
+            match emp.get_col("dept").unwrap().get(i).unwrap() {
+                Value::Text(s) => assert!(Arc::ptr_eq(&s, &first)),
+                _ => panic!("Expected Text"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_string_pool_released_by_vacuum() {
+        let mut db = Database::new();
+        db.enable_string_pool();
+        db.execute("CREATE TABLE t (id INT, tag TEXT)").unwrap();
+        db.execute("INSERT INTO t VALUES (1, 'gone')").unwrap();
+        db.execute("INSERT INTO t VALUES (2, 'kept')").unwrap();
+        db.execute("DELETE FROM t WHERE id = 1").unwrap();
+        db.execute("VACUUM").unwrap();
+
+        let pool = db.string_pool.as_ref().unwrap();
+        assert!(!pool.contains("gone"));
+        assert!(pool.contains("kept"));
+    }
+
+    #[test]
+    fn test_dictionary_encoded_column_filters() {
+        let mut db = Database::new();
+        db.execute("CREATE TABLE staff (name TEXT, dept TEXT)")
+            .unwrap();
+        db.execute("INSERT INTO staff VALUES ('Alice', 'Sales')")
+            .unwrap();
+        db.execute("INSERT INTO staff VALUES ('Bob', 'IT')")
+            .unwrap();
+        db.dictionary_encode("staff", "dept").unwrap();
+        db.execute("INSERT INTO staff VALUES ('Carol', 'Sales')")
+            .unwrap();
+        db.execute("UPDATE staff SET dept = 'Sales' WHERE name = 'Bob'")
+            .unwrap();
+        db.execute("INSERT INTO staff VALUES ('Dan', 'HR')")
+            .unwrap();
+
+        // the matching rows are found from the dictionary codes, without a full scan
+        let (res, stats) = db
+            .query_with_stats("SELECT name FROM staff WHERE dept = 'Sales'")
+            .unwrap();
+        assert_eq!(res.rows.len(), 3);
+        assert_eq!(stats.rows_scanned, 3);
+        let (res, stats) = db
+            .query_with_stats("SELECT name FROM staff WHERE dept = 'Ops'")
+            .unwrap();
+        assert!(res.rows.is_empty());
+        assert_eq!(stats.rows_scanned, 0);
+
+        // without case sensitivity, the codes cannot tell the matching rows
+        db.execute("SET case_sensitive = FALSE").unwrap();
+        let res = db
+            .query("SELECT name FROM staff WHERE dept = 'sales'")
+            .unwrap();
+        assert_eq!(res.rows.len(), 3);
+        assert!(db.dictionary_encode("staff", "missing").is_err());
+        assert!(db.dictionary_encode("nope", "dept").is_err());
+    }
+
     // ─────────────────────────────────────────────────────────────
     // Aggregation tests
     // ─────────────────────────────────────────────────────────────
@@ -1706,6 +7751,89 @@ mod tests {
         assert_eq!(res.rows[0][0], Value::Int(2));
     }
 
+    #[test]
+    fn test_approx_count_distinct() {
+        let mut db = Database::new();
+        db.execute("CREATE TABLE t (grp TEXT, val INT)").unwrap();
+        let table = db.get_table_mut("t").unwrap();
+        for i in 0..50_000 {
+            let grp = if i % 2 == 0 { "even" } else { "odd" };
+            table
+                .insert(vec![Value::Text(grp.into()), Value::Int(i % 20_000)])
+                .unwrap();
+        }
+        db.execute("INSERT INTO t (grp) VALUES ('even')").unwrap();
+
+        let res = db
+            .query("SELECT APPROX_COUNT_DISTINCT(val) FROM t")
+            .unwrap();
+        assert_eq!(res.columns, vec!["APPROX_COUNT_DISTINCT(val)"]);
+        let Value::Int(estimate) = res.rows[0][0] else {
+            panic!("expected an Int, got {:?}", res.rows[0][0]);
+        };
+        assert!((estimate - 20_000).abs() < 1_000, "estimate {estimate}");
+
+        // small groups are counted almost exactly
+        let res = db
+            .query("SELECT grp, APPROX_COUNT_DISTINCT(grp) FROM t GROUP BY grp")
+            .unwrap();
+        assert_eq!(res.rows[0][1], Value::Int(1));
+        assert_eq!(res.rows[1][1], Value::Int(1));
+    }
+
+    #[test]
+    fn test_aggregate_filter() {
+        let mut db = Database::new();
+        db.execute("CREATE TABLE t (team TEXT, active BOOL, score FLOAT)")
+            .unwrap();
+        for (team, active, score) in [
+            ("a", true, 1.5),
+            ("a", false, 2.0),
+            ("b", true, 4.0),
+            ("b", true, 8.0),
+            ("b", false, 16.0),
+        ] {
+            db.execute(&format!(
+                "INSERT INTO t VALUES ('{team}', {active}, {score:.1})"
+            ))
+            .unwrap();
+        }
+
+        let res = db
+            .query("SELECT COUNT(*) FILTER (WHERE active = TRUE), COUNT(*) FROM t")
+            .unwrap();
+        assert_eq!(
+            res.columns,
+            vec!["COUNT(*) FILTER (WHERE active = TRUE)", "COUNT(*)"]
+        );
+        assert_eq!(res.rows, vec![vec![Value::Int(3), Value::Int(5)]]);
+
+        // the filter applies per group, on top of the WHERE clause; an int literal is
+        // coerced to the FLOAT column like in WHERE
+        let res = db
+            .query(
+                "SELECT team, SUM(score) FILTER (WHERE active = TRUE AND score > 4), SUM(score) \
+                 FROM t WHERE score < 10 GROUP BY team",
+            )
+            .unwrap();
+        assert_eq!(
+            res.rows,
+            vec![
+                vec![Value::Text("a".into()), Value::Null, Value::Float(3.5)],
+                vec![
+                    Value::Text("b".into()),
+                    Value::Float(8.0),
+                    Value::Float(12.0)
+                ],
+            ]
+        );
+
+        assert!(
+            db.query("SELECT COUNT(*) FILTER (WHERE missing = 1) FROM t")
+                .is_err()
+        );
+    }
+
     #[test]
     fn test_sum_int() {
         let db = setup_employees();
@@ -1775,6 +7903,66 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_avg_float_compensated_summation() {
+        let mut db = Database::new();
+        db.execute("CREATE TABLE t (x FLOAT)").unwrap();
+        let mut values = vec![1e15];
+        values.extend(std::iter::repeat_n(0.1, 10_000));
+        values.push(-1e15);
+        let table = db.get_table_mut("t").unwrap();
+        for &v in &values {
+            table.insert(vec![Value::Float(v)]).unwrap();
+        }
+
+        // 0.1 is below the precision of 1e15, so a naive running sum drifts badly
+        let expected = 1000.0 / values.len() as f64;
+        let naive = values.iter().sum::<f64>() / values.len() as f64;
+        assert!((naive - expected).abs() > 1e-3);
+
+        let res = db.query("SELECT AVG(x) FROM t").unwrap();
+        let Value::Float(avg) = res.rows[0][0] else {
+            panic!("Expected Float");
+        };
+        assert!(
+            (avg - expected).abs() < 1e-12,
+            "got {avg}, expected {expected}"
+        );
+    }
+
+    #[test]
+    fn test_avg_int_does_not_overflow() {
+        let mut db = Database::new();
+        db.execute("CREATE TABLE t (x INT)").unwrap();
+        db.execute(&format!("INSERT INTO t VALUES ({})", i64::MAX))
+            .unwrap();
+        db.execute(&format!("INSERT INTO t VALUES ({})", i64::MAX))
+            .unwrap();
+
+        let res = db.query("SELECT AVG(x) FROM t").unwrap();
+        assert_eq!(res.rows[0][0], Value::Float(i64::MAX as f64));
+    }
+
+    #[test]
+    fn test_aggregate_rejects_non_numeric_columns() {
+        let mut db = Database::new();
+        db.execute("CREATE TABLE t (at TIMESTAMP, name TEXT)")
+            .unwrap();
+        db.get_table_mut("t")
+            .unwrap()
+            .insert(vec![Value::Timestamp(1), Value::Text("a".into())])
+            .unwrap();
+
+        for sql in [
+            "SELECT SUM(at) FROM t",
+            "SELECT AVG(at) FROM t",
+            "SELECT SUM(name) FROM t",
+        ] {
+            let err = db.query(sql).unwrap_err();
+            assert!(err.contains("is not numeric"), "{sql}: {err}");
+        }
+    }
+
     #[test]
     fn test_multiple_aggregates_in_one_query() {
         let db = setup_employees();
@@ -1821,6 +8009,42 @@ mod tests {
         assert_eq!(res.rows[0][0], Value::Null);
     }
 
+    #[test]
+    fn test_aggregates_on_empty_table() {
+        let mut db = Database::new();
+        db.execute("CREATE TABLE t (id INT, u UINT, f FLOAT)")
+            .unwrap();
+
+        // COUNT is 0, every other aggregate is NULL but keeps its type
+        let res = db
+            .query("SELECT COUNT(*), COUNT(id), SUM(u), SUM(f), AVG(id), MIN(f), MAX(u) FROM t")
+            .unwrap();
+        assert_eq!(
+            res.rows,
+            vec![vec![
+                Value::Int(0),
+                Value::Int(0),
+                Value::Null,
+                Value::Null,
+                Value::Null,
+                Value::Null,
+                Value::Null,
+            ]]
+        );
+        assert_eq!(
+            res.column_types,
+            vec![
+                Some(DataType::Int),
+                Some(DataType::Int),
+                Some(DataType::UInt),
+                Some(DataType::Float),
+                Some(DataType::Float),
+                Some(DataType::Float),
+                Some(DataType::UInt),
+            ]
+        );
+    }
+
     #[test]
     fn test_count_star_on_empty_result() {
         let db = setup_employees();
@@ -1864,10 +8088,40 @@ mod tests {
             .unwrap();
         assert_eq!(res.columns, vec!["dept", "COUNT(*)"]);
         assert_eq!(res.rows.len(), 2);
-        assert_eq!(res.rows[0][0], Value::Text("HR".into()));
-        assert_eq!(res.rows[0][1], Value::Int(2));
-        assert_eq!(res.rows[1][0], Value::Text("Sales".into()));
-        assert_eq!(res.rows[1][1], Value::Int(3));
+        // groups come in first-seen order: Sales (Alice) before HR (Carol)
+        assert_eq!(res.rows[0][0], Value::Text("Sales".into()));
+        assert_eq!(res.rows[0][1], Value::Int(3));
+        assert_eq!(res.rows[1][0], Value::Text("HR".into()));
+        assert_eq!(res.rows[1][1], Value::Int(2));
+    }
+
+    #[test]
+    fn test_group_by_order_by_group_key() {
+        let db = setup_departments();
+        let res = db
+            .query("SELECT dept, COUNT(*) FROM emp GROUP BY dept ORDER BY dept ASC")
+            .unwrap();
+        assert_eq!(
+            res.rows,
+            vec![
+                vec![Value::Text("HR".into()), Value::Int(2)],
+                vec![Value::Text("Sales".into()), Value::Int(3)],
+            ]
+        );
+
+        let res = db
+            .query("SELECT dept, MAX(salary) FROM emp GROUP BY dept ORDER BY dept DESC LIMIT 1")
+            .unwrap();
+        assert_eq!(
+            res.rows,
+            vec![vec![Value::Text("Sales".into()), Value::Int(4000)]]
+        );
+
+        // the group rows are gone, only result columns can be sorted on
+        let err = db
+            .query("SELECT COUNT(*) FROM emp GROUP BY dept ORDER BY dept")
+            .unwrap_err();
+        assert!(err.contains("must name a selected column"), "{err}");
     }
 
     #[test]
@@ -1876,8 +8130,8 @@ mod tests {
         let res = db
             .query("SELECT dept, SUM(salary) FROM emp GROUP BY dept")
             .unwrap();
-        assert_eq!(res.rows[0][1], Value::Int(7000)); // HR:  2000+5000
-        assert_eq!(res.rows[1][1], Value::Int(10500)); // Sales: 3000+4000+3500
+        assert_eq!(res.rows[0][1], Value::Int(10500)); // Sales: 3000+4000+3500
+        assert_eq!(res.rows[1][1], Value::Int(7000)); // HR:  2000+5000
     }
 
     #[test]
@@ -1887,12 +8141,12 @@ mod tests {
             .query("SELECT dept, MIN(salary), MAX(salary) FROM emp GROUP BY dept")
             .unwrap();
         assert_eq!(res.columns, vec!["dept", "MIN(salary)", "MAX(salary)"]);
-        // HR
-        assert_eq!(res.rows[0][1], Value::Int(2000));
-        assert_eq!(res.rows[0][2], Value::Int(5000));
         // Sales
-        assert_eq!(res.rows[1][1], Value::Int(3000));
-        assert_eq!(res.rows[1][2], Value::Int(4000));
+        assert_eq!(res.rows[0][1], Value::Int(3000));
+        assert_eq!(res.rows[0][2], Value::Int(4000));
+        // HR
+        assert_eq!(res.rows[1][1], Value::Int(2000));
+        assert_eq!(res.rows[1][2], Value::Int(5000));
     }
 
     #[test]
@@ -1902,8 +8156,8 @@ mod tests {
             .query("SELECT dept, COUNT(*) FROM emp WHERE salary > 3000 GROUP BY dept")
             .unwrap();
         assert_eq!(res.rows.len(), 2);
-        assert_eq!(res.rows[0][1], Value::Int(1)); // HR
-        assert_eq!(res.rows[1][1], Value::Int(2)); // Sales
+        assert_eq!(res.rows[0][1], Value::Int(2)); // Sales
+        assert_eq!(res.rows[1][1], Value::Int(1)); // HR
     }
 
     #[test]
@@ -1930,6 +8184,211 @@ mod tests {
         assert!(db.query("SELECT dept, COUNT(*) FROM emp").is_err());
     }
 
+    fn setup_nullable_x() -> Database {
+        let mut db = Database::new();
+        db.execute("CREATE TABLE t (id INT, x INT)").unwrap();
+        db.execute("INSERT INTO t (id) VALUES (1)").unwrap();
+        db.execute("INSERT INTO t VALUES (2, 5)").unwrap();
+        db.execute("INSERT INTO t (id) VALUES (3)").unwrap();
+        db.execute("INSERT INTO t VALUES (4, 5)").unwrap();
+        db.execute("INSERT INTO t (id) VALUES (5)").unwrap();
+        db
+    }
+
+    #[test]
+    fn test_select_distinct() {
+        let mut db = Database::new();
+        db.execute("CREATE TABLE users (id INT, active BOOL, score FLOAT)")
+            .unwrap();
+        for (id, active, score) in [
+            (1, true, 1.5),
+            (2, false, 2.0),
+            (3, true, 1.5),
+            (4, true, 2.0),
+            (5, false, 2.0),
+        ] {
+            db.execute(&format!(
+                "INSERT INTO users VALUES ({id}, {active}, {score:?})"
+            ))
+            .unwrap();
+        }
+
+        // duplicates are dropped in first-seen order
+        let res = db.query("SELECT DISTINCT active FROM users").unwrap();
+        assert_eq!(
+            res.rows,
+            vec![vec![Value::Bool(true)], vec![Value::Bool(false)]]
+        );
+
+        // rows are compared as a whole, floats included
+        let res = db
+            .query("SELECT DISTINCT active, score FROM users")
+            .unwrap();
+        assert_eq!(
+            res.rows,
+            vec![
+                vec![Value::Bool(true), Value::Float(1.5)],
+                vec![Value::Bool(false), Value::Float(2.0)],
+                vec![Value::Bool(true), Value::Float(2.0)],
+            ]
+        );
+
+        // the window counts distinct rows
+        let res = db
+            .query("SELECT DISTINCT score FROM users LIMIT 1 OFFSET 1")
+            .unwrap();
+        assert_eq!(res.rows, vec![vec![Value::Float(2.0)]]);
+        assert_eq!(
+            db.query("SELECT DISTINCT * FROM users").unwrap().rows.len(),
+            5
+        );
+    }
+
+    #[test]
+    fn test_distinct_returns_null_once() {
+        let db = setup_nullable_x();
+        let res = db.query("SELECT DISTINCT x FROM t").unwrap();
+        assert_eq!(res.rows, vec![vec![Value::Null], vec![Value::Int(5)]]);
+
+        let res = db.query("SELECT DISTINCT x FROM t LIMIT 1").unwrap();
+        assert_eq!(res.rows, vec![vec![Value::Null]]);
+    }
+
+    #[test]
+    fn test_group_by_buckets_nulls_together() {
+        let db = setup_nullable_x();
+        let res = db.query("SELECT x, COUNT(*) FROM t GROUP BY x").unwrap();
+        assert_eq!(
+            res.rows,
+            vec![
+                vec![Value::Null, Value::Int(3)],
+                vec![Value::Int(5), Value::Int(2)],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_group_by_without_projecting_group_key() {
+        let db = setup_departments();
+        let res = db.query("SELECT COUNT(*) FROM emp GROUP BY dept").unwrap();
+        assert_eq!(res.columns, vec!["COUNT(*)"]);
+        // one count per group, Sales first as it is seen first
+        assert_eq!(res.rows, vec![vec![Value::Int(3)], vec![Value::Int(2)]]);
+
+        let res = db
+            .query("SELECT SUM(salary), MAX(salary) FROM emp GROUP BY dept")
+            .unwrap();
+        assert_eq!(
+            res.rows,
+            vec![
+                vec![Value::Int(10500), Value::Int(4000)],
+                vec![Value::Int(7000), Value::Int(5000)],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_having_filters_groups_below_threshold() {
+        let db = setup_departments();
+        let res = db
+            .query("SELECT dept, COUNT(*) FROM emp GROUP BY dept HAVING COUNT(*) > 2")
+            .unwrap();
+        assert_eq!(res.rows.len(), 1);
+        assert_eq!(res.rows[0][0], Value::Text("Sales".into()));
+        assert_eq!(res.rows[0][1], Value::Int(3));
+
+        // aggregate that is not projected, combined with a grouped column
+        let res = db
+            .query("SELECT dept FROM emp GROUP BY dept HAVING SUM(salary) > 5000 AND dept = 'HR'")
+            .unwrap();
+        assert_eq!(res.rows, vec![vec![Value::Text("HR".into())]]);
+    }
+
+    #[test]
+    fn test_having_aggregate_alias() {
+        let db = setup_departments();
+        let sales = || vec![vec![Value::Text("Sales".into()), Value::Int(3)]];
+
+        let res = db
+            .query("SELECT dept, COUNT(*) AS n FROM emp GROUP BY dept HAVING n > 2")
+            .unwrap();
+        assert_eq!(res.rows, sales());
+
+        let res = db
+            .query(
+                "SELECT dept, COUNT(*) AS n, SUM(salary) AS total FROM emp GROUP BY dept \
+                 HAVING NOT (total < 8000 OR n < 2)",
+            )
+            .unwrap();
+        assert_eq!(
+            res.rows,
+            vec![vec![
+                Value::Text("Sales".into()),
+                Value::Int(3),
+                Value::Int(10500)
+            ]]
+        );
+
+        // a table column of the same name is still the column
+        let err = db
+            .query("SELECT dept, COUNT(*) AS salary FROM emp GROUP BY dept HAVING salary > 2")
+            .unwrap_err();
+        assert!(err.contains("must appear in GROUP BY"), "{err}");
+
+        let err = db
+            .query(
+                "SELECT dept, COUNT(*) FILTER (WHERE salary > 3000) AS n FROM emp \
+                 GROUP BY dept HAVING n > 0",
+            )
+            .unwrap_err();
+        assert!(err.contains("filtered aggregate"), "{err}");
+
+        // BETWEEN, IN and IS NULL on an alias test the aggregate too
+        let grouped = |having: &str| {
+            first_column(
+                &db,
+                &format!(
+                    "SELECT dept, COUNT(*) AS n, MAX(salary) AS top FROM emp \
+                     GROUP BY dept HAVING {having}"
+                ),
+            )
+        };
+        assert_eq!(grouped("n BETWEEN 1 AND 2"), texts(&["HR"]));
+        assert_eq!(grouped("NOT (n BETWEEN 1 AND 2)"), texts(&["Sales"]));
+        assert_eq!(grouped("n IN (3, 4)"), texts(&["Sales"]));
+        assert_eq!(grouped("n NOT IN (3, 4)"), texts(&["HR"]));
+        assert_eq!(grouped("top IS NULL"), texts(&[]));
+        assert_eq!(grouped("top IS NOT NULL AND n > 2"), texts(&["Sales"]));
+
+        let err = db
+            .query("SELECT dept, MAX(salary) AS top FROM emp GROUP BY dept HAVING top LIKE '5%'")
+            .unwrap_err();
+        assert_eq!(err, "Alias top cannot be used in this HAVING condition");
+
+        let err = db
+            .query("SELECT dept, COUNT(*) AS n FROM emp WHERE n > 1 GROUP BY dept")
+            .unwrap_err();
+        assert_eq!(
+            err,
+            "Column `n` not found (aliases cannot be used in WHERE)"
+        );
+    }
+
+    #[test]
+    fn test_having_errors() {
+        let db = setup_departments();
+        // column neither grouped nor aggregated
+        assert!(
+            db.query("SELECT dept, COUNT(*) FROM emp GROUP BY dept HAVING salary > 1")
+                .is_err()
+        );
+        // aggregates are not allowed in WHERE
+        assert!(
+            db.query("SELECT dept, COUNT(*) FROM emp WHERE COUNT(*) > 1 GROUP BY dept")
+                .is_err()
+        );
+    }
+
     // --- Transactions ---
 
     fn setup_tx_db() -> Database {
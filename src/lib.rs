@@ -14,6 +14,12 @@ pub mod data_type;
 pub mod database;
 /// Logic for converting tokens into an executable AST.
 pub mod parser;
+/// Query plans describing how a `SELECT` is executed.
+pub mod plan;
+/// Random row sampling backing `TABLESAMPLE`.
+pub mod sample;
+/// Probabilistic sketches backing approximate aggregates.
+pub mod sketch;
 /// Table and Schema definitions and management logic.
 pub mod table;
 /// Lexical scanner that converts raw SQL strings into tokens.
@@ -27,5 +33,5 @@ pub mod value;
 pub use column::Column;
 pub use data_type::DataType;
 pub use database::Database;
-pub use table::{ColumnDef, Schema, Table};
+pub use table::{ColumnDef, ColumnDefault, Schema, SchemaBuilder, Table};
 pub use value::Value;
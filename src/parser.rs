@@ -4,7 +4,7 @@ use std::sync::Arc;
 use allocative::Allocative;
 
 use crate::tokenizer::Token;
-use crate::{ColumnDef, DataType, Value, ast::*};
+use crate::{ColumnDef, ColumnDefault, DataType, Value, ast::*};
 
 /// A recursive descent parser that transforms a sequence of [Token]s
 /// into an Abstract Syntax Tree (AST) represented by a [Statement].
@@ -37,11 +37,22 @@ impl<'a> Parser<'a> {
     pub fn parse(&mut self) -> Result<Statement<'a>, String> {
         let statement = match self.current_token() {
             Token::Create => self.parse_create_table(),
+            Token::Drop => self.parse_drop_table(),
+            Token::Alter => self.parse_alter_table(),
             Token::Insert => self.parse_insert(),
             Token::Select => self.parse_select(),
             Token::Delete => self.parse_delete(),
             Token::Update => self.parse_update(),
             Token::Vacuum => self.parse_vacuum(),
+            Token::Truncate => self.parse_truncate(),
+            Token::Set => self.parse_set(),
+            Token::Explain => {
+                self.advance();
+                match self.parse_select()? {
+                    Statement::Select(select) => Ok(Statement::Explain(select)),
+                    _ => unreachable!(),
+                }
+            }
             Token::Begin => {
                 self.advance();
                 Ok(Statement::Begin)
@@ -73,6 +84,32 @@ impl<'a> Parser<'a> {
         Ok(statement)
     }
 
+    /// Parses a standalone condition, as found after `WHERE`, up to the end of the input.
+    /// Used for the `CHECK` constraints stored as SQL in [ColumnDef::check].
+    pub fn parse_condition(&mut self) -> Result<Expr<'a>, String> {
+        let condition = self.parse_expression()?;
+        if !self.is_at_end() {
+            return Err(format!(
+                "Unexpected token after condition: {:?}",
+                self.current_token()
+            ));
+        }
+        Ok(condition)
+    }
+
+    /// Parses a standalone arithmetic expression up to the end of the input.
+    /// Used for the generated columns stored as SQL in [ColumnDef::generated].
+    pub fn parse_scalar(&mut self) -> Result<ScalarExpr<'a>, String> {
+        let expr = self.parse_scalar_expr()?;
+        if !self.is_at_end() {
+            return Err(format!(
+                "Unexpected token after expression: {:?}",
+                self.current_token()
+            ));
+        }
+        Ok(expr)
+    }
+
     // --- Navigation Helpers ---
 
     /// Returns a reference to the token at the current position.
@@ -129,6 +166,11 @@ impl<'a> Parser<'a> {
                 self.advance();
                 Ok(Value::Int(nb_copy))
             }
+            Token::UnsignedNumber(nb) => {
+                let nb_copy = *nb;
+                self.advance();
+                Ok(Value::UInt(nb_copy))
+            }
             Token::FloatNumber(nb) => {
                 let nb_copy = *nb;
                 self.advance();
@@ -147,6 +189,11 @@ impl<'a> Parser<'a> {
                 self.advance();
                 Ok(Value::Text(text))
             }
+            Token::Blob(bytes) => {
+                let blob: Arc<[u8]> = Arc::from(bytes.as_slice());
+                self.advance();
+                Ok(Value::Blob(blob))
+            }
             Token::Null => {
                 self.advance();
                 Ok(Value::Null)
@@ -162,6 +209,10 @@ impl<'a> Parser<'a> {
                 self.advance();
                 Ok(DataType::Int)
             }
+            Token::UInt => {
+                self.advance();
+                Ok(DataType::UInt)
+            }
             Token::Text => {
                 self.advance();
                 Ok(DataType::Text)
@@ -174,6 +225,14 @@ impl<'a> Parser<'a> {
                 self.advance();
                 Ok(DataType::Float)
             }
+            Token::Timestamp => {
+                self.advance();
+                Ok(DataType::Timestamp)
+            }
+            Token::Bytes => {
+                self.advance();
+                Ok(DataType::Bytes)
+            }
             _ => Err(format!(
                 "Current token {:?} is not a supported data type",
                 self.current_token()
@@ -183,25 +242,91 @@ impl<'a> Parser<'a> {
 
     // --- Production Rules ---
 
-    /// Parses a column definition in a `CREATE TABLE` statement (e.g., `id INT`).
+    /// Parses a column definition in a `CREATE TABLE` statement (e.g., `id INT`),
+    /// followed by its optional `AUTO_INCREMENT`, `NOT NULL`, `DEFAULT`, `CHECK` and
+    /// `GENERATED ALWAYS AS` constraints.
     fn parse_column_def(&mut self) -> Result<ColumnDef, String> {
         let name = self.consume_ident()?;
         let data_type = self.consume_data_type()?;
-        if self.current_token() == &Token::AutoIncrement {
-            if data_type != DataType::Int {
-                return Err("AUTO_INCREMENT can only be set for INT column".into());
+        let mut column = ColumnDef::new(name, data_type);
+        loop {
+            match self.current_token() {
+                Token::AutoIncrement => {
+                    if data_type != DataType::Int {
+                        return Err("AUTO_INCREMENT can only be set for INT column".into());
+                    }
+                    self.advance();
+                    column = column.auto_increment();
+                }
+                Token::Not => {
+                    self.advance();
+                    self.consume(Token::Null)?;
+                    column = column.not_null();
+                }
+                Token::Default => {
+                    self.advance();
+                    column = column.default(self.parse_column_default(data_type)?);
+                }
+                Token::Check => {
+                    self.advance();
+                    self.consume(Token::LeftParen)?;
+                    let condition = self.parse_expression()?;
+                    self.consume(Token::RightParen)?;
+                    column = column.check(condition.to_string());
+                }
+                Token::Generated => {
+                    self.advance();
+                    self.consume(Token::Always)?;
+                    self.consume(Token::As)?;
+                    self.consume(Token::LeftParen)?;
+                    let expr = self.parse_scalar_expr()?;
+                    self.consume(Token::RightParen)?;
+                    column = column.generated(expr.to_string());
+                }
+                _ => return Ok(column),
+            }
+        }
+    }
+
+    /// Parses the expression following `DEFAULT` in a column definition.
+    fn parse_column_default(&mut self, data_type: DataType) -> Result<ColumnDefault, String> {
+        match self.current_token() {
+            Token::CurrentTimestamp => {
+                if data_type != DataType::Timestamp {
+                    return Err(
+                        "CURRENT_TIMESTAMP can only be the default of a TIMESTAMP column".into(),
+                    );
+                }
+                self.advance();
+                Ok(ColumnDefault::CurrentTimestamp)
+            }
+            _ => {
+                let value = self.consume_value()?;
+                // the same widening as an inserted literal (`x FLOAT DEFAULT 0`)
+                let coerced = value.coerce_to(data_type).ok_or_else(|| {
+                    format!(
+                        "DEFAULT {:?} does not match the column type {:?}",
+                        value, data_type
+                    )
+                })?;
+                Ok(ColumnDefault::Value(coerced))
             }
-            self.advance();
-            return Ok(ColumnDef::new(name, data_type).auto_increment());
         }
-        Ok(ColumnDef::new(name, data_type))
     }
 
-    /// Parses a full `CREATE TABLE` statement.
+    /// Parses a full `CREATE TABLE` statement, either with explicit column definitions
+    /// or as `CREATE TABLE name AS SELECT ...`.
     fn parse_create_table(&mut self) -> Result<Statement<'a>, String> {
         self.consume(Token::Create)?;
         self.consume(Token::Table)?;
         let name = self.consume_ident()?;
+        if *self.current_token() == Token::As {
+            self.advance();
+            let Statement::Select(query) = self.parse_select()? else {
+                unreachable!()
+            };
+            return Ok(Statement::CreateTableAs(CreateTableAs { name, query }));
+        }
         self.consume(Token::LeftParen)?;
 
         let mut columns = vec![];
@@ -249,53 +374,116 @@ impl<'a> Parser<'a> {
         };
 
         self.consume(Token::Values)?;
-        self.consume(Token::LeftParen)?;
 
-        let mut values = vec![];
+        // VALUES (1, 'a'), (2, 'b'), ...: every tuple is one row
+        let mut values: Vec<Vec<Value>> = vec![];
         loop {
-            values.push(self.consume_value()?);
-            match self.current_token() {
-                Token::Comma => self.advance(),
-                Token::RightParen => {
-                    self.advance();
-                    break;
-                }
-                _ => return Err("Expected ',' or ')'".into()),
-            };
+            self.consume(Token::LeftParen)?;
+            let mut row = vec![];
+            loop {
+                row.push(self.consume_value()?);
+                match self.current_token() {
+                    Token::Comma => self.advance(),
+                    Token::RightParen => {
+                        self.advance();
+                        break;
+                    }
+                    _ => return Err("Expected ',' or ')'".into()),
+                };
+            }
+            if let Some(first) = values.first()
+                && first.len() != row.len()
+            {
+                return Err(format!(
+                    "VALUES row {} has {} values, expected {} like the first row",
+                    values.len() + 1,
+                    row.len(),
+                    first.len()
+                ));
+            }
+            values.push(row);
+            if *self.current_token() != Token::Comma {
+                break;
+            }
+            self.advance();
         }
 
+        let returning = self.parse_returning()?;
+
         Ok(Statement::InsertInto(InsertInto {
             table: name,
             columns,
             values,
+            returning,
         }))
     }
 
-    fn handle_count(&mut self) -> Result<SelectItem<'a>, String> {
+    /// Parses an optional `RETURNING` projection (`RETURNING *` or `RETURNING id, name`)
+    /// at the end of an `INSERT`, `UPDATE` or `DELETE`.
+    fn parse_returning(&mut self) -> Result<Option<ColumnsSelect<'a>>, String> {
+        if *self.current_token() != Token::Returning {
+            return Ok(None);
+        }
+        self.advance();
+        let columns = self.parse_columns()?;
+        if let ColumnsSelect::Items(items) = &columns
+            && items
+                .iter()
+                .any(|item| matches!(item, SelectItem::Aggregate { .. }))
+        {
+            return Err("Aggregates are not allowed in RETURNING".into());
+        }
+        Ok(Some(columns))
+    }
+
+    /// Parses an aggregate call such as `COUNT(*)`, `COUNT(col)`, `SUM(col)` or
+    /// `APPROX_COUNT_DISTINCT(col)`.
+    fn parse_aggregate(&mut self) -> Result<Aggregate<'a>, String> {
+        let token = self.current_token().clone();
         self.advance();
         self.consume(Token::LeftParen)?;
-        match self.current_token() {
-            Token::Star => {
-                self.consume(Token::Star)?;
-                self.consume(Token::RightParen)?;
-                Ok(SelectItem::Aggregate(Aggregate::CountStar))
-            }
-            Token::Ident(_) => {
-                let col = self.consume_ident()?;
-                self.consume(Token::RightParen)?;
-                Ok(SelectItem::Aggregate(Aggregate::Count(col)))
-            }
-            _ => Err(format!(
-                "Token {:?} not allowed in a COUNT",
-                self.current_token()
-            )),
+        if token == Token::Count && *self.current_token() == Token::Star {
+            self.advance();
+            self.consume(Token::RightParen)?;
+            return Ok(Aggregate::CountStar);
+        }
+        let col = self.consume_ident().map_err(|_| {
+            format!(
+                "Token {:?} not allowed in a {:?}",
+                self.current_token(),
+                token
+            )
+        })?;
+        self.consume(Token::RightParen)?;
+        match token {
+            Token::Count => Ok(Aggregate::Count(col)),
+            Token::Sum => Ok(Aggregate::Sum(col)),
+            Token::Min => Ok(Aggregate::Min(col)),
+            Token::Max => Ok(Aggregate::Max(col)),
+            Token::Avg => Ok(Aggregate::Avg(col)),
+            Token::ApproxCountDistinct => Ok(Aggregate::ApproxCountDistinct(col)),
+            _ => Err(format!("Token {:?} is not an aggregate", token)),
         }
     }
 
+    /// Parses an optional `FILTER (WHERE condition)` following an aggregate call.
+    fn parse_aggregate_filter(&mut self) -> Result<Option<Expr<'a>>, String> {
+        if *self.current_token() != Token::Filter {
+            return Ok(None);
+        }
+        self.advance();
+        self.consume(Token::LeftParen)?;
+        self.consume(Token::Where)?;
+        let condition = self.parse_expression()?;
+        self.consume(Token::RightParen)?;
+        Ok(Some(condition))
+    }
+
     /// Parses the column selection part of a `SELECT` statement (e.g., `*` or `col1, col2`).
     fn parse_columns(&mut self) -> Result<ColumnsSelect<'a>, String> {
         match self.current_token() {
-            Token::Star => {
+            // `*, ...` is parsed as a list of items, see SelectItem::Star
+            Token::Star if self.tokens.get(self.position + 1) != Some(&Token::Comma) => {
                 self.advance();
                 Ok(ColumnsSelect::Star)
             }
@@ -303,42 +491,40 @@ impl<'a> Parser<'a> {
                 let mut items = Vec::new();
                 loop {
                     match *self.current_token() {
-                        Token::Count => {
-                            items.push(self.handle_count()?);
-                        }
-                        Token::Sum => {
-                            self.advance();
-                            self.consume(Token::LeftParen)?;
-                            let col = self.consume_ident()?;
-                            self.consume(Token::RightParen)?;
-                            items.push(SelectItem::Aggregate(Aggregate::Sum(col)));
-                        }
-                        Token::Min => {
+                        Token::Star => {
                             self.advance();
-                            self.consume(Token::LeftParen)?;
-                            let col = self.consume_ident()?;
-                            self.consume(Token::RightParen)?;
-                            items.push(SelectItem::Aggregate(Aggregate::Min(col)));
+                            items.push(SelectItem::Star);
                         }
-                        Token::Max => {
-                            self.advance();
-                            self.consume(Token::LeftParen)?;
-                            let col = self.consume_ident()?;
-                            self.consume(Token::RightParen)?;
-                            items.push(SelectItem::Aggregate(Aggregate::Max(col)));
+                        Token::Count
+                        | Token::Sum
+                        | Token::Min
+                        | Token::Max
+                        | Token::Avg
+                        | Token::ApproxCountDistinct => {
+                            let function = self.parse_aggregate()?;
+                            let filter = self.parse_aggregate_filter()?;
+                            let alias = self.parse_item_alias()?;
+                            items.push(SelectItem::Aggregate {
+                                function,
+                                filter,
+                                alias,
+                            });
                         }
-                        Token::Avg => {
-                            self.advance();
-                            self.consume(Token::LeftParen)?;
-                            let col = self.consume_ident()?;
-                            self.consume(Token::RightParen)?;
-                            items.push(SelectItem::Aggregate(Aggregate::Avg(col)));
+                        Token::LeftParen
+                            if self.tokens.get(self.position + 1) == Some(&Token::Select) =>
+                        {
+                            let query = Box::new(self.parse_subquery()?);
+                            let alias = self.parse_item_alias()?;
+                            items.push(SelectItem::Subquery { query, alias });
                         }
-                        Token::Ident(name) => {
-                            items.push(SelectItem::Column(name));
-                            self.advance();
+                        _ => {
+                            let expr = self.parse_scalar_expr()?;
+                            let alias = self.parse_item_alias()?;
+                            items.push(match (expr, alias) {
+                                (ScalarExpr::Column(name), None) => SelectItem::Column(name),
+                                (expr, alias) => SelectItem::Computed { expr, alias },
+                            });
                         }
-                        _ => return Err("Expected aggregate, * or column name".into()),
                     }
 
                     if *self.current_token() == Token::Comma {
@@ -352,12 +538,87 @@ impl<'a> Parser<'a> {
         }
     }
 
+    /// Parses the optional `AS name` following a projection item.
+    fn parse_item_alias(&mut self) -> Result<Option<&'a str>, String> {
+        if *self.current_token() != Token::As {
+            return Ok(None);
+        }
+        self.advance();
+        self.consume_ident().map(Some)
+    }
+
+    /// Parses an additive scalar expression (`a + b - c`), the entry point for computed
+    /// projections. `*` and `/` bind tighter than `+` and `-`.
+    fn parse_scalar_expr(&mut self) -> Result<ScalarExpr<'a>, String> {
+        let mut expr = self.parse_scalar_term()?;
+        loop {
+            let op = match self.current_token() {
+                Token::Plus => ArithmeticOp::Add,
+                Token::Minus => ArithmeticOp::Sub,
+                _ => break,
+            };
+            self.advance();
+            let right = self.parse_scalar_term()?;
+            expr = ScalarExpr::Binary {
+                left: Box::new(expr),
+                op,
+                right: Box::new(right),
+            };
+        }
+        Ok(expr)
+    }
+
+    /// Parses a multiplicative scalar expression (`a * b / c`).
+    fn parse_scalar_term(&mut self) -> Result<ScalarExpr<'a>, String> {
+        let mut expr = self.parse_scalar_operand()?;
+        loop {
+            let op = match self.current_token() {
+                Token::Star => ArithmeticOp::Mul,
+                Token::Slash => ArithmeticOp::Div,
+                _ => break,
+            };
+            self.advance();
+            let right = self.parse_scalar_operand()?;
+            expr = ScalarExpr::Binary {
+                left: Box::new(expr),
+                op,
+                right: Box::new(right),
+            };
+        }
+        Ok(expr)
+    }
+
+    /// Parses a column name, a literal or a parenthesized scalar expression.
+    fn parse_scalar_operand(&mut self) -> Result<ScalarExpr<'a>, String> {
+        match self.current_token() {
+            Token::Ident(name) => {
+                let name = *name;
+                self.advance();
+                Ok(ScalarExpr::Column(name))
+            }
+            Token::LeftParen => {
+                self.advance();
+                let expr = self.parse_scalar_expr()?;
+                self.consume(Token::RightParen)?;
+                Ok(expr)
+            }
+            _ => Ok(ScalarExpr::Literal(self.consume_value()?)),
+        }
+    }
+
     /// Parses a `SELECT` statement.
     fn parse_select(&mut self) -> Result<Statement<'a>, String> {
         self.consume(Token::Select)?;
+        let distinct = *self.current_token() == Token::Distinct;
+        if distinct {
+            self.advance();
+        }
         let columns = self.parse_columns()?;
         self.consume(Token::From)?;
         let table = self.consume_ident()?;
+        let alias = self.parse_table_alias()?;
+        let sample = self.parse_table_sample()?;
+        let join = self.parse_join()?;
         let where_clause: Option<Expr> = {
             if *self.current_token() == Token::Where {
                 self.advance();
@@ -367,10 +628,16 @@ impl<'a> Parser<'a> {
             }
         };
 
+        let mut rollup = false;
         let group_by = {
             if *self.current_token() == Token::Group {
                 self.consume(Token::Group)?;
                 self.consume(Token::By)?;
+                if *self.current_token() == Token::Rollup {
+                    self.advance();
+                    self.consume(Token::LeftParen)?;
+                    rollup = true;
+                }
                 let mut cols = vec![];
                 loop {
                     cols.push(self.consume_ident()?);
@@ -378,11 +645,24 @@ impl<'a> Parser<'a> {
                         break;
                     }
                 }
+                if rollup {
+                    self.consume(Token::RightParen)?;
+                }
                 Some(cols)
             } else {
                 None
             }
         };
+
+        let having = {
+            if *self.current_token() == Token::Having {
+                self.advance();
+                Some(self.parse_expression()?)
+            } else {
+                None
+            }
+        };
+
         let order_by = {
             if *self.current_token() == Token::Order {
                 self.advance();
@@ -393,42 +673,211 @@ impl<'a> Parser<'a> {
             }
         };
 
+        // MySQL's `LIMIT offset, count` gives the offset before the count
+        let mut comma_offset = None;
         let limit = {
             if *self.current_token() == Token::Limit {
                 self.advance();
-                match self.current_token() {
-                    Token::Number(n) => {
-                        let limit = *n as usize; // i64 → usize
-                        self.advance();
-                        Some(limit)
-                    }
-                    _ => return Err("LIMIT requires a number".into()),
+                let mut limit = self.consume_count("LIMIT")?;
+                if *self.current_token() == Token::Comma {
+                    self.advance();
+                    comma_offset = Some(limit);
+                    limit = self.consume_count("LIMIT offset, count")?;
                 }
+                Some(limit)
+            } else if *self.current_token() == Token::Fetch {
+                Some(self.parse_fetch_first()?)
             } else {
                 None
             }
         };
 
+        let offset = if comma_offset.is_some() {
+            if *self.current_token() == Token::Offset {
+                return Err("OFFSET cannot be combined with LIMIT offset, count".into());
+            }
+            comma_offset
+        } else if *self.current_token() == Token::Offset {
+            self.advance();
+            Some(self.consume_count("OFFSET")?)
+        } else {
+            None
+        };
+
         Ok(Statement::Select(Select {
+            distinct,
             columns,
             table,
+            alias,
+            sample,
+            join,
             where_clause,
             limit,
+            offset,
             order_by,
             group_by,
+            rollup,
+            having,
+        }))
+    }
+
+    /// Parses an optional table alias, written `AS u` or just `u`.
+    fn parse_table_alias(&mut self) -> Result<Option<&'a str>, String> {
+        match self.current_token() {
+            Token::As => {
+                self.advance();
+                self.consume_ident().map(Some)
+            }
+            Token::Ident(_) => self.consume_ident().map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    /// Parses an optional `TABLESAMPLE (p PERCENT) [REPEATABLE (seed)]` clause.
+    fn parse_table_sample(&mut self) -> Result<Option<TableSample>, String> {
+        if *self.current_token() != Token::Tablesample {
+            return Ok(None);
+        }
+        self.advance();
+        self.consume(Token::LeftParen)?;
+        let percent = match self.consume_value()? {
+            Value::Int(p) => p as f64,
+            Value::Float(p) => p,
+            value => return Err(format!("TABLESAMPLE expects a percentage, found {value:?}")),
+        };
+        if !(0.0..=100.0).contains(&percent) {
+            return Err(format!(
+                "TABLESAMPLE percentage must be between 0 and 100, got {percent}"
+            ));
+        }
+        self.consume(Token::Percent)?;
+        self.consume(Token::RightParen)?;
+
+        let seed = if *self.current_token() == Token::Repeatable {
+            self.advance();
+            self.consume(Token::LeftParen)?;
+            let seed = match self.consume_value()? {
+                Value::Int(seed) if seed >= 0 => seed as u64,
+                Value::UInt(seed) => seed,
+                value => {
+                    return Err(format!(
+                        "REPEATABLE expects a non-negative seed, found {value:?}"
+                    ));
+                }
+            };
+            self.consume(Token::RightParen)?;
+            Some(seed)
+        } else {
+            None
+        };
+        Ok(Some(TableSample { percent, seed }))
+    }
+
+    /// Parses an optional `[INNER | LEFT [OUTER]] JOIN table ON a = b` clause.
+    fn parse_join(&mut self) -> Result<Option<Join<'a>>, String> {
+        let kind = match self.current_token() {
+            Token::Join => JoinKind::Inner,
+            Token::Inner => {
+                self.advance();
+                JoinKind::Inner
+            }
+            Token::Left => {
+                self.advance();
+                if *self.current_token() == Token::Outer {
+                    self.advance();
+                }
+                JoinKind::Left
+            }
+            _ => return Ok(None),
+        };
+        self.consume(Token::Join)?;
+        let table = self.consume_ident()?;
+        let alias = self.parse_table_alias()?;
+        self.consume(Token::On)?;
+        let left = self.consume_ident()?;
+        if *self.current_token() != Token::Equal {
+            return Err(format!(
+                "JOIN ... ON only supports an equality between two columns, found {:?}",
+                self.current_token()
+            ));
+        }
+        self.advance();
+        let right = self.consume_ident()?;
+        Ok(Some(Join {
+            kind,
+            table,
+            alias,
+            on: (left, right),
         }))
     }
 
+    /// Reads the row count of a `LIMIT`, `OFFSET` or `FETCH FIRST` clause, named by
+    /// `clause` in errors.
+    ///
+    /// # Errors
+    /// Returns an error if the current token is not an integer, or is negative.
+    fn consume_count(&mut self, clause: &str) -> Result<usize, String> {
+        match *self.current_token() {
+            Token::Number(n) if n >= 0 => {
+                self.advance();
+                Ok(n as usize)
+            }
+            Token::Number(n) => Err(format!("{} cannot be negative, got {}", clause, n)),
+            _ => Err(format!("{} requires a number", clause)),
+        }
+    }
+
+    /// Parses the ANSI `FETCH FIRST [n] ROW[S] ONLY` clause, an alias for `LIMIT n`.
+    ///
+    /// Without a number, a single row is fetched.
+    fn parse_fetch_first(&mut self) -> Result<usize, String> {
+        self.consume(Token::Fetch)?;
+        self.consume(Token::First)?;
+        let count = match self.current_token() {
+            Token::Number(_) => self.consume_count("FETCH FIRST")?,
+            _ => 1,
+        };
+        match self.current_token() {
+            Token::Row | Token::Rows => self.advance(),
+            other => {
+                return Err(format!(
+                    "Expected ROW or ROWS in FETCH FIRST, found {:?}",
+                    other
+                ));
+            }
+        }
+        self.consume(Token::Only)?;
+        Ok(count)
+    }
+
     /// Parses a DELETE statement.
+    ///
+    /// The `FROM` keyword and the `WHERE` clause are both mandatory, so a mistyped
+    /// statement can never wipe a table.
     fn parse_delete(&mut self) -> Result<Statement<'a>, String> {
         self.consume(Token::Delete)?;
-        self.consume(Token::From)?;
+        if *self.current_token() != Token::From {
+            return Err(format!(
+                "Expected FROM after DELETE (DELETE FROM <table> WHERE ...), found {:?}",
+                self.current_token()
+            ));
+        }
+        self.advance();
         let table = self.consume_ident()?;
         self.consume(Token::Where)?;
         let where_clause = self.parse_expression()?;
+        let limit = if *self.current_token() == Token::Limit {
+            self.advance();
+            Some(self.consume_count("LIMIT")?)
+        } else {
+            None
+        };
+        let returning = self.parse_returning()?;
         Ok(Statement::Delete(Delete {
             table,
             where_clause,
+            limit,
+            returning,
         }))
     }
 
@@ -441,7 +890,7 @@ impl<'a> Parser<'a> {
         loop {
             let col = self.consume_ident()?;
             self.consume(Token::Equal)?;
-            let value = self.consume_value()?;
+            let value = self.parse_scalar_expr()?;
             // as if there is some columns twice in the order, SQL parsing must only understand the
             // last one.
             values.insert(col, value);
@@ -454,10 +903,12 @@ impl<'a> Parser<'a> {
         }
         self.consume(Token::Where)?;
         let where_clause = self.parse_expression()?;
+        let returning = self.parse_returning()?;
         Ok(Statement::Update(Update {
             table,
             assignments: values,
             where_clause,
+            returning,
         }))
     }
 
@@ -493,16 +944,16 @@ impl<'a> Parser<'a> {
     /// Parses AND expressions with left-associativity.
     ///
     /// Handles chains of AND operations, building a left-associative tree.
-    /// AND has higher precedence than OR but lower than comparisons.
+    /// AND has higher precedence than OR but lower than NOT.
     ///
     /// # Examples
     /// - `age > 18 AND active = TRUE`
     /// - `a = 1 AND b = 2 AND c = 3` → `((a=1) AND (b=2)) AND (c=3)`
     fn parse_and_expr(&mut self) -> Result<Expr<'a>, String> {
-        let mut expr = self.parse_op_expr()?;
+        let mut expr = self.parse_not_expr()?;
         while matches!(self.current_token(), Token::And) {
             self.advance();
-            let right_expr = self.parse_op_expr()?;
+            let right_expr = self.parse_not_expr()?;
             expr = Expr::And {
                 left: Box::new(expr),
                 right: Box::new(right_expr),
@@ -511,42 +962,233 @@ impl<'a> Parser<'a> {
         Ok(expr)
     }
 
+    /// Parses a NOT expression, which binds tighter than AND but looser than comparisons:
+    /// `NOT a = 1 AND b = 2` is `(NOT a = 1) AND b = 2`.
+    ///
+    /// NOT applies to a comparison, to another NOT (`NOT NOT active`), or to a
+    /// parenthesized condition (`NOT (a = 1 OR b = 2)`).
+    fn parse_not_expr(&mut self) -> Result<Expr<'a>, String> {
+        if !matches!(self.current_token(), Token::Not) {
+            return self.parse_op_expr();
+        }
+        self.advance();
+        Ok(Expr::Not(Box::new(self.parse_not_expr()?)))
+    }
+
     /// Parses a comparison expression (the atomic unit of WHERE clauses).
     ///
-    /// Expects the pattern: `column OPERATOR value`
+    /// Expects the pattern: `column OPERATOR value`, or a standalone `TRUE`/`FALSE`
+    /// literal which is kept as an [Expr::Literal]. An aggregate call may replace the
+    /// column (`COUNT(*) > 2`), which is only accepted by `HAVING` at execution time.
+    ///
+    /// A bare column (`active`) is shorthand for `active = TRUE`; execution rejects it
+    /// unless the column is a `BOOL`.
+    ///
+    /// A condition in parentheses is parsed whole, so it overrides the precedence of AND
+    /// over OR: `(a = 1 OR b = 2) AND c = 3`.
+    ///
+    /// `column BETWEEN low AND high` takes scalar expressions as bounds, so they can be
+    /// literals or other columns of the row. `column IS NULL` and `column IS NOT NULL`
+    /// test for `NULL`, `column [NOT] IN (v1, v2, ...)` for list membership and
+    /// `column [NOT] LIKE 'pattern'` for pattern matching.
     ///
     /// # Supported Operators
     /// - `>` (Greater Than)
     /// - `<` (Less Than)
+    /// - `>=` (Greater Than or Equal)
+    /// - `<=` (Less Than or Equal)
     /// - `=` (Equal)
+    /// - `!=` or `<>` (Not Equal)
+    /// - `<=>` (NULL-safe Equal)
     ///
     /// # Examples
     /// - `age > 18`
     /// - `name = 'Alice'`
     /// - `active = TRUE`
+    /// - `TRUE`
+    /// - `active`
+    /// - `x BETWEEN low AND high`
+    /// - `(a = 1 OR b = 2)`
+    /// - `email IS NOT NULL`
+    /// - `id NOT IN (1, 2, 3)`
+    /// - `name LIKE 'Al%'`
+    /// - `total > (SELECT AVG(total) FROM orders)`
     fn parse_op_expr(&mut self) -> Result<Expr<'a>, String> {
-        let column = self.consume_ident()?;
-        let op = match self.current_token() {
-            Token::Lower => ComparisonOp::Lt,
-            Token::Greater => ComparisonOp::Gt,
-            Token::Equal => ComparisonOp::Eq,
-            _ => {
-                return Err(format!(
-                    "Token {:?} is not a valid operator",
-                    self.current_token()
-                ));
+        match self.current_token() {
+            Token::LeftParen => {
+                self.advance();
+                let expr = self.parse_expression()?;
+                self.consume(Token::RightParen)?;
+                return Ok(expr);
             }
-        };
-        self.advance();
-        let value = self.consume_value()?;
-        Ok(Expr::Comparison { column, op, value })
-    }
+            Token::True => {
+                self.advance();
+                return Ok(Expr::Literal(true));
+            }
+            Token::False => {
+                self.advance();
+                return Ok(Expr::Literal(false));
+            }
+            Token::Count
+            | Token::Sum
+            | Token::Min
+            | Token::Max
+            | Token::Avg
+            | Token::ApproxCountDistinct => {
+                let aggregate = self.parse_aggregate()?;
+                let op = self.parse_comparison_op()?;
+                let value = self.consume_value()?;
+                return Ok(Expr::AggregateComparison {
+                    aggregate,
+                    op,
+                    value,
+                });
+            }
+            _ => {}
+        }
+        let column = self.consume_ident()?;
+        if matches!(
+            self.current_token(),
+            Token::And
+                | Token::Or
+                | Token::Group
+                | Token::Having
+                | Token::Order
+                | Token::Limit
+                | Token::Offset
+                | Token::Fetch
+                | Token::Returning
+                | Token::RightParen
+                | Token::Semicolon
+                | Token::Eof
+        ) {
+            return Ok(Expr::Comparison {
+                column,
+                op: ComparisonOp::Eq,
+                value: Value::Bool(true),
+            });
+        }
+        if matches!(self.current_token(), Token::Is) {
+            self.advance();
+            let negated = matches!(self.current_token(), Token::Not);
+            if negated {
+                self.advance();
+            }
+            self.consume(Token::Null)?;
+            return Ok(Expr::IsNull { column, negated });
+        }
+        let negated = matches!(self.current_token(), Token::Not);
+        if negated {
+            self.advance();
+        }
+        match self.current_token() {
+            Token::In => {
+                self.advance();
+                let values = self.parse_value_list()?;
+                return Ok(Expr::InList {
+                    column,
+                    values,
+                    negated,
+                });
+            }
+            Token::Like => {
+                self.advance();
+                let Token::String(pattern) = self.current_token() else {
+                    return Err(format!(
+                        "LIKE expects a string pattern, found {:?}",
+                        self.current_token()
+                    ));
+                };
+                let pattern = pattern.to_string();
+                self.advance();
+                return Ok(Expr::Like {
+                    column,
+                    pattern,
+                    negated,
+                });
+            }
+            token if negated => {
+                return Err(format!("Expected IN or LIKE after NOT, found {:?}", token));
+            }
+            _ => {}
+        }
+        if matches!(self.current_token(), Token::Between) {
+            self.advance();
+            let low = self.parse_scalar_expr()?;
+            self.consume(Token::And)?;
+            let high = self.parse_scalar_expr()?;
+            return Ok(Expr::Between { column, low, high });
+        }
+        let op = self.parse_comparison_op()?;
+        if *self.current_token() == Token::LeftParen
+            && self.tokens.get(self.position + 1) == Some(&Token::Select)
+        {
+            let query = Box::new(self.parse_subquery()?);
+            return Ok(Expr::Subquery { column, op, query });
+        }
+        if let Token::Ident(right) = *self.current_token() {
+            self.advance();
+            return Ok(Expr::ColumnComparison {
+                left: column,
+                op,
+                right,
+            });
+        }
+        let value = self.consume_value()?;
+        Ok(Expr::Comparison { column, op, value })
+    }
+
+    /// Parses the parenthesized, non-empty list of values of `IN (1, 2, 3)`.
+    fn parse_value_list(&mut self) -> Result<Vec<Value>, String> {
+        self.consume(Token::LeftParen)?;
+        if *self.current_token() == Token::RightParen {
+            return Err("IN requires at least one value".into());
+        }
+        let mut values = vec![self.consume_value()?];
+        while *self.current_token() == Token::Comma {
+            self.advance();
+            values.push(self.consume_value()?);
+        }
+        self.consume(Token::RightParen)?;
+        Ok(values)
+    }
+
+    /// Parses a parenthesized `SELECT` used as a value (`(SELECT MAX(n) FROM t)`).
+    fn parse_subquery(&mut self) -> Result<Select<'a>, String> {
+        self.consume(Token::LeftParen)?;
+        let Statement::Select(query) = self.parse_select()? else {
+            unreachable!()
+        };
+        self.consume(Token::RightParen)?;
+        Ok(query)
+    }
+
+    /// Parses a comparison operator (`>`, `<`, `>=`, `<=`, `=`, `!=`/`<>` or `<=>`).
+    fn parse_comparison_op(&mut self) -> Result<ComparisonOp, String> {
+        let op = match self.current_token() {
+            Token::Lower => ComparisonOp::Lt,
+            Token::Greater => ComparisonOp::Gt,
+            Token::LowerEqual => ComparisonOp::Lte,
+            Token::GreaterEqual => ComparisonOp::Gte,
+            Token::Equal => ComparisonOp::Eq,
+            Token::NotEqual => ComparisonOp::Neq,
+            Token::NullSafeEqual => ComparisonOp::NullSafeEq,
+            _ => {
+                return Err(format!(
+                    "Token {:?} is not a valid operator",
+                    self.current_token()
+                ));
+            }
+        };
+        self.advance();
+        Ok(op)
+    }
 
     /// Parses an `ORDER BY` clause.
     ///
     /// # Default Behavior
     /// If no direction (`ASC` or `DESC`) is specified, defaults to `ASC`
-    /// per SQL standard.
+    /// per SQL standard. Each clause may end with `NULLS FIRST` or `NULLS LAST`.
     ///
     /// # Returns
     /// A vector of [OrderByClause] in the order they should be applied.
@@ -555,7 +1197,7 @@ impl<'a> Parser<'a> {
         let mut clauses = vec![];
 
         loop {
-            let column = self.consume_ident()?;
+            let expr = self.parse_scalar_expr()?;
 
             let direction = match self.current_token() {
                 Token::Asc => {
@@ -569,7 +1211,29 @@ impl<'a> Parser<'a> {
                 _ => SortDirection::Asc, // ASC by default
             };
 
-            clauses.push(OrderByClause { column, direction });
+            let nulls = if *self.current_token() == Token::Nulls {
+                self.advance();
+                match self.current_token() {
+                    Token::First => Some(NullsOrder::First),
+                    Token::Last => Some(NullsOrder::Last),
+                    token => {
+                        return Err(format!(
+                            "Expected FIRST or LAST after NULLS, found {token:?}"
+                        ));
+                    }
+                }
+            } else {
+                None
+            };
+            if nulls.is_some() {
+                self.advance();
+            }
+
+            clauses.push(OrderByClause {
+                expr,
+                direction,
+                nulls,
+            });
 
             if matches!(self.current_token(), Token::Comma) {
                 self.advance();
@@ -581,6 +1245,60 @@ impl<'a> Parser<'a> {
         Ok(clauses)
     }
 
+    /// Parses a `SET name = value` statement.
+    fn parse_set(&mut self) -> Result<Statement<'a>, String> {
+        self.consume(Token::Set)?;
+        let name = self.consume_ident()?;
+        self.consume(Token::Equal)?;
+        let value = self.consume_value()?;
+        Ok(Statement::Set(SetVariable { name, value }))
+    }
+
+    /// Parses a `DROP TABLE [IF EXISTS] name` statement.
+    fn parse_drop_table(&mut self) -> Result<Statement<'a>, String> {
+        self.consume(Token::Drop)?;
+        self.consume(Token::Table)?;
+        let if_exists = *self.current_token() == Token::If;
+        if if_exists {
+            self.advance();
+            self.consume(Token::Exists)?;
+        }
+        let name = self.consume_ident()?;
+        Ok(Statement::DropTable(DropTable { name, if_exists }))
+    }
+
+    /// Parses an `ALTER TABLE name DROP [COLUMN] column` statement.
+    fn parse_alter_table(&mut self) -> Result<Statement<'a>, String> {
+        self.consume(Token::Alter)?;
+        self.consume(Token::Table)?;
+        let table = self.consume_ident()?;
+        let action = match self.current_token() {
+            Token::Drop => {
+                self.advance();
+                if *self.current_token() == Token::Column {
+                    self.advance();
+                }
+                AlterAction::DropColumn(self.consume_ident()?)
+            }
+            other => {
+                return Err(format!(
+                    "Expected DROP COLUMN after ALTER TABLE {}, found {:?}",
+                    table, other
+                ));
+            }
+        };
+        Ok(Statement::AlterTable(AlterTable { table, action }))
+    }
+
+    /// Parses a `TRUNCATE [TABLE] name` statement.
+    fn parse_truncate(&mut self) -> Result<Statement<'a>, String> {
+        self.consume(Token::Truncate)?;
+        if *self.current_token() == Token::Table {
+            self.advance();
+        }
+        Ok(Statement::Truncate(self.consume_ident()?))
+    }
+
     /// Parses a `VACUUM` statement.
     ///
     /// If a string is given, exec the vacuum inside this specific table, else, do it in all
@@ -624,6 +1342,24 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_create_table_as_select() {
+        let statement = parse_sql("CREATE TABLE adults AS SELECT name FROM users WHERE age > 17");
+
+        match statement {
+            Statement::CreateTableAs(ct) => {
+                assert_eq!(ct.name, "adults");
+                assert_eq!(ct.query.table, "users");
+                assert_eq!(
+                    ct.query.columns,
+                    ColumnsSelect::Items(vec![SelectItem::Column("name")])
+                );
+                assert!(ct.query.where_clause.is_some());
+            }
+            _ => panic!("Expected CreateTableAs"),
+        }
+    }
+
     #[test]
     fn test_parse_insert() {
         let sql = "INSERT INTO users (id, name) VALUES (1, 'Alice')";
@@ -636,15 +1372,50 @@ mod tests {
         match statement {
             Statement::InsertInto(ins) => {
                 assert_eq!(ins.table, "users");
-                assert_eq!(ins.columns, Some(vec!["id".into(), "name".into()]));
-                assert_eq!(ins.values.len(), 2);
-                assert_eq!(ins.values[0], Value::Int(1));
-                assert_eq!(ins.values[1], Value::Text(Arc::from("Alice")));
+                assert_eq!(ins.columns, Some(vec!["id", "name"]));
+                assert_eq!(
+                    ins.values,
+                    vec![vec![Value::Int(1), Value::Text(Arc::from("Alice"))]]
+                );
             }
             _ => panic!("Expected InsertInto"),
         }
     }
 
+    #[test]
+    fn test_parse_insert_multiple_rows() {
+        let Statement::InsertInto(ins) =
+            parse_sql("INSERT INTO users (id, name) VALUES (1, 'a'), (2, NULL), (3, 'c')")
+        else {
+            panic!("Expected InsertInto");
+        };
+        assert_eq!(
+            ins.values,
+            vec![
+                vec![Value::Int(1), Value::Text(Arc::from("a"))],
+                vec![Value::Int(2), Value::Null],
+                vec![Value::Int(3), Value::Text(Arc::from("c"))],
+            ]
+        );
+
+        let tokens = Tokenizer::new("INSERT INTO users VALUES (1, 'a'), (2)")
+            .tokenize()
+            .unwrap();
+        let err = Parser::new(tokens).parse().unwrap_err();
+        assert_eq!(
+            err,
+            "VALUES row 2 has 1 values, expected 2 like the first row"
+        );
+
+        for sql in [
+            "INSERT INTO users VALUES (1, 'a'),",
+            "INSERT INTO users VALUES (1, 'a') (2, 'b')",
+        ] {
+            let tokens = Tokenizer::new(sql).tokenize().unwrap();
+            assert!(Parser::new(tokens).parse().is_err(), "{sql}");
+        }
+    }
+
     #[test]
     fn test_parse_insert_no_columns() {
         let sql = "INSERT INTO users VALUES (1, 'Alice')";
@@ -658,9 +1429,10 @@ mod tests {
             Statement::InsertInto(ins) => {
                 assert_eq!(ins.table, "users");
                 assert_eq!(ins.columns, None);
-                assert_eq!(ins.values.len(), 2);
-                assert_eq!(ins.values[0], Value::Int(1));
-                assert_eq!(ins.values[1], Value::Text(Arc::from("Alice")));
+                assert_eq!(
+                    ins.values,
+                    vec![vec![Value::Int(1), Value::Text(Arc::from("Alice"))]]
+                );
             }
             _ => panic!("Expected InsertInto"),
         }
@@ -695,10 +1467,8 @@ mod tests {
 
         match statement {
             Statement::Select(sel) => {
-                let columns: Vec<SelectItem> = vec![
-                    SelectItem::Column("name".into()),
-                    SelectItem::Column("age".into()),
-                ];
+                let columns: Vec<SelectItem> =
+                    vec![SelectItem::Column("name"), SelectItem::Column("age")];
                 assert_eq!(sel.columns, ColumnsSelect::Items(columns));
                 assert_eq!(sel.table, "users".to_string());
             }
@@ -706,6 +1476,23 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_select_distinct() {
+        let Statement::Select(sel) = parse_sql("SELECT DISTINCT active FROM users") else {
+            panic!("Expected Select");
+        };
+        assert!(sel.distinct);
+        assert_eq!(
+            sel.columns,
+            ColumnsSelect::Items(vec![SelectItem::Column("active")])
+        );
+
+        let Statement::Select(sel) = parse_sql("SELECT active FROM users") else {
+            panic!("Expected Select");
+        };
+        assert!(!sel.distinct);
+    }
+
     #[test]
     fn test_parse_select_with_where() {
         let sql = "SELECT * FROM users WHERE age > 18";
@@ -762,7 +1549,7 @@ mod tests {
             Statement::Select(sel) => {
                 let order_by = sel.order_by.unwrap();
                 assert_eq!(order_by.len(), 1);
-                assert_eq!(order_by[0].column, "age");
+                assert_eq!(order_by[0].expr, ScalarExpr::Column("age"));
                 assert_eq!(order_by[0].direction, SortDirection::Asc);
             }
             _ => panic!("Expected Select"),
@@ -783,16 +1570,139 @@ mod tests {
                 let order_by = sel.order_by.unwrap();
                 assert_eq!(order_by.len(), 2);
 
-                assert_eq!(order_by[0].column, "age");
+                assert_eq!(order_by[0].expr, ScalarExpr::Column("age"));
                 assert_eq!(order_by[0].direction, SortDirection::Asc);
 
-                assert_eq!(order_by[1].column, "name");
+                assert_eq!(order_by[1].expr, ScalarExpr::Column("name"));
                 assert_eq!(order_by[1].direction, SortDirection::Desc);
             }
             _ => panic!("Expected Select"),
         }
     }
 
+    #[test]
+    fn test_parse_order_by_nulls() {
+        let Statement::Select(sel) =
+            parse_sql("SELECT a FROM t ORDER BY a DESC NULLS FIRST, b NULLS LAST, c")
+        else {
+            panic!("Expected Select");
+        };
+        let order_by = sel.order_by.unwrap();
+        let nulls: Vec<_> = order_by.iter().map(|clause| clause.nulls).collect();
+        assert_eq!(
+            nulls,
+            [Some(NullsOrder::First), Some(NullsOrder::Last), None]
+        );
+        assert!(order_by[0].nulls_first());
+        assert!(!order_by[1].nulls_first());
+        assert!(order_by[2].nulls_first());
+
+        let tokens = Tokenizer::new("SELECT a FROM t ORDER BY a NULLS")
+            .tokenize()
+            .unwrap();
+        assert!(Parser::new(tokens).parse().is_err());
+    }
+
+    #[test]
+    fn test_parse_aggregates() {
+        let Statement::Select(sel) = parse_sql(
+            "SELECT COUNT(*), COUNT(email), SUM(price), AVG(price), MIN(price), MAX(price) FROM t",
+        ) else {
+            panic!("Expected Select");
+        };
+        let ColumnsSelect::Items(items) = sel.columns else {
+            panic!("Expected items");
+        };
+        let functions: Vec<_> = items
+            .into_iter()
+            .map(|item| match item {
+                SelectItem::Aggregate { function, .. } => function,
+                other => panic!("Expected an aggregate, got {other:?}"),
+            })
+            .collect();
+        assert_eq!(
+            functions,
+            vec![
+                Aggregate::CountStar,
+                Aggregate::Count("email"),
+                Aggregate::Sum("price"),
+                Aggregate::Avg("price"),
+                Aggregate::Min("price"),
+                Aggregate::Max("price"),
+            ]
+        );
+
+        for sql in [
+            "SELECT SUM(*) FROM t",
+            "SELECT COUNT() FROM t",
+            "SELECT MAX(price FROM t",
+            "SELECT AVG(price + 1) FROM t",
+        ] {
+            let tokens = Tokenizer::new(sql).tokenize().unwrap();
+            assert!(Parser::new(tokens).parse().is_err(), "{sql}");
+        }
+    }
+
+    #[test]
+    fn test_parse_aggregate_filter() {
+        let Statement::Select(sel) =
+            parse_sql("SELECT COUNT(*) FILTER (WHERE active = TRUE), SUM(n) FROM t")
+        else {
+            panic!("Expected Select");
+        };
+        assert_eq!(
+            sel.columns,
+            ColumnsSelect::Items(vec![
+                SelectItem::Aggregate {
+                    function: Aggregate::CountStar,
+                    filter: Some(Expr::Comparison {
+                        column: "active",
+                        op: ComparisonOp::Eq,
+                        value: Value::Bool(true),
+                    }),
+                    alias: None,
+                },
+                SelectItem::Aggregate {
+                    function: Aggregate::Sum("n"),
+                    filter: None,
+                    alias: None,
+                },
+            ])
+        );
+
+        let tokens = Tokenizer::new("SELECT COUNT(*) FILTER (active = TRUE) FROM t")
+            .tokenize()
+            .unwrap();
+        assert!(Parser::new(tokens).parse().is_err());
+    }
+
+    #[test]
+    fn test_parse_returning() {
+        let Statement::InsertInto(insert) =
+            parse_sql("INSERT INTO users (name) VALUES ('Alice') RETURNING id, name")
+        else {
+            panic!("Expected InsertInto");
+        };
+        assert_eq!(
+            insert.returning,
+            Some(ColumnsSelect::Items(vec![
+                SelectItem::Column("id"),
+                SelectItem::Column("name")
+            ]))
+        );
+
+        let Statement::Delete(delete) = parse_sql("DELETE FROM users WHERE active RETURNING *")
+        else {
+            panic!("Expected Delete");
+        };
+        assert_eq!(delete.returning, Some(ColumnsSelect::Star));
+
+        let tokens = Tokenizer::new("UPDATE users SET age = 1 WHERE id = 1 RETURNING COUNT(*)")
+            .tokenize()
+            .unwrap();
+        assert!(Parser::new(tokens).parse().is_err());
+    }
+
     #[test]
     fn test_parse_delete_simple_where() {
         let sql = "DELETE FROM users WHERE age > 12";
@@ -809,11 +1719,47 @@ mod tests {
                 op: ComparisonOp::Gt,
                 value: Value::Int(12),
             },
+            limit: None,
+            returning: None,
         });
 
         assert_eq!(statement, expected);
     }
 
+    #[test]
+    fn test_parse_delete_not_equal() {
+        for sql in ["DELETE FROM t WHERE id != 1", "DELETE FROM t WHERE id <> 1"] {
+            let Statement::Delete(delete) = parse_sql(sql) else {
+                panic!("Expected Delete");
+            };
+            assert_eq!(
+                delete.where_clause,
+                Expr::Comparison {
+                    column: "id",
+                    op: ComparisonOp::Neq,
+                    value: Value::Int(1),
+                }
+            );
+            assert_eq!(delete.where_clause.to_string(), "id != 1");
+        }
+    }
+
+    #[test]
+    fn test_parse_delete_limit() {
+        let Statement::Delete(delete) =
+            parse_sql("DELETE FROM logs WHERE level = 'debug' LIMIT 100 RETURNING id")
+        else {
+            panic!("Expected Delete");
+        };
+        assert_eq!(delete.limit, Some(100));
+        assert!(delete.returning.is_some());
+
+        let tokens = Tokenizer::new("DELETE FROM logs WHERE id > 1 LIMIT x")
+            .tokenize()
+            .unwrap();
+        assert!(Parser::new(tokens).parse().is_err());
+    }
+
     #[test]
     fn test_parse_delete_where_and() {
         let sql = "DELETE FROM users WHERE age > 12 AND name = 'John'";
@@ -837,6 +1783,8 @@ mod tests {
                     value: Value::Text("John".into()),
                 }),
             },
+            limit: None,
+            returning: None,
         });
 
         assert_eq!(statement, expected);
@@ -852,7 +1800,7 @@ mod tests {
         let statement = parser.parse().unwrap();
 
         let mut values = HashMap::new();
-        values.insert("name", Value::Text("Bob".into()));
+        values.insert("name", ScalarExpr::Literal(Value::Text("Bob".into())));
 
         let expected = Statement::Update(Update {
             table: "users",
@@ -862,11 +1810,28 @@ mod tests {
                 op: ComparisonOp::Gt,
                 value: Value::Int(12),
             },
+            returning: None,
         });
 
         assert_eq!(statement, expected);
     }
 
+    #[test]
+    fn test_parse_update_with_expression() {
+        let Statement::Update(update) =
+            parse_sql("UPDATE counters SET hits = hits + 1, ratio = (a - b) / 2 WHERE id = 5")
+        else {
+            panic!("Expected Update");
+        };
+        assert_eq!(update.assignments["hits"].to_string(), "hits + 1");
+        assert_eq!(update.assignments["ratio"].to_string(), "(a - b) / 2");
+
+        let tokens = Tokenizer::new("UPDATE counters SET hits = hits + WHERE id = 5")
+            .tokenize()
+            .unwrap();
+        assert!(Parser::new(tokens).parse().is_err());
+    }
+
     #[test]
     fn test_parse_update_set_null() {
         let sql = "UPDATE users SET name = NULL WHERE age > 12";
@@ -877,7 +1842,7 @@ mod tests {
         let statement = parser.parse().unwrap();
 
         let mut values = HashMap::new();
-        values.insert("name", Value::Null);
+        values.insert("name", ScalarExpr::Literal(Value::Null));
 
         let expected = Statement::Update(Update {
             table: "users",
@@ -887,6 +1852,7 @@ mod tests {
                 op: ComparisonOp::Gt,
                 value: Value::Int(12),
             },
+            returning: None,
         });
 
         assert_eq!(statement, expected);
@@ -904,14 +1870,73 @@ mod tests {
         match statement {
             Statement::InsertInto(ins) => {
                 assert_eq!(ins.table, "users");
-                assert_eq!(ins.values.len(), 2);
-                assert_eq!(ins.values[0], Value::Int(1));
-                assert_eq!(ins.values[1], Value::Null);
+                assert_eq!(ins.values, vec![vec![Value::Int(1), Value::Null]]);
             }
             _ => panic!("Expected InsertInto"),
         }
     }
 
+    #[test]
+    fn test_parse_truncate() {
+        assert_eq!(
+            parse_sql("TRUNCATE TABLE users"),
+            Statement::Truncate("users")
+        );
+        assert_eq!(parse_sql("truncate users"), Statement::Truncate("users"));
+
+        for sql in ["TRUNCATE", "TRUNCATE TABLE", "TRUNCATE TABLE a b"] {
+            let tokens = Tokenizer::new(sql).tokenize().unwrap();
+            assert!(Parser::new(tokens).parse().is_err(), "{sql}");
+        }
+    }
+
+    #[test]
+    fn test_parse_drop_table() {
+        assert_eq!(
+            parse_sql("DROP TABLE users"),
+            Statement::DropTable(DropTable {
+                name: "users",
+                if_exists: false,
+            })
+        );
+        assert_eq!(
+            parse_sql("drop table if exists users"),
+            Statement::DropTable(DropTable {
+                name: "users",
+                if_exists: true,
+            })
+        );
+
+        for sql in [
+            "DROP users",
+            "DROP TABLE IF users",
+            "DROP TABLE",
+            "DROP TABLE a b",
+        ] {
+            let tokens = Tokenizer::new(sql).tokenize().unwrap();
+            assert!(Parser::new(tokens).parse().is_err(), "{sql}");
+        }
+    }
+
+    #[test]
+    fn test_parse_alter_table_drop_column() {
+        let expected = Statement::AlterTable(AlterTable {
+            table: "users",
+            action: AlterAction::DropColumn("age"),
+        });
+        assert_eq!(parse_sql("ALTER TABLE users DROP COLUMN age"), expected);
+        assert_eq!(parse_sql("alter table users drop age"), expected);
+
+        for sql in [
+            "ALTER TABLE users DROP COLUMN",
+            "ALTER TABLE users ADD COLUMN age INT",
+            "ALTER users DROP COLUMN age",
+        ] {
+            let tokens = Tokenizer::new(sql).tokenize().unwrap();
+            assert!(Parser::new(tokens).parse().is_err(), "{sql}");
+        }
+    }
+
     #[test]
     fn test_parse_vacuum_without_table() {
         let sql = "VACUUM";
@@ -935,8 +1960,804 @@ mod tests {
         let mut parser = Parser::new(tokens);
         let statement = parser.parse().unwrap();
 
-        let expected = Statement::Vacuum(Some("users".into()));
+        let expected = Statement::Vacuum(Some("users"));
 
         assert_eq!(statement, expected);
     }
+
+    #[test]
+    fn test_parse_where_boolean_literals() {
+        let sql = "SELECT * FROM users WHERE TRUE OR FALSE";
+        let mut tokenizer = Tokenizer::new(sql);
+        let tokens = tokenizer.tokenize().unwrap();
+
+        let mut parser = Parser::new(tokens);
+        let statement = parser.parse().unwrap();
+
+        match statement {
+            Statement::Select(sel) => {
+                assert_eq!(
+                    sel.where_clause,
+                    Some(Expr::Or {
+                        left: Box::new(Expr::Literal(true)),
+                        right: Box::new(Expr::Literal(false)),
+                    })
+                );
+            }
+            _ => panic!("Expected Select"),
+        }
+    }
+
+    #[test]
+    fn test_parse_where_bare_bool_column() {
+        let Statement::Select(sel) = parse_sql("SELECT * FROM users WHERE active AND NOT banned")
+        else {
+            panic!("Expected Select");
+        };
+        assert_eq!(
+            sel.where_clause,
+            Some(Expr::And {
+                left: Box::new(Expr::Comparison {
+                    column: "active",
+                    op: ComparisonOp::Eq,
+                    value: Value::Bool(true),
+                }),
+                right: Box::new(Expr::Not(Box::new(Expr::Comparison {
+                    column: "banned",
+                    op: ComparisonOp::Eq,
+                    value: Value::Bool(true),
+                }))),
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_where_parentheses() {
+        let comparison = |column| Expr::Comparison {
+            column,
+            op: ComparisonOp::Eq,
+            value: Value::Int(1),
+        };
+        let Statement::Select(sel) = parse_sql("SELECT * FROM t WHERE (a = 1 OR b = 1) AND c = 1")
+        else {
+            panic!("Expected Select");
+        };
+        assert_eq!(
+            sel.where_clause,
+            Some(Expr::And {
+                left: Box::new(Expr::Or {
+                    left: Box::new(comparison("a")),
+                    right: Box::new(comparison("b")),
+                }),
+                right: Box::new(comparison("c")),
+            })
+        );
+        assert_eq!(
+            sel.where_clause.unwrap().to_string(),
+            "(a = 1 OR b = 1) AND c = 1"
+        );
+
+        // without parentheses AND binds tighter
+        let Statement::Select(sel) = parse_sql("SELECT * FROM t WHERE a = 1 OR b = 1 AND c = 1")
+        else {
+            panic!("Expected Select");
+        };
+        assert!(matches!(sel.where_clause, Some(Expr::Or { .. })));
+
+        let Statement::Select(sel) =
+            parse_sql("SELECT * FROM t WHERE ((a = 1)) AND (b = 1 AND (c = 1 OR d = 1))")
+        else {
+            panic!("Expected Select");
+        };
+        assert_eq!(
+            sel.where_clause,
+            Some(Expr::And {
+                left: Box::new(comparison("a")),
+                right: Box::new(Expr::And {
+                    left: Box::new(comparison("b")),
+                    right: Box::new(Expr::Or {
+                        left: Box::new(comparison("c")),
+                        right: Box::new(comparison("d")),
+                    }),
+                }),
+            })
+        );
+
+        for sql in [
+            "SELECT * FROM t WHERE (a = 1 OR b = 1 AND c = 1",
+            "SELECT * FROM t WHERE a = 1) OR b = 1",
+            "SELECT * FROM t WHERE () AND a = 1",
+        ] {
+            let tokens = Tokenizer::new(sql).tokenize().unwrap();
+            assert!(Parser::new(tokens).parse().is_err(), "{sql}");
+        }
+    }
+
+    #[test]
+    fn test_parse_where_in_list() {
+        let Statement::Select(sel) =
+            parse_sql("SELECT * FROM t WHERE id IN (1, 2, 3) AND name NOT IN ('a', NULL)")
+        else {
+            panic!("Expected Select");
+        };
+        assert_eq!(
+            sel.where_clause,
+            Some(Expr::And {
+                left: Box::new(Expr::InList {
+                    column: "id",
+                    values: vec![Value::Int(1), Value::Int(2), Value::Int(3)],
+                    negated: false,
+                }),
+                right: Box::new(Expr::InList {
+                    column: "name",
+                    values: vec![Value::Text("a".into()), Value::Null],
+                    negated: true,
+                }),
+            })
+        );
+        assert_eq!(
+            sel.where_clause.unwrap().to_string(),
+            "id IN (1, 2, 3) AND name NOT IN ('a', NULL)"
+        );
+
+        for sql in [
+            "SELECT * FROM t WHERE id IN ()",
+            "SELECT * FROM t WHERE id IN (1, )",
+            "SELECT * FROM t WHERE id IN 1, 2",
+            "SELECT * FROM t WHERE id NOT 1",
+        ] {
+            let tokens = Tokenizer::new(sql).tokenize().unwrap();
+            assert!(Parser::new(tokens).parse().is_err(), "{sql}");
+        }
+    }
+
+    #[test]
+    fn test_parse_where_like() {
+        let Statement::Select(sel) =
+            parse_sql("SELECT * FROM t WHERE name LIKE 'Al%' OR name NOT LIKE '_o''s'")
+        else {
+            panic!("Expected Select");
+        };
+        assert_eq!(
+            sel.where_clause,
+            Some(Expr::Or {
+                left: Box::new(Expr::Like {
+                    column: "name",
+                    pattern: "Al%".into(),
+                    negated: false,
+                }),
+                right: Box::new(Expr::Like {
+                    column: "name",
+                    pattern: "_o's".into(),
+                    negated: true,
+                }),
+            })
+        );
+        assert_eq!(
+            sel.where_clause.unwrap().to_string(),
+            "name LIKE 'Al%' OR name NOT LIKE '_o''s'"
+        );
+
+        for sql in [
+            "SELECT * FROM t WHERE name LIKE 1",
+            "SELECT * FROM t WHERE name LIKE",
+            "SELECT * FROM t WHERE name NOT = 'a'",
+        ] {
+            let tokens = Tokenizer::new(sql).tokenize().unwrap();
+            assert!(Parser::new(tokens).parse().is_err(), "{sql}");
+        }
+    }
+
+    #[test]
+    fn test_parse_where_not() {
+        let Statement::Select(sel) = parse_sql("SELECT * FROM t WHERE NOT age > 18 AND id = 1")
+        else {
+            panic!("Expected Select");
+        };
+        // NOT binds tighter than AND
+        assert_eq!(
+            sel.where_clause,
+            Some(Expr::And {
+                left: Box::new(Expr::Not(Box::new(Expr::Comparison {
+                    column: "age",
+                    op: ComparisonOp::Gt,
+                    value: Value::Int(18),
+                }))),
+                right: Box::new(Expr::Comparison {
+                    column: "id",
+                    op: ComparisonOp::Eq,
+                    value: Value::Int(1),
+                }),
+            })
+        );
+
+        let Statement::Select(sel) = parse_sql("SELECT * FROM t WHERE NOT (a = 1 AND b = 2)")
+        else {
+            panic!("Expected Select");
+        };
+        let where_clause = sel.where_clause.unwrap();
+        assert!(matches!(&where_clause, Expr::Not(inner) if matches!(**inner, Expr::And { .. })));
+        assert_eq!(where_clause.to_string(), "NOT (a = 1 AND b = 2)");
+
+        let Statement::Select(sel) = parse_sql("SELECT * FROM t WHERE NOT NOT active") else {
+            panic!("Expected Select");
+        };
+        assert_eq!(
+            sel.where_clause,
+            Some(Expr::Not(Box::new(Expr::Not(Box::new(Expr::Comparison {
+                column: "active",
+                op: ComparisonOp::Eq,
+                value: Value::Bool(true),
+            })))))
+        );
+
+        let tokens = Tokenizer::new("SELECT * FROM t WHERE NOT (a = 1")
+            .tokenize()
+            .unwrap();
+        assert!(Parser::new(tokens).parse().is_err());
+    }
+
+    #[test]
+    fn test_parse_where_between() {
+        let Statement::Select(sel) =
+            parse_sql("SELECT * FROM r WHERE x BETWEEN low AND 10 AND id = 1")
+        else {
+            panic!("Expected Select");
+        };
+        // the first AND belongs to BETWEEN, the second one joins the predicates
+        assert_eq!(
+            sel.where_clause,
+            Some(Expr::And {
+                left: Box::new(Expr::Between {
+                    column: "x",
+                    low: ScalarExpr::Column("low"),
+                    high: ScalarExpr::Literal(Value::Int(10)),
+                }),
+                right: Box::new(Expr::Comparison {
+                    column: "id",
+                    op: ComparisonOp::Eq,
+                    value: Value::Int(1),
+                }),
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_where_greater_lower_equal() {
+        let Statement::Select(sel) = parse_sql("SELECT * FROM t WHERE a >= 1 AND b <= c") else {
+            panic!("Expected Select");
+        };
+        assert_eq!(
+            sel.where_clause,
+            Some(Expr::And {
+                left: Box::new(Expr::Comparison {
+                    column: "a",
+                    op: ComparisonOp::Gte,
+                    value: Value::Int(1),
+                }),
+                right: Box::new(Expr::ColumnComparison {
+                    left: "b",
+                    op: ComparisonOp::Lte,
+                    right: "c",
+                }),
+            })
+        );
+        assert_eq!(sel.where_clause.unwrap().to_string(), "a >= 1 AND b <= c");
+    }
+
+    #[test]
+    fn test_parse_where_is_null() {
+        let Statement::Select(sel) =
+            parse_sql("SELECT * FROM t WHERE email IS NULL OR phone IS NOT NULL")
+        else {
+            panic!("Expected Select");
+        };
+        assert_eq!(
+            sel.where_clause,
+            Some(Expr::Or {
+                left: Box::new(Expr::IsNull {
+                    column: "email",
+                    negated: false,
+                }),
+                right: Box::new(Expr::IsNull {
+                    column: "phone",
+                    negated: true,
+                }),
+            })
+        );
+        assert_eq!(
+            sel.where_clause.unwrap().to_string(),
+            "email IS NULL OR phone IS NOT NULL"
+        );
+
+        let tokens = Tokenizer::new("SELECT * FROM t WHERE email IS 1")
+            .tokenize()
+            .unwrap();
+        assert!(Parser::new(tokens).parse().is_err());
+    }
+
+    #[test]
+    fn test_parse_where_scalar_subquery() {
+        let Statement::Select(sel) =
+            parse_sql("SELECT * FROM t WHERE n > (SELECT MIN(n) FROM t WHERE ok) AND id = 1")
+        else {
+            panic!("Expected Select");
+        };
+        let Some(Expr::And { left, .. }) = sel.where_clause else {
+            panic!("Expected And");
+        };
+        let Expr::Subquery { column, op, query } = *left else {
+            panic!("Expected Subquery");
+        };
+        assert_eq!((column, op), ("n", ComparisonOp::Gt));
+        assert_eq!(query.table, "t");
+        assert!(query.where_clause.is_some());
+    }
+
+    #[test]
+    fn test_parse_join() {
+        let Statement::Select(sel) =
+            parse_sql("SELECT users.name FROM users LEFT OUTER JOIN orders ON users.id = user_id")
+        else {
+            panic!("Expected Select");
+        };
+        assert_eq!(
+            sel.columns,
+            ColumnsSelect::Items(vec![SelectItem::Column("users.name")])
+        );
+        assert_eq!(
+            sel.join,
+            Some(Join {
+                kind: JoinKind::Left,
+                table: "orders",
+                alias: None,
+                on: ("users.id", "user_id"),
+            })
+        );
+
+        let Statement::Select(sel) =
+            parse_sql("SELECT u.name FROM users u JOIN orders o ON u.id = o.user_id")
+        else {
+            panic!("Expected Select");
+        };
+        let join = sel.join.unwrap();
+        assert_eq!((join.table, join.alias), ("orders", Some("o")));
+
+        for sql in [
+            "SELECT * FROM a JOIN b ON x = y",
+            "SELECT * FROM a INNER JOIN b ON x = y",
+        ] {
+            let Statement::Select(sel) = parse_sql(sql) else {
+                panic!("Expected Select");
+            };
+            assert_eq!(sel.join.map(|j| j.kind), Some(JoinKind::Inner));
+        }
+    }
+
+    #[test]
+    fn test_parse_table_alias() {
+        for sql in [
+            "SELECT u.name FROM users u WHERE u.id = 1",
+            "SELECT u.name FROM users AS u WHERE u.id = 1",
+        ] {
+            let Statement::Select(sel) = parse_sql(sql) else {
+                panic!("Expected Select");
+            };
+            assert_eq!((sel.table, sel.alias), ("users", Some("u")));
+            assert!(sel.where_clause.is_some());
+        }
+
+        let Statement::Select(sel) = parse_sql("SELECT * FROM users") else {
+            panic!("Expected Select");
+        };
+        assert_eq!(sel.alias, None);
+
+        let tokens = Tokenizer::new("SELECT * FROM users AS WHERE id = 1")
+            .tokenize()
+            .unwrap();
+        assert!(Parser::new(tokens).parse().is_err());
+    }
+
+    #[test]
+    fn test_parse_set() {
+        assert_eq!(
+            parse_sql("SET float_epsilon = 0.5"),
+            Statement::Set(SetVariable {
+                name: "float_epsilon",
+                value: Value::Float(0.5),
+            })
+        );
+        let tokens = Tokenizer::new("SET case_sensitive FALSE")
+            .tokenize()
+            .unwrap();
+        assert!(Parser::new(tokens).parse().is_err());
+    }
+
+    #[test]
+    fn test_parse_correlated_subquery_item() {
+        let Statement::Select(sel) = parse_sql(
+            "SELECT name, (SELECT COUNT(*) FROM orders o WHERE o.user_id = u.id) AS n FROM users u",
+        ) else {
+            panic!("Expected Select");
+        };
+        let ColumnsSelect::Items(items) = sel.columns else {
+            panic!("Expected items");
+        };
+        assert_eq!(items[0], SelectItem::Column("name"));
+        let SelectItem::Subquery { query, alias } = &items[1] else {
+            panic!("Expected Subquery");
+        };
+        assert_eq!(*alias, Some("n"));
+        assert_eq!(query.alias, Some("o"));
+        assert_eq!(
+            query.where_clause,
+            Some(Expr::ColumnComparison {
+                left: "o.user_id",
+                op: ComparisonOp::Eq,
+                right: "u.id",
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_column_check() {
+        let Statement::CreateTable(create) =
+            parse_sql("CREATE TABLE t (age INT CHECK (age BETWEEN 0 AND 150), name TEXT)")
+        else {
+            panic!("Expected CreateTable");
+        };
+        assert_eq!(
+            create.columns[0].check.as_deref(),
+            Some("age BETWEEN 0 AND 150")
+        );
+        assert_eq!(create.columns[1].check, None);
+
+        let tokens = Tokenizer::new("CREATE TABLE t (age INT CHECK age > 0)")
+            .tokenize()
+            .unwrap();
+        assert!(Parser::new(tokens).parse().is_err());
+
+        // floats are printed without an exponent, so the stored condition parses back
+        let Statement::CreateTable(create) =
+            parse_sql("CREATE TABLE m (f FLOAT CHECK (f > -0.000001 AND f < 10000000000000000.0))")
+        else {
+            panic!("Expected CreateTable");
+        };
+        let check = create.columns[0].check.as_deref().unwrap();
+        assert_eq!(check, "f > -0.000001 AND f < 10000000000000000.0");
+        let tokens = Tokenizer::new(check).tokenize().unwrap();
+        let condition = Parser::new(tokens).parse_condition().unwrap();
+        assert_eq!(condition.to_string(), check);
+
+        let tokens = Tokenizer::new("age > 1 AND name = 'x'").tokenize().unwrap();
+        assert!(Parser::new(tokens).parse_condition().is_ok());
+        let tokens = Tokenizer::new("age > 1 name").tokenize().unwrap();
+        assert!(Parser::new(tokens).parse_condition().is_err());
+    }
+
+    #[test]
+    fn test_parse_column_not_null() {
+        let Statement::CreateTable(create) =
+            parse_sql("CREATE TABLE t (id INT AUTO_INCREMENT NOT NULL, name TEXT NOT NULL, x INT)")
+        else {
+            panic!("Expected CreateTable");
+        };
+        let flags: Vec<_> = create.columns.iter().map(|col| col.not_null).collect();
+        assert_eq!(flags, vec![true, true, false]);
+        assert!(create.columns[0].auto_increment);
+
+        let tokens = Tokenizer::new("CREATE TABLE t (name TEXT NOT)")
+            .tokenize()
+            .unwrap();
+        assert!(Parser::new(tokens).parse().is_err());
+    }
+
+    #[test]
+    fn test_parse_generated_column() {
+        let Statement::CreateTable(create) =
+            parse_sql("CREATE TABLE t (w INT, h INT, area INT GENERATED ALWAYS AS (w * h + 1))")
+        else {
+            panic!("Expected CreateTable");
+        };
+        assert_eq!(create.columns[2].generated.as_deref(), Some("(w * h) + 1"));
+        assert_eq!(create.columns[0].generated, None);
+
+        let tokens = Tokenizer::new("CREATE TABLE t (w INT, a INT GENERATED AS (w))")
+            .tokenize()
+            .unwrap();
+        assert!(Parser::new(tokens).parse().is_err());
+
+        let tokens = Tokenizer::new("w * 2").tokenize().unwrap();
+        assert!(Parser::new(tokens).parse_scalar().is_ok());
+        let tokens = Tokenizer::new("w * 2 h").tokenize().unwrap();
+        assert!(Parser::new(tokens).parse_scalar().is_err());
+    }
+
+    #[test]
+    fn test_parse_limit_offset() {
+        let Statement::Select(sel) = parse_sql("SELECT * FROM t LIMIT 10 OFFSET 20") else {
+            panic!("Expected Select");
+        };
+        assert_eq!((sel.offset, sel.limit), (Some(20), Some(10)));
+
+        let Statement::Select(sel) = parse_sql("SELECT * FROM t ORDER BY id OFFSET 5") else {
+            panic!("Expected Select");
+        };
+        assert_eq!((sel.offset, sel.limit), (Some(5), None));
+
+        for sql in [
+            "SELECT * FROM t OFFSET",
+            "SELECT * FROM t OFFSET -1",
+            "SELECT * FROM t OFFSET 2 LIMIT 1",
+        ] {
+            let tokens = Tokenizer::new(sql).tokenize().unwrap();
+            assert!(Parser::new(tokens).parse().is_err(), "{sql}");
+        }
+    }
+
+    #[test]
+    fn test_parse_limit_offset_comma() {
+        let Statement::Select(sel) = parse_sql("SELECT * FROM t LIMIT 20, 10") else {
+            panic!("Expected Select");
+        };
+        assert_eq!((sel.offset, sel.limit), (Some(20), Some(10)));
+
+        let Statement::Select(sel) = parse_sql("SELECT * FROM t LIMIT 20") else {
+            panic!("Expected Select");
+        };
+        assert_eq!((sel.offset, sel.limit), (None, Some(20)));
+
+        for sql in [
+            "SELECT * FROM t LIMIT 20, 10 OFFSET 5",
+            "SELECT * FROM t LIMIT 20,",
+        ] {
+            let tokens = Tokenizer::new(sql).tokenize().unwrap();
+            assert!(Parser::new(tokens).parse().is_err(), "{sql}");
+        }
+    }
+
+    #[test]
+    fn test_parse_table_sample() {
+        let Statement::Select(sel) =
+            parse_sql("SELECT * FROM t u TABLESAMPLE (12.5 PERCENT) REPEATABLE (42) WHERE id > 1")
+        else {
+            panic!("Expected Select");
+        };
+        assert_eq!(sel.alias, Some("u"));
+        assert_eq!(
+            sel.sample,
+            Some(TableSample {
+                percent: 12.5,
+                seed: Some(42),
+            })
+        );
+
+        let Statement::Select(sel) = parse_sql("SELECT * FROM t TABLESAMPLE (10 PERCENT)") else {
+            panic!("Expected Select");
+        };
+        assert_eq!(sel.sample.and_then(|s| s.seed), None);
+
+        for sql in [
+            "SELECT * FROM t TABLESAMPLE (150 PERCENT)",
+            "SELECT * FROM t TABLESAMPLE (10)",
+            "SELECT * FROM t TABLESAMPLE ('a' PERCENT)",
+        ] {
+            let tokens = Tokenizer::new(sql).tokenize().unwrap();
+            assert!(Parser::new(tokens).parse().is_err(), "{sql}");
+        }
+    }
+
+    /// Tokenizes and parses `sql` through the full pipeline.
+    fn parse_sql(sql: &str) -> Statement<'_> {
+        let tokens = Tokenizer::new(sql).tokenize().unwrap();
+        Parser::new(tokens).parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_lowercase_keywords_match_uppercase() {
+        let canonical = parse_sql("SELECT name FROM users WHERE AGE > 18 AND NAME = 'x' OR id = 1");
+        let lowercase = parse_sql("select name from users where AGE > 18 and NAME = 'x' or id = 1");
+        let mixed = parse_sql("SeLeCt name FrOm users WhErE AGE > 18 aNd NAME = 'x' Or id = 1");
+
+        assert_eq!(lowercase, canonical);
+        assert_eq!(mixed, canonical);
+    }
+
+    #[test]
+    fn test_parse_lowercase_and_or_in_delete_and_update() {
+        assert_eq!(
+            parse_sql("delete from users where age > 18 and name = 'x'"),
+            parse_sql("DELETE FROM users WHERE age > 18 AND name = 'x'")
+        );
+        assert_eq!(
+            parse_sql("update users set age = 1 where id = 1 or id = 2"),
+            parse_sql("UPDATE users SET age = 1 WHERE id = 1 OR id = 2")
+        );
+    }
+
+    #[test]
+    fn test_parse_group_by_rollup() {
+        let Statement::Select(sel) = parse_sql(
+            "SELECT region, product, SUM(amount) FROM sales GROUP BY ROLLUP(region, product)",
+        ) else {
+            panic!("Expected Select");
+        };
+        assert_eq!(sel.group_by, Some(vec!["region", "product"]));
+        assert!(sel.rollup);
+
+        let Statement::Select(sel) =
+            parse_sql("SELECT region, COUNT(*) FROM sales GROUP BY region")
+        else {
+            panic!("Expected Select");
+        };
+        assert!(!sel.rollup);
+
+        let tokens = Tokenizer::new("SELECT COUNT(*) FROM sales GROUP BY ROLLUP(region")
+            .tokenize()
+            .unwrap();
+        assert!(Parser::new(tokens).parse().is_err());
+    }
+
+    #[test]
+    fn test_parse_group_by_having() {
+        let Statement::Select(select) =
+            parse_sql("SELECT dept, COUNT(*) FROM emp GROUP BY dept HAVING COUNT(*) > 2")
+        else {
+            panic!("Expected Select");
+        };
+        assert_eq!(select.group_by, Some(vec!["dept"]));
+        assert_eq!(
+            select.having,
+            Some(Expr::AggregateComparison {
+                aggregate: Aggregate::CountStar,
+                op: ComparisonOp::Gt,
+                value: Value::Int(2),
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_computed_projection_with_alias() {
+        let Statement::Select(select) = parse_sql("SELECT name, age * 2 AS double_age FROM t")
+        else {
+            panic!("Expected Select");
+        };
+        assert_eq!(
+            select.columns,
+            ColumnsSelect::Items(vec![
+                SelectItem::Column("name"),
+                SelectItem::Computed {
+                    expr: ScalarExpr::Binary {
+                        left: Box::new(ScalarExpr::Column("age")),
+                        op: ArithmeticOp::Mul,
+                        right: Box::new(ScalarExpr::Literal(Value::Int(2))),
+                    },
+                    alias: Some("double_age"),
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_projection_aliases() {
+        let Statement::Select(select) =
+            parse_sql("SELECT age AS years, COUNT(*) AS n, SUM(age) FROM t GROUP BY age")
+        else {
+            panic!("Expected Select");
+        };
+        assert_eq!(
+            select.columns,
+            ColumnsSelect::Items(vec![
+                SelectItem::Computed {
+                    expr: ScalarExpr::Column("age"),
+                    alias: Some("years"),
+                },
+                SelectItem::Aggregate {
+                    function: Aggregate::CountStar,
+                    filter: None,
+                    alias: Some("n"),
+                },
+                SelectItem::Aggregate {
+                    function: Aggregate::Sum("age"),
+                    filter: None,
+                    alias: None,
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_star_mixed_with_columns() {
+        let Statement::Select(select) = parse_sql("SELECT *, id FROM t") else {
+            panic!("Expected Select");
+        };
+        assert_eq!(
+            select.columns,
+            ColumnsSelect::Items(vec![SelectItem::Star, SelectItem::Column("id")])
+        );
+
+        let Statement::Select(select) = parse_sql("SELECT * FROM t") else {
+            panic!("Expected Select");
+        };
+        assert_eq!(select.columns, ColumnsSelect::Star);
+    }
+
+    #[test]
+    fn test_parse_column_default_current_timestamp() {
+        let Statement::CreateTable(create) = parse_sql(
+            "CREATE TABLE t (id INT AUTO_INCREMENT, at TIMESTAMP DEFAULT CURRENT_TIMESTAMP)",
+        ) else {
+            panic!("Expected CreateTable");
+        };
+        assert_eq!(
+            create.columns,
+            vec![
+                ColumnDef::new("id", DataType::Int).auto_increment(),
+                ColumnDef::new("at", DataType::Timestamp).default(ColumnDefault::CurrentTimestamp),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_column_default_value() {
+        let Statement::CreateTable(create) = parse_sql(
+            "CREATE TABLE t (n INT DEFAULT 0, ratio FLOAT DEFAULT 1, tag TEXT DEFAULT 'new')",
+        ) else {
+            panic!("Expected CreateTable");
+        };
+        let defaults: Vec<_> = create
+            .columns
+            .iter()
+            .map(|col| col.default.clone())
+            .collect();
+        assert_eq!(
+            defaults,
+            vec![
+                Some(ColumnDefault::Value(Value::Int(0))),
+                Some(ColumnDefault::Value(Value::Float(1.0))),
+                Some(ColumnDefault::Value(Value::Text("new".into()))),
+            ]
+        );
+
+        for sql in [
+            "CREATE TABLE t (n INT DEFAULT 'zero')",
+            "CREATE TABLE t (n INT DEFAULT 1.5)",
+            "CREATE TABLE t (n INT DEFAULT)",
+        ] {
+            let tokens = Tokenizer::new(sql).tokenize().unwrap();
+            assert!(Parser::new(tokens).parse().is_err(), "{sql}");
+        }
+    }
+
+    #[test]
+    fn test_parse_fetch_first_rows_only() {
+        let Statement::Select(select) =
+            parse_sql("SELECT * FROM t ORDER BY id FETCH FIRST 3 ROWS ONLY")
+        else {
+            panic!("Expected Select");
+        };
+        assert_eq!(select.limit, Some(3));
+
+        let Statement::Select(select) = parse_sql("SELECT * FROM t FETCH FIRST ROW ONLY") else {
+            panic!("Expected Select");
+        };
+        assert_eq!(select.limit, Some(1));
+
+        let tokens = Tokenizer::new("SELECT * FROM t FETCH FIRST 2 ROWS")
+            .tokenize()
+            .unwrap();
+        assert!(Parser::new(tokens).parse().is_err());
+    }
+
+    #[test]
+    fn test_parse_delete_requires_from() {
+        assert!(matches!(
+            parse_sql("DELETE FROM t WHERE id = 1"),
+            Statement::Delete(Delete { table: "t", .. })
+        ));
+
+        let tokens = Tokenizer::new("DELETE t WHERE id = 1").tokenize().unwrap();
+        let err = Parser::new(tokens).parse().unwrap_err();
+        assert!(err.contains("Expected FROM"), "{err}");
+    }
 }
@@ -0,0 +1,166 @@
+use std::fmt;
+
+use crate::ast::JoinKind;
+
+/// A node of a query plan, as returned by [crate::Database::explain_plan].
+///
+/// The tree is read from the leaves up: rows come out of a [PlanNode::Scan] (or a
+/// [PlanNode::Join] of two scans) and flow through each parent node in turn. Conditions,
+/// keys and aggregates are kept as their SQL text so the plan owns all of its data and
+/// can be stored or serialized freely.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PlanNode {
+    /// Reads every live row of a table.
+    Scan {
+        /// The name of the table.
+        table: String,
+        /// The alias the query gives to the table, if any.
+        alias: Option<String>,
+    },
+    /// Keeps a random share of the rows (`TABLESAMPLE`).
+    Sample {
+        /// The share of rows kept, from 0 to 100.
+        percent: f64,
+        /// The seed given with `REPEATABLE`, if any.
+        seed: Option<u64>,
+        /// The sampled input.
+        input: Box<PlanNode>,
+    },
+    /// Nested-loop join of two inputs on an equality.
+    Join {
+        /// `INNER` or `LEFT`.
+        kind: JoinKind,
+        /// The two columns compared, as written in the query.
+        on: (String, String),
+        /// The outer (left) input.
+        left: Box<PlanNode>,
+        /// The inner (right) input, rescanned for every left row.
+        right: Box<PlanNode>,
+    },
+    /// Keeps the rows matching a `WHERE` condition.
+    Filter {
+        /// The condition, rendered as SQL.
+        condition: String,
+        /// The filtered input.
+        input: Box<PlanNode>,
+    },
+    /// Groups the rows and computes aggregates, then filters the groups with `HAVING`.
+    Aggregate {
+        /// The `GROUP BY` columns; empty for a single group over every row.
+        group_by: Vec<String>,
+        /// The aggregates computed for each group.
+        aggregates: Vec<String>,
+        /// The `HAVING` condition, if any.
+        having: Option<String>,
+        /// The grouped input.
+        input: Box<PlanNode>,
+    },
+    /// Sorts the rows on `ORDER BY` keys, the first key being the most significant.
+    Sort {
+        /// Each key with its direction (`age DESC`).
+        keys: Vec<String>,
+        /// The sorted input.
+        input: Box<PlanNode>,
+    },
+    /// Removes duplicate result rows (`SELECT DISTINCT`).
+    Distinct {
+        /// The deduplicated input.
+        input: Box<PlanNode>,
+    },
+    /// Skips `offset` rows and keeps at most `limit` of the rest.
+    Limit {
+        /// The maximum number of rows kept, `None` for no maximum.
+        limit: Option<usize>,
+        /// The number of rows skipped first.
+        offset: usize,
+        /// The truncated input.
+        input: Box<PlanNode>,
+    },
+}
+
+impl PlanNode {
+    /// Returns the direct inputs of the node: none for a scan, two for a join.
+    pub fn children(&self) -> Vec<&PlanNode> {
+        match self {
+            PlanNode::Scan { .. } => vec![],
+            PlanNode::Join { left, right, .. } => vec![left, right],
+            PlanNode::Sample { input, .. }
+            | PlanNode::Filter { input, .. }
+            | PlanNode::Aggregate { input, .. }
+            | PlanNode::Sort { input, .. }
+            | PlanNode::Distinct { input }
+            | PlanNode::Limit { input, .. } => vec![input],
+        }
+    }
+
+    /// Writes the node on one line, then its children indented below it.
+    fn fmt_indented(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        write!(f, "{:width$}", "", width = depth * 2)?;
+        match self {
+            PlanNode::Scan { table, alias } => {
+                write!(f, "Scan {}", table)?;
+                if let Some(alias) = alias {
+                    write!(f, " AS {}", alias)?;
+                }
+            }
+            PlanNode::Join { kind, on, .. } => {
+                let kind = match kind {
+                    JoinKind::Inner => "Inner",
+                    JoinKind::Left => "Left",
+                };
+                write!(f, "{} Join on {} = {}", kind, on.0, on.1)?;
+            }
+            PlanNode::Sample { percent, seed, .. } => {
+                write!(f, "Sample {}%", percent)?;
+                if let Some(seed) = seed {
+                    write!(f, " seed {}", seed)?;
+                }
+            }
+            PlanNode::Filter { condition, .. } => write!(f, "Filter {}", condition)?,
+            PlanNode::Aggregate {
+                group_by,
+                aggregates,
+                having,
+                ..
+            } => {
+                write!(f, "Aggregate {}", aggregates.join(", "))?;
+                if !group_by.is_empty() {
+                    write!(f, " by {}", group_by.join(", "))?;
+                }
+                if let Some(having) = having {
+                    write!(f, " having {}", having)?;
+                }
+            }
+            PlanNode::Sort { keys, .. } => write!(f, "Sort {}", keys.join(", "))?,
+            PlanNode::Distinct { .. } => f.write_str("Distinct")?,
+            PlanNode::Limit { limit, offset, .. } => {
+                f.write_str("Limit")?;
+                if let Some(limit) = limit {
+                    write!(f, " {}", limit)?;
+                }
+                if *offset > 0 {
+                    write!(f, " offset {}", offset)?;
+                }
+            }
+        }
+        for child in self.children() {
+            writeln!(f)?;
+            child.fmt_indented(f, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+/// Renders the plan as an indented tree, one node per line, parents above their inputs.
+///
+/// ```text
+/// Limit 5
+///   Sort age DESC
+///     Filter age > 18
+///       Scan users
+/// ```
+impl fmt::Display for PlanNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_indented(f, 0)
+    }
+}
@@ -0,0 +1,63 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+
+/// Draws a Bernoulli sample of rows for `TABLESAMPLE`: each call to [RowSampler::keep]
+/// independently says whether to keep the next row.
+///
+/// The generator is a SplitMix64, so the same seed always draws the same rows from the
+/// same table.
+///
+/// # Example
+/// ```
+/// # use db::sample::RowSampler;
+/// let mut first = RowSampler::new(25.0, Some(7));
+/// let mut second = RowSampler::new(25.0, Some(7));
+/// let a: Vec<bool> = (0..100).map(|_| first.keep()).collect();
+/// let b: Vec<bool> = (0..100).map(|_| second.keep()).collect();
+/// assert_eq!(a, b);
+/// ```
+#[derive(Debug, Clone)]
+pub struct RowSampler {
+    /// The probability of keeping a row, from 0 to 1.
+    probability: f64,
+    state: u64,
+}
+
+impl RowSampler {
+    /// Creates a sampler keeping `percent`% of the rows, seeded with `seed` or with a
+    /// random seed when `None`.
+    pub fn new(percent: f64, seed: Option<u64>) -> Self {
+        let state = seed.unwrap_or_else(|| RandomState::new().build_hasher().finish());
+        Self {
+            probability: percent / 100.0,
+            state,
+        }
+    }
+
+    /// Returns whether the next row belongs to the sample.
+    pub fn keep(&mut self) -> bool {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+        // the top 53 bits give a uniform float in [0, 1)
+        ((z >> 11) as f64 / (1u64 << 53) as f64) < self.probability
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_row_sampler_bounds() {
+        let mut none = RowSampler::new(0.0, Some(1));
+        let mut all = RowSampler::new(100.0, Some(1));
+        assert!((0..1000).all(|_| !none.keep() && all.keep()));
+
+        let mut half = RowSampler::new(50.0, Some(3));
+        let kept = (0..10_000).filter(|_| half.keep()).count();
+        assert!((4_700..=5_300).contains(&kept), "{kept}");
+    }
+}
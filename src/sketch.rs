@@ -0,0 +1,105 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use crate::value::Value;
+
+/// Number of bits of the hash used to pick a register.
+const PRECISION: u32 = 12;
+/// Number of registers, `2^PRECISION`.
+const REGISTERS: usize = 1 << PRECISION;
+
+/// A HyperLogLog sketch estimating the number of distinct values it has seen.
+///
+/// The sketch always takes `2^12` bytes, whatever the number of values. Its estimates
+/// have a relative standard error of `1.04 / sqrt(2^12)`, about 1.6%: roughly two
+/// estimates out of three are within 1.6% of the exact count, and almost all within 5%.
+/// Small cardinalities (below about 10 000) fall back to linear counting, which is
+/// much more precise.
+///
+/// # Example
+/// ```
+/// # use db::sketch::HyperLogLog;
+/// # use db::value::Value;
+/// let mut hll = HyperLogLog::new();
+/// for i in 0..1000 {
+///     hll.insert(&Value::Int(i % 100));
+/// }
+/// assert!((95..=105).contains(&hll.estimate()));
+/// ```
+#[derive(Debug, Clone)]
+pub struct HyperLogLog {
+    /// For each register, the highest rank (position of the first set bit) seen.
+    registers: Vec<u8>,
+}
+
+impl Default for HyperLogLog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HyperLogLog {
+    /// Creates an empty sketch.
+    pub fn new() -> Self {
+        Self {
+            registers: vec![0; REGISTERS],
+        }
+    }
+
+    /// Adds a value to the sketch. Adding the same value again has no effect.
+    pub fn insert(&mut self, value: &Value) {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        let hash = hasher.finish();
+
+        let register = (hash >> (64 - PRECISION)) as usize;
+        // the guard bit bounds the rank when the remaining bits are all zero
+        let rest = (hash << PRECISION) | (1 << (PRECISION - 1));
+        let rank = rest.leading_zeros() as u8 + 1;
+        self.registers[register] = self.registers[register].max(rank);
+    }
+
+    /// Returns the estimated number of distinct values inserted.
+    pub fn estimate(&self) -> u64 {
+        let m = REGISTERS as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let sum: f64 = self
+            .registers
+            .iter()
+            .map(|&rank| 2f64.powi(-i32::from(rank)))
+            .sum();
+        let raw = alpha * m * m / sum;
+
+        let zeros = self.registers.iter().filter(|&&rank| rank == 0).count();
+        if raw <= 2.5 * m && zeros > 0 {
+            // linear counting is more accurate while many registers are still empty
+            (m * (m / zeros as f64).ln()).round() as u64
+        } else {
+            raw.round() as u64
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_sketch() {
+        assert_eq!(HyperLogLog::new().estimate(), 0);
+    }
+
+    #[test]
+    fn test_estimate_within_error_bound() {
+        for cardinality in [1_000, 50_000, 200_000] {
+            let mut hll = HyperLogLog::new();
+            for i in 0..cardinality {
+                // every value twice, the duplicates must not count
+                hll.insert(&Value::Text(format!("user{i}").into()));
+                hll.insert(&Value::Text(format!("user{i}").into()));
+            }
+            let error = (hll.estimate() as f64 - cardinality as f64).abs() / cardinality as f64;
+            assert!(error < 0.05, "{cardinality}: estimate {}", hll.estimate());
+        }
+    }
+}
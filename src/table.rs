@@ -1,6 +1,7 @@
 use allocative::Allocative;
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use bitvec::prelude::*;
 
@@ -18,6 +19,45 @@ pub struct ColumnDef {
     pub data_type: DataType,
     /// If the value can be auto incremented WARNING: Only for Value::Int
     pub auto_increment: bool,
+    /// With `NOT NULL`, the column rejects `NULL`, whether written explicitly or left out
+    /// of an `INSERT` without a default.
+    pub not_null: bool,
+    /// The value used when an `INSERT` omits this column (`NULL` if `None`).
+    pub default: Option<ColumnDefault>,
+    /// A `CHECK` condition every inserted or updated row must satisfy, kept as SQL
+    /// (`age BETWEEN 0 AND 150`). It is enforced by the `INSERT` and `UPDATE`
+    /// statements of [crate::Database], not by [Table::insert].
+    pub check: Option<String>,
+    /// The expression of a generated column, kept as SQL (`w * h`). Its value is computed
+    /// from the other columns of the row by the `INSERT` and `UPDATE` statements of
+    /// [crate::Database], which reject any direct write to the column.
+    pub generated: Option<String>,
+}
+
+/// The default of a column, evaluated each time a row is inserted without it.
+#[derive(Debug, Clone, PartialEq, Allocative)]
+pub enum ColumnDefault {
+    /// `DEFAULT CURRENT_TIMESTAMP`: the insertion time. Only valid for `TIMESTAMP` columns.
+    CurrentTimestamp,
+    /// `DEFAULT 0`: a constant, of the type of the column.
+    Value(Value),
+}
+
+impl ColumnDefault {
+    /// Produces the value to store for a new row.
+    pub fn evaluate(&self) -> Value {
+        match self {
+            ColumnDefault::CurrentTimestamp => Value::Timestamp(now_micros()),
+            ColumnDefault::Value(value) => value.clone(),
+        }
+    }
+}
+
+/// Returns the current time in microseconds since the Unix epoch.
+fn now_micros() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_micros() as i64)
 }
 
 impl ColumnDef {
@@ -26,9 +66,51 @@ impl ColumnDef {
             name: name.into(),
             data_type,
             auto_increment: false,
+            not_null: false,
+            default: None,
+            check: None,
+            generated: None,
         }
     }
 
+    /// Sets the value used when an `INSERT` omits this column.
+    ///
+    /// Uses the builder pattern, like [`ColumnDef::auto_increment`]:
+    /// `ColumnDef::new("created_at", DataType::Timestamp).default(ColumnDefault::CurrentTimestamp)`
+    pub fn default(mut self, default: ColumnDefault) -> Self {
+        self.default = Some(default);
+        self
+    }
+
+    /// Makes the column reject `NULL` values.
+    ///
+    /// Uses the builder pattern, like [`ColumnDef::auto_increment`]:
+    /// `ColumnDef::new("name", DataType::Text).not_null()`
+    pub fn not_null(mut self) -> Self {
+        self.not_null = true;
+        self
+    }
+
+    /// Sets a `CHECK` condition, written as a SQL `WHERE` condition over the columns of
+    /// the table. The condition is validated when the table is created.
+    ///
+    /// Uses the builder pattern, like [`ColumnDef::auto_increment`]:
+    /// `ColumnDef::new("age", DataType::Int).check("age BETWEEN 0 AND 150")`
+    pub fn check(mut self, condition: impl Into<String>) -> Self {
+        self.check = Some(condition.into());
+        self
+    }
+
+    /// Makes this a generated column, computed from an arithmetic expression over the
+    /// other columns of the table. The expression is validated when the table is created.
+    ///
+    /// Uses the builder pattern, like [`ColumnDef::auto_increment`]:
+    /// `ColumnDef::new("area", DataType::Int).generated("w * h")`
+    pub fn generated(mut self, expression: impl Into<String>) -> Self {
+        self.generated = Some(expression.into());
+        self
+    }
+
     /// Marks this column as auto-incremented. Only valid for `DataType::Int` columns.
     ///
     /// Uses the builder pattern so it can be chained after [`ColumnDef::new`]:
@@ -44,21 +126,142 @@ impl ColumnDef {
 }
 
 /// Defines the structure of a table, consisting of an ordered list of column definitions.
-#[derive(Debug, Clone, Allocative)]
+#[derive(Debug, Clone, PartialEq, Allocative)]
 pub struct Schema {
     /// The ordered collection of column definitions.
     pub columns: Vec<ColumnDef>,
 }
 
+/// Fluent builder for a [Schema], created with [Schema::builder].
+///
+/// ```
+/// use db::{ColumnDef, DataType, Schema};
+///
+/// let schema = Schema::builder()
+///     .column_def(ColumnDef::new("id", DataType::Int).auto_increment())
+///     .column("name", DataType::Text)
+///     .build();
+/// assert_eq!(schema.columns.len(), 2);
+/// ```
+#[derive(Debug, Default)]
+pub struct SchemaBuilder {
+    columns: Vec<ColumnDef>,
+}
+
+impl SchemaBuilder {
+    /// Appends a plain column, without constraints.
+    pub fn column(self, name: impl Into<String>, data_type: DataType) -> Self {
+        self.column_def(ColumnDef::new(name, data_type))
+    }
+
+    /// Appends a fully configured column, e.g. one marked with
+    /// [`ColumnDef::auto_increment`] or [`ColumnDef::default`].
+    pub fn column_def(mut self, column: ColumnDef) -> Self {
+        self.columns.push(column);
+        self
+    }
+
+    /// Returns the schema. It is not validated here: [Schema::validate] runs when the
+    /// table is created.
+    pub fn build(self) -> Schema {
+        Schema {
+            columns: self.columns,
+        }
+    }
+}
+
 impl Schema {
+    /// Starts building a schema column by column, see [SchemaBuilder].
+    pub fn builder() -> SchemaBuilder {
+        SchemaBuilder::default()
+    }
+
+    /// Checks that the schema can back a table.
+    ///
+    /// # Errors
+    /// Returns an error if the schema has no column, if two columns share the same
+    /// name, if an `AUTO_INCREMENT` column is not an `INT`, or if a
+    /// `DEFAULT CURRENT_TIMESTAMP` column is not a `TIMESTAMP`, or if a constant default
+    /// is not of the type of its column.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.columns.is_empty() {
+            return Err("A table must have at least one column".into());
+        }
+        let mut names = HashSet::new();
+        for col in &self.columns {
+            if !names.insert(col.name.as_str()) {
+                return Err(format!("Duplicate column name '{}'", col.name));
+            }
+            if col.auto_increment && col.data_type != DataType::Int {
+                return Err(format!(
+                    "AUTO_INCREMENT column '{}' must be of type INT",
+                    col.name
+                ));
+            }
+            if col.default == Some(ColumnDefault::CurrentTimestamp)
+                && col.data_type != DataType::Timestamp
+            {
+                return Err(format!(
+                    "DEFAULT CURRENT_TIMESTAMP column '{}' must be of type TIMESTAMP",
+                    col.name
+                ));
+            }
+            if let Some(ColumnDefault::Value(value)) = &col.default
+                && value.data_type().is_some_and(|t| t != col.data_type)
+            {
+                return Err(format!(
+                    "DEFAULT {:?} of column '{}' must be of type {:?}",
+                    value, col.name, col.data_type
+                ));
+            }
+            if col.generated.is_some() && (col.auto_increment || col.default.is_some()) {
+                return Err(format!(
+                    "Generated column '{}' cannot have AUTO_INCREMENT or DEFAULT",
+                    col.name
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the position of the named column.
+    ///
+    /// The columns of a joined query are qualified (`users.id`): there, an unqualified
+    /// name matches the only column with that name, whichever its table.
+    ///
+    /// # Errors
+    /// Returns an error if no column matches, or if several tables have the column.
     pub fn index_of(&self, name: &str) -> Result<usize, String> {
-        self.columns
-            .iter()
-            .position(|col| col.name == name)
-            .ok_or_else(|| format!("Column '{}' not found", name))
+        if let Some(idx) = self.columns.iter().position(|col| col.name == name) {
+            return Ok(idx);
+        }
+        let mut matches = self.columns.iter().enumerate().filter_map(|(idx, col)| {
+            let (_, short) = col.name.rsplit_once('.')?;
+            (short == name).then_some(idx)
+        });
+        match (matches.next(), matches.next()) {
+            (Some(idx), None) => Ok(idx),
+            (Some(_), Some(_)) => Err(format!(
+                "Column '{}' is ambiguous, qualify it with its table",
+                name
+            )),
+            (None, _) => Err(format!("Column '{}' not found", name)),
+        }
+    }
+
+    /// Returns the data type of the named column, or `None` if it does not exist
+    /// (resolved like [Schema::index_of]).
+    pub fn data_type_of(&self, name: &str) -> Option<DataType> {
+        self.index_of(name)
+            .ok()
+            .map(|idx| self.columns[idx].data_type)
     }
 }
 
+fn not_null_violation(column: &str) -> String {
+    format!("Column {:?} is NOT NULL and cannot be set to NULL", column)
+}
+
 /// Represents a database table stored in memory.
 ///
 /// Data is stored in a columnar format (one [Column] per schema field) to improve
@@ -92,8 +295,8 @@ pub struct Table {
     /// `max(remaining values) + 1`.
     pub next_auto_id: Option<i64>,
     /// Schema index (as `i64`) of the auto-increment column, or `None` if the
-    /// table has no auto-increment column. Set once at table creation and never
-    /// modified afterwards.
+    /// table has no auto-increment column. Set at table creation and only shifted
+    /// (or cleared) by [`Table::drop_column`].
     pub idx_col_auto_id: Option<i64>,
 }
 
@@ -135,13 +338,15 @@ impl Table {
         }
     }
 
-    /// Appends a new row of values to the table.
+    /// Checks that a row could be appended by [Table::insert], without storing it.
     ///
     /// # Errors
     /// Returns an error if:
     /// - The number of values provided does not match the number of columns in the schema.
     /// - The data type of any value does not match the corresponding column's data type.
-    pub fn insert(&mut self, mut values: Vec<Value>) -> Result<(), String> {
+    /// - A `NOT NULL` column gets `NULL`, except the auto-increment column, where `NULL`
+    ///   stands for the next id.
+    pub fn validate_row(&self, values: &[Value]) -> Result<(), String> {
         // Validate row length
         if values.len() != self.schema.columns.len() {
             return Err(format!(
@@ -151,6 +356,53 @@ impl Table {
             ));
         }
 
+        // Validate types
+        for (value, column) in values.iter().zip(&self.schema.columns) {
+            if value.data_type().is_some_and(|t| t != column.data_type) {
+                return Err(format!(
+                    "type of value {:?} mismatch with schema column type: {:?} vs {:?}",
+                    value,
+                    value.data_type(),
+                    column.data_type
+                ));
+            }
+            if column.not_null && !column.auto_increment && *value == Value::Null {
+                return Err(not_null_violation(&column.name));
+            }
+        }
+        Ok(())
+    }
+
+    /// Fills the `NULL` auto-increment values of `rows` with the ids [Table::insert] would
+    /// give them if they were inserted in order, without changing the table.
+    ///
+    /// Explicit ids are kept and move the next id past them, as they do on insert.
+    pub fn assign_auto_ids(&self, rows: &mut [Vec<Value>]) {
+        let (Some(idx), Some(mut next_id)) = (self.idx_col_auto_id, self.next_auto_id) else {
+            return;
+        };
+        for row in rows {
+            match row.get_mut(idx as usize) {
+                Some(value @ Value::Null) => {
+                    *value = Value::Int(next_id);
+                    next_id += 1;
+                }
+                Some(Value::Int(v)) if *v >= next_id => next_id = *v + 1,
+                _ => {}
+            }
+        }
+    }
+
+    /// Appends a new row of values to the table and returns its index.
+    ///
+    /// The whole row is validated before any column is touched, so a failed insert
+    /// leaves the table unchanged.
+    ///
+    /// # Errors
+    /// Same as [Table::validate_row].
+    pub fn insert(&mut self, mut values: Vec<Value>) -> Result<usize, String> {
+        self.validate_row(&values)?;
+
         // Fill auto-increment column if the value is Null
         if let (Some(idx), Some(next_id)) = (self.idx_col_auto_id, &mut self.next_auto_id) {
             let idx = idx as usize;
@@ -165,19 +417,8 @@ impl Table {
             }
         }
 
-        // Validate types and push values to respective columns
+        // Push values to respective columns
         for (i, mut value) in values.into_iter().enumerate() {
-            if value
-                .data_type()
-                .is_some_and(|t| t != self.schema.columns[i].data_type)
-            {
-                return Err(format!(
-                    "type of value {:?} mismatch with schema column type: {:?} vs {:?}",
-                    value,
-                    value.data_type(),
-                    self.schema.columns[i].data_type
-                ));
-            }
             // if the value is a string check in the hashset if it already exists or not and
             // increase strong count if yes.
             if let Value::Text(ref mut s) = value {
@@ -188,7 +429,7 @@ impl Table {
 
         self.deletion_vector.push(false);
         self.row_count += 1;
-        Ok(())
+        Ok(self.row_count - 1)
     }
 
     /// Retrieves a full row of values at the specified index.
@@ -212,6 +453,35 @@ impl Table {
         Ok(())
     }
 
+    /// Deletes every live row for which `pred` returns `false`, like [Vec::retain].
+    ///
+    /// The predicate receives the full row, in schema order. Removed rows are marked in
+    /// the deletion vector exactly like a SQL `DELETE`, so `row_count` keeps counting
+    /// them until the next [Table::vacuum]. Returns the number of rows removed.
+    pub fn retain<F>(&mut self, mut pred: F) -> usize
+    where
+        F: FnMut(&[Value]) -> bool,
+    {
+        let mut removed = 0;
+        let mut row = Vec::with_capacity(self.columns.len());
+        for row_idx in 0..self.row_count {
+            if self.deletion_vector[row_idx] {
+                continue;
+            }
+            row.clear();
+            row.extend(
+                self.columns
+                    .iter()
+                    .map(|col| col.get(row_idx).unwrap_or(Value::Null)),
+            );
+            if !pred(&row) {
+                self.deletion_vector.set(row_idx, true);
+                removed += 1;
+            }
+        }
+        removed
+    }
+
     /// Finds and returns a reference to a specific column by its name.
     ///
     /// Returns `None` if no column with the given name exists in this table.
@@ -226,6 +496,15 @@ impl Table {
         self.columns.iter_mut().find(|col| col.name == name)
     }
 
+    /// Switches a `Text` column to dictionary-encoded storage.
+    ///
+    /// See [Column::encode_dictionary]. Worth it for low-cardinality columns only.
+    pub fn dictionary_encode(&mut self, name: &str) -> Result<(), String> {
+        self.get_col_mut(name)
+            .ok_or_else(|| format!("column {:?} is not a column from this table", name))?
+            .encode_dictionary()
+    }
+
     pub fn vacuum(&mut self) -> Result<(), String> {
         if !self.deletion_vector.any() {
             return Ok(());
@@ -261,12 +540,91 @@ impl Table {
         Ok(())
     }
 
+    /// Removes the column `name` and its data, keeping [Table::schema] and
+    /// [Table::columns] aligned. Rows keep their indices, so `row_count` is unchanged.
+    ///
+    /// # Errors
+    /// Returns an error if the column does not exist or is the only column of the table.
+    pub fn drop_column(&mut self, name: &str) -> Result<(), String> {
+        let idx = self
+            .schema
+            .columns
+            .iter()
+            .position(|col| col.name == name)
+            .ok_or_else(|| format!("Column {:?} does not exist in table {:?}", name, self.name))?;
+        if self.columns.len() == 1 {
+            return Err(format!(
+                "Cannot drop column {:?}, the only column of table {:?}",
+                name, self.name
+            ));
+        }
+
+        self.schema.columns.remove(idx);
+        self.columns.remove(idx);
+        match self.idx_col_auto_id {
+            Some(auto) if auto as usize == idx => {
+                self.idx_col_auto_id = None;
+                self.next_auto_id = None;
+            }
+            Some(auto) if auto as usize > idx => self.idx_col_auto_id = Some(auto - 1),
+            _ => {}
+        }
+
+        // strings that only the dropped column used are no longer referenced
+        self.string_interner
+            .retain(|value| Arc::strong_count(value) > 1);
+        Ok(())
+    }
+
+    /// Removes every row at once, without going through the deletion vector.
+    ///
+    /// Like a [Table::vacuum] that leaves nothing, the auto-increment counter restarts at 1.
+    pub fn truncate(&mut self) {
+        for column in &mut self.columns {
+            column.clear();
+        }
+        self.row_count = 0;
+        self.deletion_vector.clear();
+        self.string_interner.clear();
+        if self.next_auto_id.is_some() {
+            self.next_auto_id = Some(1);
+        }
+    }
+
+    /// Releases the spare capacity of every column, the deletion vector and the string
+    /// interner.
+    ///
+    /// Neither deletes nor [Table::vacuum] give memory back on their own: call this after
+    /// a bulk deletion and a vacuum to reclaim it.
+    pub fn shrink_to_fit(&mut self) {
+        for column in &mut self.columns {
+            column.shrink_to_fit();
+        }
+        self.deletion_vector.shrink_to_fit();
+        self.string_interner.shrink_to_fit();
+    }
+
     /// Do an update of values at each row_idx wanted.
+    ///
+    /// # Errors
+    /// Returns an error if a column does not exist or does not accept its value. `NULL`
+    /// for a `NOT NULL` column is detected before any value is written.
     pub fn update(
         &mut self,
         rows_idx: &[usize],
         values: HashMap<&str, Value>,
     ) -> Result<(), String> {
+        for (col, value) in &values {
+            if *value == Value::Null
+                && self
+                    .schema
+                    .columns
+                    .iter()
+                    .any(|def| def.name == *col && def.not_null)
+            {
+                return Err(not_null_violation(col));
+            }
+        }
         for (col, mut value) in values {
             if let Value::Text(ref mut s) = value {
                 self.internalize_string(s);
@@ -281,6 +639,40 @@ impl Table {
         Ok(())
     }
 
+    /// Writes a different value to each row of column `col`: `values` pairs a row index
+    /// with its new value.
+    ///
+    /// # Errors
+    /// Returns an error if the column does not exist or does not accept a value. `NULL`
+    /// for a `NOT NULL` column is detected before any value is written.
+    pub fn update_rows(
+        &mut self,
+        col: &str,
+        mut values: Vec<(usize, Value)>,
+    ) -> Result<(), String> {
+        if values.iter().any(|(_, value)| *value == Value::Null)
+            && self
+                .schema
+                .columns
+                .iter()
+                .any(|def| def.name == col && def.not_null)
+        {
+            return Err(not_null_violation(col));
+        }
+        for (_, value) in &mut values {
+            if let Value::Text(s) = value {
+                self.internalize_string(s);
+            }
+        }
+        let column = self
+            .get_col_mut(col)
+            .ok_or_else(|| format!("column {:?} is not a column from this table", col))?;
+        for (row, value) in &values {
+            column.set(*row, value)?;
+        }
+        Ok(())
+    }
+
     /// Determines if the table needs a vacuum based on the provided configuration.
     pub fn should_vacuum(&self, config: &VacuumConfig) -> bool {
         if !config.enabled {
@@ -302,6 +694,39 @@ impl Table {
         false
     }
 
+    /// Checks that the physical storage is consistent with the schema.
+    ///
+    /// Verifies that there is exactly one column per [ColumnDef], in schema order, that
+    /// each column passes [Column::validate_against], and that the deletion vector covers
+    /// `row_count` rows. Tables built through [Table::insert] always pass; this is meant
+    /// for tables assembled from their public fields, e.g. when loading them back from
+    /// disk, so a drifted schema is reported instead of causing a panic mid-query.
+    /// [Database::load_table](crate::Database::load_table) runs it on every table it adds.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.columns.len() != self.schema.columns.len() {
+            return Err(format!(
+                "Table '{}' has {} columns while its schema declares {}",
+                self.name,
+                self.columns.len(),
+                self.schema.columns.len()
+            ));
+        }
+        for (column, def) in self.columns.iter().zip(&self.schema.columns) {
+            column
+                .validate_against(def, self.row_count)
+                .map_err(|e| format!("Table '{}': {e}", self.name))?;
+        }
+        if self.deletion_vector.len() != self.row_count {
+            return Err(format!(
+                "Table '{}' deletion vector covers {} rows, expected {}",
+                self.name,
+                self.deletion_vector.len(),
+                self.row_count
+            ));
+        }
+        Ok(())
+    }
+
     /// Check if a string is already in the string_interner and get its Arc.
     pub fn lookup_string(&self, s: &str) -> Option<Arc<str>> {
         self.string_interner.get(s).cloned()
@@ -349,6 +774,85 @@ mod tests {
         assert_eq!(row1, vec![Value::Int(2), Value::Null]);
     }
 
+    #[test]
+    fn test_schema_builder() {
+        let built = Schema::builder()
+            .column("id", DataType::Int)
+            .column("name", DataType::Text)
+            .build();
+        let manual = Schema {
+            columns: vec![
+                ColumnDef::new("id", DataType::Int),
+                ColumnDef::new("name", DataType::Text),
+            ],
+        };
+        assert_eq!(built, manual);
+
+        let with_constraints = Schema::builder()
+            .column_def(ColumnDef::new("id", DataType::Int).auto_increment())
+            .column_def(
+                ColumnDef::new("at", DataType::Timestamp).default(ColumnDefault::CurrentTimestamp),
+            )
+            .build();
+        assert!(with_constraints.columns[0].auto_increment);
+        assert_eq!(
+            with_constraints.columns[1].default,
+            Some(ColumnDefault::CurrentTimestamp)
+        );
+    }
+
+    #[test]
+    fn test_table_clone_is_independent() {
+        let schema = Schema {
+            columns: vec![
+                ColumnDef::new("id", DataType::Int).auto_increment(),
+                ColumnDef::new("name", DataType::Text),
+            ],
+        };
+        let mut table = Table::new("users".into(), schema);
+        table
+            .insert(vec![Value::Null, Value::Text("Alice".into())])
+            .unwrap();
+        table
+            .insert(vec![Value::Null, Value::Text("Bob".into())])
+            .unwrap();
+
+        let mut clone = table.clone();
+        assert_eq!(clone.schema, table.schema);
+        assert_eq!(clone.row_count, 2);
+        assert_eq!(clone.get_row(1), table.get_row(1));
+
+        clone.delete_row(0).unwrap();
+        clone
+            .insert(vec![Value::Null, Value::Text("Carol".into())])
+            .unwrap();
+
+        assert_eq!(table.row_count, 2);
+        assert_eq!(
+            table.get_row(0),
+            Some(vec![Value::Int(1), Value::Text("Alice".into())])
+        );
+        assert_eq!(table.next_auto_id, Some(3));
+        assert_eq!(clone.get_row(0), None);
+        assert_eq!(
+            clone.get_row(2),
+            Some(vec![Value::Int(3), Value::Text("Carol".into())])
+        );
+    }
+
+    #[test]
+    fn test_insert_returns_row_index() {
+        let schema = Schema::builder().column("id", DataType::Int).build();
+        let mut table = Table::new("t".into(), schema);
+
+        assert_eq!(table.insert(vec![Value::Int(10)]), Ok(0));
+        assert_eq!(table.insert(vec![Value::Int(20)]), Ok(1));
+        // a failed insert does not consume an index
+        assert!(table.insert(vec![Value::Bool(true)]).is_err());
+        assert_eq!(table.insert(vec![Value::Int(30)]), Ok(2));
+        assert_eq!(table.get_row(2), Some(vec![Value::Int(30)]));
+    }
+
     #[test]
     fn test_column_count_mismatch() {
         let schema = Schema {
@@ -376,6 +880,29 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_type_mismatch_leaves_table_unchanged() {
+        let schema = Schema {
+            columns: vec![
+                ColumnDef::new("id", DataType::Int).auto_increment(),
+                ColumnDef::new("name", DataType::Text),
+                ColumnDef::new("age", DataType::Int),
+            ],
+        };
+        let mut table = Table::new("test".into(), schema);
+
+        let result = table.insert(vec![
+            Value::Null,
+            Value::Text("Alice".into()),
+            Value::Text("thirty".into()),
+        ]);
+        assert!(result.is_err());
+
+        assert_eq!(table.row_count, 0);
+        assert!(table.columns.iter().all(|c| c.is_empty()));
+        assert_eq!(table.next_auto_id, Some(1));
+    }
+
     #[test]
     fn test_get_col() {
         let schema = Schema {
@@ -571,4 +1098,248 @@ mod tests {
         assert_eq!(table.idx_col_auto_id, None);
         assert_eq!(table.next_auto_id, None);
     }
+
+    #[test]
+    fn test_retain_even_ids() {
+        let schema = Schema {
+            columns: vec![
+                ColumnDef::new("id", DataType::Int),
+                ColumnDef::new("name", DataType::Text),
+            ],
+        };
+        let mut table = Table::new("users".into(), schema);
+        for id in 0..6 {
+            table
+                .insert(vec![
+                    Value::Int(id),
+                    Value::Text(format!("user{id}").into()),
+                ])
+                .unwrap();
+        }
+        table.delete_row(2).unwrap();
+
+        let removed = table.retain(|row| row[0].as_int().is_some_and(|id| id % 2 == 0));
+        assert_eq!(removed, 3);
+
+        let survivors: Vec<_> = (0..table.row_count)
+            .filter_map(|i| table.get_row(i))
+            .collect();
+        assert_eq!(
+            survivors,
+            vec![
+                vec![Value::Int(0), Value::Text("user0".into())],
+                vec![Value::Int(4), Value::Text("user4".into())],
+            ]
+        );
+
+        table.vacuum().unwrap();
+        assert_eq!(table.row_count, 2);
+    }
+
+    fn make_valid_table() -> Table {
+        let schema = Schema::builder()
+            .column("id", DataType::Int)
+            .column("name", DataType::Text)
+            .build();
+        let mut table = Table::new("users".into(), schema);
+        table
+            .insert(vec![Value::Int(1), Value::Text("alice".into())])
+            .unwrap();
+        table.insert(vec![Value::Int(2), Value::Null]).unwrap();
+        table
+    }
+
+    #[test]
+    fn test_drop_column() {
+        let schema = Schema::builder()
+            .column("name", DataType::Text)
+            .column_def(ColumnDef::new("id", DataType::Int).auto_increment())
+            .column("age", DataType::Int)
+            .build();
+        let mut table = Table::new("users".into(), schema);
+        table
+            .insert(vec![Value::Text("Ada".into()), Value::Null, Value::Int(36)])
+            .unwrap();
+        table
+            .insert(vec![Value::Text("Bob".into()), Value::Null, Value::Null])
+            .unwrap();
+
+        // the auto-increment column shifts along with the columns after the dropped one
+        table.drop_column("name").unwrap();
+        assert_eq!(table.row_count, 2);
+        assert_eq!(table.idx_col_auto_id, Some(0));
+        assert!(table.lookup_string("Ada").is_none());
+        table.insert(vec![Value::Null, Value::Int(7)]).unwrap();
+        assert_eq!(table.get_row(2), Some(vec![Value::Int(3), Value::Int(7)]));
+        table.validate().unwrap();
+
+        table.drop_column("id").unwrap();
+        assert_eq!((table.idx_col_auto_id, table.next_auto_id), (None, None));
+        assert_eq!(table.get_row(0), Some(vec![Value::Int(36)]));
+
+        let err = table.drop_column("id").unwrap_err();
+        assert!(err.contains("does not exist"), "{err}");
+        let err = table.drop_column("age").unwrap_err();
+        assert!(err.contains("only column"), "{err}");
+    }
+
+    #[test]
+    fn test_not_null_columns() {
+        let schema = Schema::builder()
+            .column_def(
+                ColumnDef::new("id", DataType::Int)
+                    .auto_increment()
+                    .not_null(),
+            )
+            .column_def(ColumnDef::new("name", DataType::Text).not_null())
+            .column("age", DataType::Int)
+            .build();
+        let mut table = Table::new("users".into(), schema);
+
+        // NULL still asks for the next id in the auto-increment column
+        table
+            .insert(vec![Value::Null, Value::Text("Ada".into()), Value::Null])
+            .unwrap();
+        let err = table
+            .insert(vec![Value::Int(5), Value::Null, Value::Int(3)])
+            .unwrap_err();
+        assert!(err.contains("\"name\" is NOT NULL"), "{err}");
+        assert_eq!(table.row_count, 1);
+
+        let values = HashMap::from([("age", Value::Int(4)), ("name", Value::Null)]);
+        assert!(table.update(&[0], values).is_err());
+        assert_eq!(
+            table.get_row(0),
+            Some(vec![Value::Int(1), Value::Text("Ada".into()), Value::Null])
+        );
+    }
+
+    #[test]
+    fn test_update_rows() {
+        let schema = Schema::builder()
+            .column("id", DataType::Int)
+            .column_def(ColumnDef::new("name", DataType::Text).not_null())
+            .build();
+        let mut table = Table::new("users".into(), schema);
+        for (id, name) in [(1, "Ada"), (2, "Bob"), (3, "Cy")] {
+            table
+                .insert(vec![Value::Int(id), Value::Text(name.into())])
+                .unwrap();
+        }
+
+        table
+            .update_rows("id", vec![(0, Value::Int(10)), (2, Value::Int(30))])
+            .unwrap();
+        let ids: Vec<_> = (0..3)
+            .map(|i| table.get_row(i).unwrap()[0].clone())
+            .collect();
+        assert_eq!(ids, [10, 2, 30].map(Value::Int));
+
+        let values = vec![(0, Value::Text("Al".into())), (1, Value::Null)];
+        assert!(table.update_rows("name", values).is_err());
+        assert_eq!(table.get_row(0).unwrap()[1], Value::Text("Ada".into()));
+        assert!(table.update_rows("nope", vec![]).is_err());
+    }
+
+    #[test]
+    fn test_truncate() {
+        let schema = Schema::builder()
+            .column_def(ColumnDef::new("id", DataType::Int).auto_increment())
+            .column("name", DataType::Text)
+            .build();
+        let mut table = Table::new("users".into(), schema);
+        for name in ["Ada", "Bob", "Ada"] {
+            table
+                .insert(vec![Value::Null, Value::Text(name.into())])
+                .unwrap();
+        }
+        table.delete_row(1).unwrap();
+        table.dictionary_encode("name").unwrap();
+
+        table.truncate();
+        assert_eq!(table.row_count, 0);
+        assert!(table.deletion_vector.is_empty());
+        assert!(table.lookup_string("Ada").is_none());
+        assert!(table.get_col("name").unwrap().is_dictionary_encoded());
+        table.validate().unwrap();
+
+        table
+            .insert(vec![Value::Null, Value::Text("Cy".into())])
+            .unwrap();
+        assert_eq!(
+            table.get_row(0),
+            Some(vec![Value::Int(1), Value::Text("Cy".into())])
+        );
+        table.validate().unwrap();
+    }
+
+    #[test]
+    fn test_validate_accepts_consistent_table() {
+        let mut table = make_valid_table();
+        assert!(table.validate().is_ok());
+        table.delete_row(0).unwrap();
+        table.dictionary_encode("name").unwrap();
+        assert!(table.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_rejects_type_drift() {
+        let mut table = make_valid_table();
+        table.columns[0].data_type = DataType::Float;
+        let err = table.validate().unwrap_err();
+        assert!(err.contains("id"), "{err}");
+        assert!(err.contains("Float"), "{err}");
+
+        let mut table = make_valid_table();
+        table.columns[0].data = crate::column::ColumnData::Float(vec![1.0, 2.0]);
+        assert!(table.validate().unwrap_err().contains("storage"));
+    }
+
+    #[test]
+    fn test_validate_rejects_length_drift() {
+        let mut table = make_valid_table();
+        table.columns[1].push(Value::Text("bob".into())).unwrap();
+        assert!(table.validate().unwrap_err().contains("expected 2 rows"));
+
+        let mut table = make_valid_table();
+        table.deletion_vector.push(false);
+        assert!(table.validate().unwrap_err().contains("deletion vector"));
+    }
+
+    #[test]
+    fn test_validate_rejects_column_mismatch() {
+        let mut table = make_valid_table();
+        table.columns.pop();
+        assert!(table.validate().unwrap_err().contains("schema declares 2"));
+
+        let mut table = make_valid_table();
+        table.columns.swap(0, 1);
+        assert!(table.validate().is_err());
+    }
+
+    #[test]
+    fn test_shrink_to_fit_after_vacuum() {
+        let schema = Schema::builder()
+            .column("id", DataType::Int)
+            .column("name", DataType::Text)
+            .build();
+        let mut table = Table::new("t".into(), schema);
+        for i in 0..10_000 {
+            table
+                .insert(vec![Value::Int(i), Value::Text(format!("name{i}").into())])
+                .unwrap();
+        }
+        table.retain(|row| row[0] < Value::Int(10));
+        table.vacuum().unwrap();
+        let vacuumed_size = allocative::size_of_unique_allocated_data(&table);
+
+        table.shrink_to_fit();
+        let shrunk_size = allocative::size_of_unique_allocated_data(&table);
+        assert!(
+            shrunk_size * 20 < vacuumed_size,
+            "shrunk: {shrunk_size} bytes, vacuumed: {vacuumed_size} bytes"
+        );
+        assert_eq!(table.row_count, 10);
+        assert!(table.validate().is_ok());
+    }
 }
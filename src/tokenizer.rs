@@ -1,10 +1,18 @@
 use std::borrow::Cow;
+use std::fmt;
+
+use crate::value::from_hex;
 
 /// Represents the smallest meaningful units (atoms) of the SQL language.
 #[derive(Debug, Clone, PartialEq)]
 pub enum Token<'a> {
     // --- SQL Keywords ---
     Create,
+    Alter,
+    Column,
+    Drop,
+    If,
+    Exists,
     Table,
     Insert,
     Update,
@@ -17,18 +25,48 @@ pub enum Token<'a> {
     Where,
     And,
     Or,
+    Not,
+    Is,
+    In,
+    Like,
+    Between,
+    Join,
+    Inner,
+    Left,
+    Outer,
+    On,
     Limit,
+    Returning,
+    Offset,
+    Fetch,
+    First,
+    Row,
+    Rows,
+    Only,
     Order,
     By,
     Asc,
     Desc,
+    Nulls,
+    Last,
     Vacuum,
+    Truncate,
+    Explain,
+    Tablesample,
+    Rollup,
+    Percent,
+    Repeatable,
+    As,
+    Distinct,
 
     // --- Data Types ---
     Int,
+    UInt,
     Text,
     Float,
     Bool,
+    Timestamp,
+    Bytes,
 
     // Select options
     Count,
@@ -36,7 +74,10 @@ pub enum Token<'a> {
     Sum,
     Min,
     Max,
+    ApproxCountDistinct,
+    Filter,
     Group,
+    Having,
 
     // Transactions
     Begin,
@@ -48,11 +89,15 @@ pub enum Token<'a> {
     Ident(&'a str),
     /// A 64-bit integer literal (e.g., `42`).
     Number(i64),
+    /// An integer literal too large for an `i64`, up to `u64::MAX`.
+    UnsignedNumber(u64),
     /// A string literal, defined between single quotes (e.g., `'Alice'`).
     /// Double single quotes are used to escape: `'it''s'` → `it's`.
     String(Cow<'a, str>),
     /// A 64-bit floating-point literal (e.g., `3.14`).
     FloatNumber(f64),
+    /// A binary literal, written as hexadecimal digits (e.g., `X'deadbeef'`).
+    Blob(Vec<u8>),
     /// The boolean literal `TRUE`.
     True,
     /// The boolean literal `FALSE`.
@@ -71,12 +116,26 @@ pub enum Token<'a> {
     Semicolon,
     /// Multiplication or wildcard symbol `*`
     Star,
+    /// Addition symbol `+`
+    Plus,
+    /// Subtraction symbol `-`
+    Minus,
+    /// Division symbol `/`
+    Slash,
     /// Greater than
     Greater,
     /// Lower than
     Lower,
+    /// Greater than or equal to `>=`
+    GreaterEqual,
+    /// Lower than or equal to `<=`
+    LowerEqual,
     /// Equal to
     Equal,
+    /// NULL-safe equal to `<=>`
+    NullSafeEqual,
+    /// Not equal to, written `!=` or `<>`
+    NotEqual,
 
     // --- Special ---
     /// Represents the End Of File/Input.
@@ -84,13 +143,38 @@ pub enum Token<'a> {
     /// The `AUTO_INCREMENT` keyword, used in `CREATE TABLE` column definitions
     /// to mark an `INT` column as auto-generated on insert.
     AutoIncrement,
+    /// The `DEFAULT` keyword, introducing a column default in `CREATE TABLE`.
+    Default,
+    /// The `CHECK` keyword, introducing a column constraint in `CREATE TABLE`.
+    Check,
+    /// The `GENERATED` keyword, from `GENERATED ALWAYS AS (expr)` in a column definition.
+    Generated,
+    /// The `ALWAYS` keyword, from `GENERATED ALWAYS AS (expr)` in a column definition.
+    Always,
+    /// The `CURRENT_TIMESTAMP` keyword, the insertion time as a column default.
+    CurrentTimestamp,
+}
+
+/// A lexical error, as reported by [Tokenizer::tokenize_collect_errors].
+#[derive(Debug, Clone, PartialEq)]
+pub struct LexError {
+    /// The byte offset in the input where the faulty token starts.
+    pub position: usize,
+    /// What went wrong, as [Tokenizer::tokenize] would report it.
+    pub message: String,
+}
+
+impl fmt::Display for LexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at position {}", self.message, self.position)
+    }
 }
 
 /// A lexical scanner (lexer) that converts a raw SQL string into a sequence of [Token]s.
 pub struct Tokenizer<'a> {
-    /// The input string stored as a vector of characters for easy iteration.
+    /// The SQL text being scanned.
     input: &'a str,
-    /// The current position in the character vector.
+    /// The byte offset of the current character in `input`, always on a char boundary.
     position: usize,
 }
 
@@ -123,7 +207,7 @@ impl<'a> Tokenizer<'a> {
                 break;
             }
 
-            let token = self.next_token()?;
+            let token = self.next_token(tokens.last())?;
             tokens.push(token);
         }
 
@@ -131,8 +215,56 @@ impl<'a> Tokenizer<'a> {
         Ok(tokens)
     }
 
+    /// Processes the entire input like [Self::tokenize], but keeps going after an
+    /// error, so every lexical error of the input can be reported at once.
+    ///
+    /// A faulty token is left out of the returned tokens, and scanning resumes right
+    /// after it (after the bad character for an unsupported one). The tokens always
+    /// end with [Token::Eof].
+    ///
+    /// # Example
+    /// ```
+    /// # use db::tokenizer::{Tokenizer, Token};
+    /// let (tokens, errors) = Tokenizer::new("SELECT ? FROM t").tokenize_collect_errors();
+    /// assert_eq!(tokens[..2], [Token::Select, Token::From]);
+    /// assert_eq!(errors[0].position, 7);
+    /// ```
+    pub fn tokenize_collect_errors(&mut self) -> (Vec<Token<'a>>, Vec<LexError>) {
+        let mut tokens = Vec::new();
+        let mut errors = Vec::new();
+
+        while !self.is_at_end() {
+            self.skip_whitespace();
+
+            if self.is_at_end() {
+                break;
+            }
+
+            let start = self.position;
+            match self.next_token(tokens.last()) {
+                Ok(token) => tokens.push(token),
+                Err(message) => {
+                    errors.push(LexError {
+                        position: start,
+                        message,
+                    });
+                    // an unsupported character is not consumed by next_token
+                    if self.position == start {
+                        self.advance();
+                    }
+                }
+            }
+        }
+
+        tokens.push(Token::Eof);
+        (tokens, errors)
+    }
+
     /// Identifies the next token based on the character at the current position.
-    fn next_token(&mut self) -> Result<Token<'a>, String> {
+    ///
+    /// `previous` is the token just before, which tells a negative number (`> -5`) from
+    /// a subtraction (`a - 5`).
+    fn next_token(&mut self, previous: Option<&Token>) -> Result<Token<'a>, String> {
         let ch = self.current_char();
 
         match ch {
@@ -152,6 +284,44 @@ impl<'a> Tokenizer<'a> {
                 self.advance();
                 Ok(Token::Star)
             }
+            '+' => {
+                self.advance();
+                Ok(Token::Plus)
+            }
+            '-' if self.starts_negative_number(previous) => self.read_number(),
+            '-' => {
+                self.advance();
+                Ok(Token::Minus)
+            }
+            '/' => {
+                self.advance();
+                Ok(Token::Slash)
+            }
+            '<' if self.input[self.position..].starts_with("<=>") => {
+                self.position += 3;
+                Ok(Token::NullSafeEqual)
+            }
+            '<' if self.input[self.position + 1..].starts_with('>') => {
+                self.position += 2;
+                Ok(Token::NotEqual)
+            }
+            '!' if self.input[self.position + 1..].starts_with('=') => {
+                self.position += 2;
+                Ok(Token::NotEqual)
+            }
+            // a doubled `=` (`>==`) is a typo, not `>=` followed by `=`
+            '>' | '<' if self.input[self.position + 1..].starts_with("==") => Err(format!(
+                "operator {:?} is not supported",
+                &self.input[self.position..self.position + 3]
+            )),
+            '>' if self.input[self.position + 1..].starts_with('=') => {
+                self.position += 2;
+                Ok(Token::GreaterEqual)
+            }
+            '<' if self.input[self.position + 1..].starts_with('=') => {
+                self.position += 2;
+                Ok(Token::LowerEqual)
+            }
             '>' => {
                 self.advance();
                 Ok(Token::Greater)
@@ -164,8 +334,9 @@ impl<'a> Tokenizer<'a> {
                 self.advance();
                 Ok(Token::Equal)
             }
+            'x' | 'X' if self.input[self.position + 1..].starts_with('\'') => self.read_blob(),
             c if c.is_alphabetic() => self.read_identifier(),
-            c if c.is_numeric() => self.read_number(),
+            c if c.is_ascii_digit() => self.read_number(),
             '\'' => self.read_string(),
             _ => Err(format!("character: {:?} is not supported", ch)),
         }
@@ -173,14 +344,14 @@ impl<'a> Tokenizer<'a> {
 
     // --- Navigation Helpers ---
 
-    /// Returns the character at the current position.
+    /// Returns the character at the current position, decoding multi-byte UTF-8.
     fn current_char(&self) -> char {
-        self.input.as_bytes()[self.position] as char
+        self.input[self.position..].chars().next().unwrap_or('\0')
     }
 
     /// Moves the cursor forward by one character.
     fn advance(&mut self) {
-        self.position += 1;
+        self.position += self.current_char().len_utf8();
     }
 
     /// Checks if the cursor has reached the end of the input.
@@ -200,19 +371,40 @@ impl<'a> Tokenizer<'a> {
     /// Reads a sequence of alphanumeric characters and determines if it's
     /// a reserved SQL keyword or a user-defined identifier.
     ///
-    /// Keywords are matched case-insensitively.
+    /// Identifiers may use any Unicode letter (`naïve`, `名前`) and continue with Unicode
+    /// letters, digits or `_`.
+    ///
+    /// Keywords are matched case-insensitively. Dot-separated names (`users.id`) are read
+    /// as a single qualified identifier.
     fn read_identifier(&mut self) -> Result<Token<'a>, String> {
         let start = self.position;
-        while !self.is_at_end()
-            && (self.current_char().is_alphanumeric() || self.current_char() == '_')
-        {
+        loop {
+            while !self.is_at_end()
+                && (self.current_char().is_alphanumeric() || self.current_char() == '_')
+            {
+                self.advance();
+            }
+            // a qualified name (`users.id`) is a single identifier
+            let qualified = self.input[self.position..]
+                .strip_prefix('.')
+                .and_then(|rest| rest.chars().next())
+                .is_some_and(|c| c.is_alphabetic() || c == '_');
+            if !qualified {
+                break;
+            }
             self.advance();
         }
 
         let ident = &self.input[start..self.position];
 
-        match ident.to_uppercase().as_str() {
+        // ASCII-only folding: `ſelect` must stay an identifier, not become `SELECT`
+        match ident.to_ascii_uppercase().as_str() {
             "CREATE" => Ok(Token::Create),
+            "ALTER" => Ok(Token::Alter),
+            "COLUMN" => Ok(Token::Column),
+            "DROP" => Ok(Token::Drop),
+            "IF" => Ok(Token::If),
+            "EXISTS" => Ok(Token::Exists),
             "TABLE" => Ok(Token::Table),
             "INSERT" => Ok(Token::Insert),
             "INTO" => Ok(Token::Into),
@@ -223,27 +415,65 @@ impl<'a> Tokenizer<'a> {
             "FROM" => Ok(Token::From),
             "WHERE" => Ok(Token::Where),
             "INT" => Ok(Token::Int),
+            "UINT" => Ok(Token::UInt),
             "TEXT" => Ok(Token::Text),
             "FLOAT" => Ok(Token::Float),
             "BOOL" => Ok(Token::Bool),
+            "TIMESTAMP" => Ok(Token::Timestamp),
+            "BYTES" | "BLOB" => Ok(Token::Bytes),
+            "DEFAULT" => Ok(Token::Default),
+            "CHECK" => Ok(Token::Check),
+            "GENERATED" => Ok(Token::Generated),
+            "ALWAYS" => Ok(Token::Always),
+            "CURRENT_TIMESTAMP" => Ok(Token::CurrentTimestamp),
             "TRUE" => Ok(Token::True),
             "FALSE" => Ok(Token::False),
             "NULL" => Ok(Token::Null),
             "AND" => Ok(Token::And),
             "OR" => Ok(Token::Or),
+            "OFFSET" => Ok(Token::Offset),
+            "RETURNING" => Ok(Token::Returning),
+            "NOT" => Ok(Token::Not),
+            "IS" => Ok(Token::Is),
+            "IN" => Ok(Token::In),
+            "LIKE" => Ok(Token::Like),
+            "BETWEEN" => Ok(Token::Between),
+            "JOIN" => Ok(Token::Join),
+            "INNER" => Ok(Token::Inner),
+            "LEFT" => Ok(Token::Left),
+            "OUTER" => Ok(Token::Outer),
+            "ON" => Ok(Token::On),
             "LIMIT" => Ok(Token::Limit),
+            "FETCH" => Ok(Token::Fetch),
+            "FIRST" => Ok(Token::First),
+            "ROW" => Ok(Token::Row),
+            "ROWS" => Ok(Token::Rows),
+            "ONLY" => Ok(Token::Only),
             "ORDER" => Ok(Token::Order),
             "BY" => Ok(Token::By),
             "ASC" => Ok(Token::Asc),
             "DESC" => Ok(Token::Desc),
+            "NULLS" => Ok(Token::Nulls),
+            "LAST" => Ok(Token::Last),
             "SET" => Ok(Token::Set),
             "VACUUM" => Ok(Token::Vacuum),
+            "TRUNCATE" => Ok(Token::Truncate),
+            "EXPLAIN" => Ok(Token::Explain),
+            "TABLESAMPLE" => Ok(Token::Tablesample),
+            "ROLLUP" => Ok(Token::Rollup),
+            "PERCENT" => Ok(Token::Percent),
+            "REPEATABLE" => Ok(Token::Repeatable),
+            "AS" => Ok(Token::As),
+            "DISTINCT" => Ok(Token::Distinct),
             "COUNT" => Ok(Token::Count),
             "AVG" => Ok(Token::Avg),
             "SUM" => Ok(Token::Sum),
             "MIN" => Ok(Token::Min),
             "MAX" => Ok(Token::Max),
+            "APPROX_COUNT_DISTINCT" => Ok(Token::ApproxCountDistinct),
+            "FILTER" => Ok(Token::Filter),
             "GROUP" => Ok(Token::Group),
+            "HAVING" => Ok(Token::Having),
             "AUTO_INCREMENT" => Ok(Token::AutoIncrement),
             "BEGIN" => Ok(Token::Begin),
             "COMMIT" => Ok(Token::Commit),
@@ -252,14 +482,40 @@ impl<'a> Tokenizer<'a> {
         }
     }
 
-    /// Reads a numeric literal. If a dot `.` is encountered, it returns a
-    /// [Token::FloatNumber], otherwise a [Token::Number].
+    /// Whether the `-` at the current position is the sign of a number literal: it is
+    /// directly followed by a digit, and `previous` cannot end an operand. After a name,
+    /// a value or a closing parenthesis, the `-` is a subtraction (`a-1`, `(a) - 1`).
+    fn starts_negative_number(&self, previous: Option<&Token>) -> bool {
+        let ends_operand = matches!(
+            previous,
+            Some(
+                Token::Ident(_)
+                    | Token::Number(_)
+                    | Token::UnsignedNumber(_)
+                    | Token::FloatNumber(_)
+                    | Token::String(_)
+                    | Token::Blob(_)
+                    | Token::True
+                    | Token::False
+                    | Token::Null
+                    | Token::CurrentTimestamp
+                    | Token::RightParen
+            )
+        );
+        !ends_operand && self.input[self.position + 1..].starts_with(|c: char| c.is_ascii_digit())
+    }
+
+    /// Reads a numeric literal, with its leading `-` if it is negative. If a dot `.` is
+    /// encountered, it returns a [Token::FloatNumber], otherwise a [Token::Number].
     fn read_number(&mut self) -> Result<Token<'a>, String> {
         let start = self.position;
         let mut has_dot = false;
+        if self.current_char() == '-' {
+            self.advance();
+        }
 
         while !self.is_at_end()
-            && (self.current_char().is_numeric() || (self.current_char() == '.' && !has_dot))
+            && (self.current_char().is_ascii_digit() || (self.current_char() == '.' && !has_dot))
         {
             if self.current_char() == '.' {
                 has_dot = true;
@@ -269,6 +525,12 @@ impl<'a> Tokenizer<'a> {
         let number = &self.input[start..self.position];
 
         if !self.is_at_end() && self.current_char() == '.' {
+            // consume the rest of the literal, so scanning can resume after it
+            while !self.is_at_end()
+                && (self.current_char().is_ascii_digit() || self.current_char() == '.')
+            {
+                self.advance();
+            }
             return Err("multiple dots are not allowed for a float".into());
         }
 
@@ -279,10 +541,14 @@ impl<'a> Tokenizer<'a> {
                 .map_err(|e| e.to_string());
         }
 
-        number
-            .parse::<i64>()
-            .map(Token::Number)
-            .map_err(|e| e.to_string())
+        match number.parse::<i64>() {
+            Ok(n) => Ok(Token::Number(n)),
+            // only the range above i64::MAX needs an unsigned literal
+            Err(e) => number
+                .parse::<u64>()
+                .map(Token::UnsignedNumber)
+                .map_err(|_| e.to_string()),
+        }
     }
 
     /// Reads a string literal enclosed in single quotes.
@@ -324,6 +590,25 @@ impl<'a> Tokenizer<'a> {
             Ok(Token::String(Cow::Borrowed(&self.input[start..end])))
         }
     }
+
+    /// Reads a binary literal `X'...'`, decoding its pairs of hexadecimal digits.
+    fn read_blob(&mut self) -> Result<Token<'a>, String> {
+        self.position += 2; // Skip the `X` and the opening quote
+
+        let start = self.position;
+        let Some(len) = self.input[start..].find('\'') else {
+            return Err("Unterminated binary literal".into());
+        };
+        let hex = &self.input[start..start + len];
+        self.position = start + len + 1; // skip the closing quote
+
+        from_hex(hex).map(Token::Blob).ok_or_else(|| {
+            format!(
+                "Binary literal X'{}' must be an even number of hex digits",
+                hex
+            )
+        })
+    }
 }
 
 #[cfg(test)]
@@ -421,6 +706,50 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_tokenize_negative_numbers() {
+        let tokens = Tokenizer::new("(-5, -2.5) > -9223372036854775808")
+            .tokenize()
+            .unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::LeftParen,
+                Token::Number(-5),
+                Token::Comma,
+                Token::FloatNumber(-2.5),
+                Token::RightParen,
+                Token::Greater,
+                Token::Number(i64::MIN),
+                Token::Eof,
+            ]
+        );
+
+        // after an operand, `-` is a subtraction, even without spaces
+        let tokens = Tokenizer::new("a-1 - -2 (b)-3 4-5").tokenize().unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Ident("a"),
+                Token::Minus,
+                Token::Number(1),
+                Token::Minus,
+                Token::Number(-2),
+                Token::LeftParen,
+                Token::Ident("b"),
+                Token::RightParen,
+                Token::Minus,
+                Token::Number(3),
+                Token::Number(4),
+                Token::Minus,
+                Token::Number(5),
+                Token::Eof,
+            ]
+        );
+        let tokens = Tokenizer::new("- 5").tokenize().unwrap();
+        assert_eq!(tokens, vec![Token::Minus, Token::Number(5), Token::Eof]);
+    }
+
     #[test]
     fn test_tokenize_strings() {
         let mut tokenizer = Tokenizer::new("'Alice', 'Bob Dylan'");
@@ -451,6 +780,45 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_tokenize_qualified_identifier() {
+        let tokens = Tokenizer::new("users.id, t._x, 1.5").tokenize().unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Ident("users.id"),
+                Token::Comma,
+                Token::Ident("t._x"),
+                Token::Comma,
+                Token::FloatNumber(1.5),
+                Token::Eof,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_blob_literal() {
+        let mut tokenizer = Tokenizer::new("X'DEADbeef' x''");
+        let tokens = tokenizer.tokenize().unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Blob(vec![0xde, 0xad, 0xbe, 0xef]),
+                Token::Blob(vec![]),
+                Token::Eof,
+            ]
+        );
+
+        assert!(Tokenizer::new("X'abc'").tokenize().is_err());
+        assert!(Tokenizer::new("X'zz'").tokenize().is_err());
+        assert!(Tokenizer::new("X'ab").tokenize().is_err());
+        // an identifier starting with x is still an identifier
+        assert_eq!(
+            Tokenizer::new("xpos").tokenize().unwrap()[0],
+            Token::Ident("xpos")
+        );
+    }
+
     #[test]
     fn test_zero_copy_ident_borrows_input() {
         let sql = "SELECT users";
@@ -459,7 +827,7 @@ mod tests {
 
         // The &str inside Ident points directly into the original sql string
         if let Token::Ident(name) = tokens[1] {
-            assert!(std::ptr::eq(name.as_bytes(), sql[7..].as_bytes()));
+            assert!(std::ptr::eq(name.as_bytes(), &sql.as_bytes()[7..]));
         } else {
             panic!("Expected Ident token");
         }
@@ -475,7 +843,7 @@ mod tests {
         if let Token::String(ref s) = tokens[0] {
             assert_eq!(s.as_ref(), "hello");
             assert!(matches!(s, Cow::Borrowed(_)));
-            assert!(std::ptr::eq(s.as_bytes(), sql[1..6].as_bytes()));
+            assert!(std::ptr::eq(s.as_bytes(), &sql.as_bytes()[1..6]));
         } else {
             panic!("Expected String token");
         }
@@ -527,4 +895,155 @@ mod tests {
         let tokens = tokenizer.tokenize().unwrap();
         assert_eq!(tokens, vec![Token::Select, Token::From, Token::Eof]);
     }
+
+    #[test]
+    fn test_case_insensitive_and_or() {
+        let mut tokenizer = Tokenizer::new("and AND And or OR oR");
+        let tokens = tokenizer.tokenize().unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::And,
+                Token::And,
+                Token::And,
+                Token::Or,
+                Token::Or,
+                Token::Or,
+                Token::Eof
+            ]
+        );
+    }
+
+    #[test]
+    fn test_null_safe_equal() {
+        let tokens = Tokenizer::new("a <=> NULL AND b < 1").tokenize().unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Ident("a"),
+                Token::NullSafeEqual,
+                Token::Null,
+                Token::And,
+                Token::Ident("b"),
+                Token::Lower,
+                Token::Number(1),
+                Token::Eof,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_greater_lower_equal() {
+        let tokens = Tokenizer::new("a >= 1 AND b<=2 AND c > 3")
+            .tokenize()
+            .unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Ident("a"),
+                Token::GreaterEqual,
+                Token::Number(1),
+                Token::And,
+                Token::Ident("b"),
+                Token::LowerEqual,
+                Token::Number(2),
+                Token::And,
+                Token::Ident("c"),
+                Token::Greater,
+                Token::Number(3),
+                Token::Eof,
+            ]
+        );
+
+        let err = Tokenizer::new("a >== 1").tokenize().unwrap_err();
+        assert!(err.contains("\">==\""), "{err}");
+        assert!(Tokenizer::new("a <== 1").tokenize().is_err());
+    }
+
+    #[test]
+    fn test_not_equal() {
+        let tokens = Tokenizer::new("a != 1 OR b <> 2").tokenize().unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Ident("a"),
+                Token::NotEqual,
+                Token::Number(1),
+                Token::Or,
+                Token::Ident("b"),
+                Token::NotEqual,
+                Token::Number(2),
+                Token::Eof,
+            ]
+        );
+        assert!(Tokenizer::new("a ! 1").tokenize().is_err());
+    }
+
+    #[test]
+    fn test_unicode_identifiers() {
+        let tokens = Tokenizer::new("SELECT naïve, 名前, t.col٣ FROM ſelect WHERE é = 'ü'")
+            .tokenize()
+            .unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Select,
+                Token::Ident("naïve"),
+                Token::Comma,
+                Token::Ident("名前"),
+                Token::Comma,
+                Token::Ident("t.col٣"),
+                Token::From,
+                Token::Ident("ſelect"),
+                Token::Where,
+                Token::Ident("é"),
+                Token::Equal,
+                Token::String(Cow::Borrowed("ü")),
+                Token::Eof,
+            ]
+        );
+
+        let err = Tokenizer::new("SELECT € FROM t").tokenize().unwrap_err();
+        assert_eq!(err, "character: '€' is not supported");
+        // a number cannot start with a non-ASCII digit
+        assert!(Tokenizer::new("SELECT ٣").tokenize().is_err());
+    }
+
+    #[test]
+    fn test_tokenize_collect_errors() {
+        let sql = "SELECT a ? b FROM t WHERE c = #";
+        assert!(Tokenizer::new(sql).tokenize().is_err());
+
+        let (tokens, errors) = Tokenizer::new(sql).tokenize_collect_errors();
+        assert_eq!(
+            errors,
+            vec![
+                LexError {
+                    position: 9,
+                    message: "character: '?' is not supported".into(),
+                },
+                LexError {
+                    position: 30,
+                    message: "character: '#' is not supported".into(),
+                },
+            ]
+        );
+        assert_eq!(
+            errors[1].to_string(),
+            "character: '#' is not supported at position 30"
+        );
+        assert_eq!(tokens.len(), 9);
+        assert_eq!(tokens[1..3], [Token::Ident("a"), Token::Ident("b")]);
+        assert_eq!(tokens.last(), Some(&Token::Eof));
+
+        // a faulty literal is skipped as a whole
+        let (tokens, errors) = Tokenizer::new("1.2.3 x 'open").tokenize_collect_errors();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[1].position, 8);
+        assert!(tokens.contains(&Token::Ident("x")));
+
+        let (tokens, errors) = Tokenizer::new("SELECT 1").tokenize_collect_errors();
+        assert!(errors.is_empty());
+        assert_eq!(tokens, Tokenizer::new("SELECT 1").tokenize().unwrap());
+    }
 }
@@ -1,4 +1,5 @@
 use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
 use std::sync::Arc;
 
 use allocative::Allocative;
@@ -15,6 +16,8 @@ pub enum Value {
     Null,
     /// A 64-bit signed integer value.
     Int(i64),
+    /// A 64-bit unsigned integer value.
+    UInt(u64),
     /// A 64-bit floating-point value.
     Float(f64),
     /// A UTF-8 string value, wrapped in an [Arc] for efficient,
@@ -22,6 +25,10 @@ pub enum Value {
     Text(Arc<str>),
     /// A boolean value.
     Bool(bool),
+    /// A point in time, in microseconds since the Unix epoch (UTC).
+    Timestamp(i64),
+    /// Raw binary data, shared through an [Arc] like [Value::Text].
+    Blob(Arc<[u8]>),
 }
 
 impl PartialEq for Value {
@@ -41,17 +48,29 @@ impl PartialOrd for Value {
 /// Implements a total ordering for [Value].
 ///
 /// The ordering between different types is arbitrary but deterministic:
-/// Null < Int < Float < Text < Bool.
+/// Null < Int < UInt < Float < Text < Bool < Timestamp < Blob.
 ///
-/// For Float values, NaN is considered smaller than any other non-NaN number.
+/// Like `Int` and `Float`, `Int` and `UInt` values are never equal: SQL comparisons
+/// mixing them are handled by the engine, which compares them numerically.
+///
+/// Blobs are ordered lexicographically by bytes.
+///
+/// For Float values, NaN is considered smaller than any other number, and equal to
+/// itself so that the order stays total.
+///
+/// [Value::sort_cmp] is the order used by `ORDER BY`, which compares numbers of
+/// different types by value.
 impl Ord for Value {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
         match (self, other) {
             (Value::Null, Value::Null) => Ordering::Equal,
             (Value::Int(a), Value::Int(b)) => a.cmp(b),
-            (Value::Float(a), Value::Float(b)) => a.partial_cmp(b).unwrap_or(Ordering::Less),
+            (Value::UInt(a), Value::UInt(b)) => a.cmp(b),
+            (Value::Float(a), Value::Float(b)) => cmp_floats(*a, *b),
             (Value::Text(a), Value::Text(b)) => a.cmp(b),
             (Value::Bool(a), Value::Bool(b)) => a.cmp(b),
+            (Value::Timestamp(a), Value::Timestamp(b)) => a.cmp(b),
+            (Value::Blob(a), Value::Blob(b)) => a.cmp(b),
 
             // ordre arbitraire entre types différents
             (Value::Null, _) => Ordering::Less,
@@ -60,11 +79,75 @@ impl Ord for Value {
             (Value::Int(_), _) => Ordering::Less,
             (_, Value::Int(_)) => Ordering::Greater,
 
+            (Value::UInt(_), _) => Ordering::Less,
+            (_, Value::UInt(_)) => Ordering::Greater,
+
             (Value::Float(_), _) => Ordering::Less,
             (_, Value::Float(_)) => Ordering::Greater,
 
             (Value::Text(_), _) => Ordering::Less,
             (_, Value::Text(_)) => Ordering::Greater,
+
+            (Value::Bool(_), _) => Ordering::Less,
+            (_, Value::Bool(_)) => Ordering::Greater,
+
+            (Value::Timestamp(_), _) => Ordering::Less,
+            (_, Value::Timestamp(_)) => Ordering::Greater,
+        }
+    }
+}
+
+/// Compares two floats, NaN first.
+fn cmp_floats(a: f64, b: f64) -> Ordering {
+    a.partial_cmp(&b)
+        .unwrap_or_else(|| b.is_nan().cmp(&a.is_nan()))
+}
+
+/// Compares an integer and a float exactly, where converting the integer to a float
+/// could round it. NaN is smaller than any integer.
+fn cmp_int_float(i: i128, f: f64) -> Ordering {
+    if f.is_nan() {
+        return Ordering::Greater;
+    }
+    // every i128 lies in this range, where `f as i128` does not saturate
+    let bound = 2f64.powi(127);
+    if f >= bound {
+        return Ordering::Less;
+    }
+    if f < -bound {
+        return Ordering::Greater;
+    }
+    let whole = f.trunc();
+    i.cmp(&(whole as i128))
+        .then_with(|| 0f64.partial_cmp(&(f - whole)).unwrap_or(Ordering::Equal))
+}
+
+/// Hashes a [Value] consistently with its [Eq] implementation.
+///
+/// The variant is hashed first, so `Int(1)` and `UInt(1)` (which are not equal) hash
+/// differently. `0.0` and `-0.0` compare equal, so they hash the same.
+impl Hash for Value {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            Value::Null => {}
+            Value::Int(v) | Value::Timestamp(v) => v.hash(state),
+            Value::UInt(v) => v.hash(state),
+            Value::Float(v) => {
+                // the values equal to each other must hash the same: 0.0 and -0.0, and
+                // every NaN
+                let v = if *v == 0.0 {
+                    0.0
+                } else if v.is_nan() {
+                    f64::NAN
+                } else {
+                    *v
+                };
+                v.to_bits().hash(state)
+            }
+            Value::Text(v) => v.hash(state),
+            Value::Bool(v) => v.hash(state),
+            Value::Blob(v) => v.hash(state),
         }
     }
 }
@@ -75,6 +158,11 @@ impl Value {
         matches!(self, Self::Null)
     }
 
+    /// Returns `true` if the value is a [Value::Int], a [Value::UInt] or a [Value::Float].
+    pub fn is_numeric(&self) -> bool {
+        matches!(self, Self::Int(_) | Self::UInt(_) | Self::Float(_))
+    }
+
     /// Returns the inner integer value if this is a [Value::Int].
     /// Otherwise, returns `None`.
     pub fn as_int(&self) -> Option<i64> {
@@ -84,6 +172,15 @@ impl Value {
         }
     }
 
+    /// Returns the inner unsigned integer value if this is a [Value::UInt].
+    /// Otherwise, returns `None`.
+    pub fn as_uint(&self) -> Option<u64> {
+        match self {
+            Self::UInt(u) => Some(*u),
+            _ => None,
+        }
+    }
+
     /// Returns the inner float value if this is a [Value::Float].
     /// Otherwise, returns `None`.
     pub fn as_float(&self) -> Option<f64> {
@@ -111,6 +208,124 @@ impl Value {
         }
     }
 
+    /// Returns the inner bytes if this is a [Value::Blob].
+    /// Otherwise, returns `None`.
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Self::Blob(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    /// Returns the inner microseconds since the Unix epoch if this is a [Value::Timestamp].
+    /// Otherwise, returns `None`.
+    pub fn as_timestamp(&self) -> Option<i64> {
+        match self {
+            Self::Timestamp(t) => Some(*t),
+            _ => None,
+        }
+    }
+
+    /// Compares two values for `ORDER BY`.
+    ///
+    /// `Int`, `UInt` and `Float` values are compared by their numeric value, so a
+    /// computed key mixing them sorts as expected (`Int(2) < Float(2.5) < UInt(3)`); two
+    /// numbers of the same value are equal. Every other pair follows the [Ord] of
+    /// [Value]: text in byte order, `false` before `true`, NaN before any number, and
+    /// values of different types by their type rank.
+    ///
+    /// `NULL` is the smallest value, so it comes first in ascending order and last in
+    /// descending order. `NULLS FIRST`/`NULLS LAST` place it before this comparison runs.
+    pub fn sort_cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Self::Int(a), Self::UInt(b)) => i128::from(*a).cmp(&i128::from(*b)),
+            (Self::UInt(a), Self::Int(b)) => i128::from(*a).cmp(&i128::from(*b)),
+            (Self::Int(i), Self::Float(f)) => cmp_int_float(i128::from(*i), *f),
+            (Self::UInt(u), Self::Float(f)) => cmp_int_float(i128::from(*u), *f),
+            (Self::Float(f), Self::Int(i)) => cmp_int_float(i128::from(*i), *f).reverse(),
+            (Self::Float(f), Self::UInt(u)) => cmp_int_float(i128::from(*u), *f).reverse(),
+            (a, b) => a.cmp(b),
+        }
+    }
+
+    /// Converts the value to `target` when it can be compared to values of that type
+    /// without loss of meaning.
+    ///
+    /// `NULL` and values already of type `target` are returned unchanged, an `Int` is
+    /// widened to a `Float`, and a non-negative `Int` becomes a `UInt`. Returns `None` for
+    /// any other combination, e.g. an `Int` against a `Text` column.
+    pub fn coerce_to(&self, target: DataType) -> Option<Value> {
+        match (self, target) {
+            (Self::Int(i), DataType::Float) => Some(Self::Float(*i as f64)),
+            (Self::Int(i), DataType::UInt) => u64::try_from(*i).ok().map(Self::UInt),
+            (v, t) if v.data_type().is_none_or(|ty| ty == t) => Some(v.clone()),
+            _ => None,
+        }
+    }
+
+    /// Formats the value as a CSV field, the inverse of [Value::from_csv_field].
+    ///
+    /// `NULL` is the empty field. Numbers use Rust's shortest round-trip formatting,
+    /// without thousands separators, so they parse back to the exact same value.
+    /// Text is quoted (with `"` doubled) when it is empty or contains a separator,
+    /// a quote or a line break.
+    pub fn to_csv_field(&self) -> String {
+        match self {
+            Self::Null => String::new(),
+            Self::Int(i) => i.to_string(),
+            Self::UInt(u) => u.to_string(),
+            Self::Float(f) => f.to_string(),
+            Self::Text(s) => {
+                if s.is_empty() || s.contains([',', '"', '\n', '\r']) {
+                    format!("\"{}\"", s.replace('"', "\"\""))
+                } else {
+                    s.to_string()
+                }
+            }
+            Self::Bool(b) => b.to_string(),
+            Self::Timestamp(t) => t.to_string(),
+            // like text, an empty blob is quoted to tell it apart from NULL
+            Self::Blob(b) if b.is_empty() => "\"\"".to_string(),
+            Self::Blob(b) => to_hex(b),
+        }
+    }
+
+    /// Parses a CSV field produced by [Value::to_csv_field] as a value of type `ty`.
+    ///
+    /// An empty field is `NULL`; a quoted empty field (`""`) is an empty text or blob.
+    /// Blobs are written as hexadecimal digits.
+    ///
+    /// # Errors
+    /// Returns an error if the field is not a valid representation of `ty`.
+    pub fn from_csv_field(field: &str, ty: DataType) -> Result<Value, String> {
+        if field.is_empty() {
+            return Ok(Self::Null);
+        }
+        let invalid = || format!("Invalid {:?} CSV field {:?}", ty, field);
+        match ty {
+            DataType::Int => field.parse().map(Self::Int).map_err(|_| invalid()),
+            DataType::UInt => field.parse().map(Self::UInt).map_err(|_| invalid()),
+            DataType::Float => field.parse().map(Self::Float).map_err(|_| invalid()),
+            DataType::Timestamp => field.parse().map(Self::Timestamp).map_err(|_| invalid()),
+            DataType::Bool => match field.to_ascii_lowercase().as_str() {
+                "true" => Ok(Self::Bool(true)),
+                "false" => Ok(Self::Bool(false)),
+                _ => Err(invalid()),
+            },
+            DataType::Bytes if field == "\"\"" => Ok(Self::Blob(Arc::from([]))),
+            DataType::Bytes => from_hex(field)
+                .map(|bytes| Self::Blob(bytes.into()))
+                .ok_or_else(invalid),
+            DataType::Text => {
+                let text = match field.strip_prefix('"').and_then(|f| f.strip_suffix('"')) {
+                    Some(quoted) => quoted.replace("\"\"", "\""),
+                    None => field.to_string(),
+                };
+                Ok(Self::Text(text.into()))
+            }
+        }
+    }
+
     /// Returns the logical [DataType] corresponding to this value.
     ///
     /// Returns `None` if the value is [Value::Null], because in this database
@@ -119,13 +334,60 @@ impl Value {
         match self {
             Self::Null => None,
             Self::Int(_) => Some(DataType::Int),
+            Self::UInt(_) => Some(DataType::UInt),
             Self::Float(_) => Some(DataType::Float),
             Self::Text(_) => Some(DataType::Text),
             Self::Bool(_) => Some(DataType::Bool),
+            Self::Timestamp(_) => Some(DataType::Timestamp),
+            Self::Blob(_) => Some(DataType::Bytes),
         }
     }
 }
 
+/// Formats bytes as lowercase hexadecimal digits, two per byte.
+pub(crate) fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Decodes pairs of hexadecimal digits (either case), the inverse of [to_hex].
+///
+/// Returns `None` on an odd number of digits or a non-hex character.
+pub(crate) fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    hex.as_bytes()
+        .chunks(2)
+        .map(|pair| {
+            std::str::from_utf8(pair)
+                .ok()
+                .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+        })
+        .collect()
+}
+
+/// Returns the smaller of two values according to the [Value] ordering.
+///
+/// Like SQL `MIN`, a `NULL` operand is ignored in favor of the other one; the result
+/// is only `NULL` when both operands are.
+pub fn min(a: &Value, b: &Value) -> Value {
+    match (a, b) {
+        (Value::Null, other) | (other, Value::Null) => other.clone(),
+        _ => std::cmp::min(a, b).clone(),
+    }
+}
+
+/// Returns the greater of two values according to the [Value] ordering.
+///
+/// Like SQL `MAX`, a `NULL` operand is ignored in favor of the other one; the result
+/// is only `NULL` when both operands are.
+pub fn max(a: &Value, b: &Value) -> Value {
+    match (a, b) {
+        (Value::Null, other) | (other, Value::Null) => other.clone(),
+        _ => std::cmp::max(a, b).clone(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -143,6 +405,19 @@ mod tests {
         assert!(!Value::Bool(true).is_null());
     }
 
+    // ─────────────────────────────────────────────────────────────
+    // Test 1b : is_numeric
+    // ─────────────────────────────────────────────────────────────
+    #[test]
+    fn test_is_numeric() {
+        assert!(Value::Int(1).is_numeric());
+        assert!(Value::Float(1.0).is_numeric());
+        assert!(!Value::Null.is_numeric());
+        assert!(!Value::Text("1".into()).is_numeric());
+        assert!(!Value::Bool(true).is_numeric());
+        assert!(!Value::Timestamp(1).is_numeric());
+    }
+
     // ─────────────────────────────────────────────────────────────
     // Test 2 : as_int
     // ─────────────────────────────────────────────────────────────
@@ -160,7 +435,7 @@ mod tests {
     // ─────────────────────────────────────────────────────────────
     #[test]
     fn test_as_float() {
-        assert_eq!(Value::Float(3.14).as_float(), Some(3.14));
+        assert_eq!(Value::Float(2.5).as_float(), Some(2.5));
         assert_eq!(Value::Null.as_float(), None);
         assert_eq!(Value::Int(1).as_float(), None);
         assert_eq!(Value::Text("3.14".into()).as_float(), None);
@@ -238,18 +513,24 @@ mod tests {
         let values = vec![
             Value::Null,
             Value::Int(42),
-            Value::Float(3.14),
+            Value::UInt(u64::MAX),
+            Value::Float(2.5),
             Value::Text("hello".into()),
             Value::Bool(true),
+            Value::Timestamp(1_700_000_000_000_000),
+            Value::Blob(Arc::from([0xde, 0xad])),
         ];
 
         for v in values {
             match v.clone() {
                 Value::Null => assert!(v.is_null()),
                 Value::Int(i) => assert_eq!(v.as_int(), Some(i)),
+                Value::UInt(u) => assert_eq!(v.as_uint(), Some(u)),
                 Value::Float(f) => assert_eq!(v.as_float(), Some(f)),
                 Value::Text(s) => assert_eq!(v.as_str(), Some(s.as_ref())),
                 Value::Bool(b) => assert_eq!(v.as_bool(), Some(b)),
+                Value::Timestamp(t) => assert_eq!(v.as_timestamp(), Some(t)),
+                Value::Blob(b) => assert_eq!(v.as_bytes(), Some(b.as_ref())),
             }
         }
     }
@@ -260,8 +541,190 @@ mod tests {
     #[test]
     fn test_type_hierarchy() {
         assert!(Value::Null < Value::Int(0));
-        assert!(Value::Int(100) < Value::Float(0.0));
+        assert!(Value::Int(100) < Value::UInt(0));
+        assert!(Value::UInt(100) < Value::Float(0.0));
+        assert_ne!(Value::Int(1), Value::UInt(1));
         assert!(Value::Float(100.0) < Value::Text("abc".into()));
         assert!(Value::Text("zzz".into()) < Value::Bool(false));
+        assert!(Value::Bool(true) < Value::Timestamp(0));
+        assert!(Value::Timestamp(i64::MAX) < Value::Blob(Arc::from([])));
+        // blobs compare byte by byte, a prefix sorts first
+        assert!(Value::Blob(Arc::from([0x01, 0xff])) < Value::Blob(Arc::from([0x02])));
+        assert!(Value::Blob(Arc::from([0x01])) < Value::Blob(Arc::from([0x01, 0x00])));
+    }
+
+    // ─────────────────────────────────────────────────────────────
+    // Test 11 : CSV round-trip
+    // ─────────────────────────────────────────────────────────────
+    #[test]
+    fn test_csv_field_round_trip() {
+        let values = vec![
+            (Value::Int(-1_234_567), DataType::Int),
+            (Value::Int(i64::MAX), DataType::Int),
+            (Value::UInt(u64::MAX), DataType::UInt),
+            (Value::Float(0.1), DataType::Float),
+            (Value::Float(-1.5e300), DataType::Float),
+            (Value::Text("plain".into()), DataType::Text),
+            (Value::Text("a, \"quoted\"\nline".into()), DataType::Text),
+            (Value::Text("".into()), DataType::Text),
+            (Value::Bool(false), DataType::Bool),
+            (Value::Timestamp(1_700_000_000_123_456), DataType::Timestamp),
+            (
+                Value::Blob(Arc::from([0xde, 0xad, 0xbe, 0xef])),
+                DataType::Bytes,
+            ),
+            (Value::Blob(Arc::from([])), DataType::Bytes),
+        ];
+
+        for (value, ty) in values {
+            let field = value.to_csv_field();
+            let parsed = Value::from_csv_field(&field, ty).unwrap();
+            assert_eq!(parsed, value, "field {field:?}");
+            assert_eq!(parsed.data_type(), Some(ty));
+        }
+
+        assert_eq!(Value::Int(1_000_000).to_csv_field(), "1000000");
+        assert_eq!(Value::Null.to_csv_field(), "");
+        assert_eq!(Value::from_csv_field("", DataType::Int), Ok(Value::Null));
+        assert!(Value::from_csv_field("1,000", DataType::Int).is_err());
+        assert!(Value::from_csv_field("yes", DataType::Bool).is_err());
+        assert_eq!(Value::Blob(Arc::from([0x0a, 0xff])).to_csv_field(), "0aff");
+        assert!(Value::from_csv_field("abc", DataType::Bytes).is_err());
+    }
+
+    // ─────────────────────────────────────────────────────────────
+    // Test 12 : coerce_to
+    // ─────────────────────────────────────────────────────────────
+    #[test]
+    fn test_coerce_to() {
+        assert_eq!(
+            Value::Int(18).coerce_to(DataType::Float),
+            Some(Value::Float(18.0))
+        );
+        assert_eq!(
+            Value::Int(18).coerce_to(DataType::Int),
+            Some(Value::Int(18))
+        );
+        assert_eq!(Value::Null.coerce_to(DataType::Text), Some(Value::Null));
+        assert_eq!(Value::Int(5).coerce_to(DataType::Text), None);
+        assert_eq!(Value::Float(1.5).coerce_to(DataType::Int), None);
+        assert_eq!(Value::Bool(true).coerce_to(DataType::Timestamp), None);
+        assert_eq!(
+            Value::Int(7).coerce_to(DataType::UInt),
+            Some(Value::UInt(7))
+        );
+        assert_eq!(Value::Int(-1).coerce_to(DataType::UInt), None);
+    }
+
+    // ─────────────────────────────────────────────────────────────
+    // Test 13 : min / max
+    // ─────────────────────────────────────────────────────────────
+    #[test]
+    fn test_min_max() {
+        assert_eq!(min(&Value::Int(3), &Value::Int(-7)), Value::Int(-7));
+        assert_eq!(max(&Value::Int(3), &Value::Int(-7)), Value::Int(3));
+        assert_eq!(
+            min(&Value::Float(2.5), &Value::Float(0.5)),
+            Value::Float(0.5)
+        );
+        assert_eq!(
+            max(&Value::Float(2.5), &Value::Float(0.5)),
+            Value::Float(2.5)
+        );
+
+        // across types, the documented type order applies: Int < Float
+        assert_eq!(min(&Value::Int(100), &Value::Float(0.0)), Value::Int(100));
+        assert_eq!(max(&Value::Int(100), &Value::Float(0.0)), Value::Float(0.0));
+
+        // NULL is ignored in favor of the other operand
+        assert_eq!(min(&Value::Null, &Value::Int(1)), Value::Int(1));
+        assert_eq!(min(&Value::Float(1.5), &Value::Null), Value::Float(1.5));
+        assert_eq!(max(&Value::Null, &Value::Int(1)), Value::Int(1));
+        assert_eq!(max(&Value::Float(1.5), &Value::Null), Value::Float(1.5));
+        assert_eq!(min(&Value::Null, &Value::Null), Value::Null);
+        assert_eq!(max(&Value::Null, &Value::Null), Value::Null);
+    }
+
+    // ─────────────────────────────────────────────────────────────
+    // Test 13b : sort order
+    // ─────────────────────────────────────────────────────────────
+    #[test]
+    fn test_sort_cmp() {
+        let mut values = vec![
+            Value::UInt(3),
+            Value::Float(f64::NAN),
+            Value::Text("b".into()),
+            Value::Null,
+            Value::Float(2.5),
+            Value::Bool(true),
+            Value::Int(-1),
+            Value::Text("a".into()),
+            Value::Bool(false),
+            Value::Int(2),
+        ];
+        values.sort_by(Value::sort_cmp);
+        assert_eq!(
+            values,
+            vec![
+                Value::Null,
+                Value::Float(f64::NAN),
+                Value::Int(-1),
+                Value::Int(2),
+                Value::Float(2.5),
+                Value::UInt(3),
+                Value::Text("a".into()),
+                Value::Text("b".into()),
+                Value::Bool(false),
+                Value::Bool(true),
+            ]
+        );
+
+        // numbers are compared exactly, without rounding the integer to a float
+        let big = 1i64 << 53;
+        assert_eq!(
+            Value::Int(big + 1).sort_cmp(&Value::Float(big as f64)),
+            Ordering::Greater
+        );
+        assert_eq!(Value::Float(-0.5).sort_cmp(&Value::Int(0)), Ordering::Less);
+        assert_eq!(
+            Value::UInt(u64::MAX).sort_cmp(&Value::Int(-1)),
+            Ordering::Greater
+        );
+        assert_eq!(Value::Float(2.0).sort_cmp(&Value::UInt(2)), Ordering::Equal);
+
+        // NaN is ordered consistently, and equal to itself
+        assert_eq!(Value::Float(f64::NAN), Value::Float(f64::NAN));
+        assert_eq!(
+            Value::Float(f64::NAN).cmp(&Value::Float(1.0)),
+            Ordering::Less
+        );
+        assert_eq!(
+            Value::Float(1.0).cmp(&Value::Float(f64::NAN)),
+            Ordering::Greater
+        );
+    }
+
+    // ─────────────────────────────────────────────────────────────
+    // Test 14 : hash agrees with Eq
+    // ─────────────────────────────────────────────────────────────
+    #[test]
+    fn test_hash_consistent_with_eq() {
+        use std::collections::hash_map::DefaultHasher;
+        let hash = |v: &Value| {
+            let mut hasher = DefaultHasher::new();
+            v.hash(&mut hasher);
+            hasher.finish()
+        };
+        assert_eq!(hash(&Value::Float(0.0)), hash(&Value::Float(-0.0)));
+        assert_eq!(
+            hash(&Value::Float(f64::NAN)),
+            hash(&Value::Float(-f64::NAN))
+        );
+        assert_eq!(
+            hash(&Value::Text("a".into())),
+            hash(&Value::Text("a".into()))
+        );
+        assert_ne!(hash(&Value::Int(1)), hash(&Value::UInt(1)));
+        assert_ne!(hash(&Value::Int(1)), hash(&Value::Timestamp(1)));
     }
 }